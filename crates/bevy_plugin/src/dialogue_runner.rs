@@ -1,6 +1,6 @@
 pub use self::events::{
     DialogueCompleteEvent, DialogueStartEvent, ExecuteCommandEvent, LineHintsEvent,
-    NodeCompleteEvent, NodeStartEvent, PresentLineEvent, PresentOptionsEvent,
+    NodeCompleteEvent, NodeStartEvent, PresentLineEvent, PresentOptionsEvent, VariableChangedEvent,
 };
 pub use self::{
     builder::DialogueRunnerBuilder,
@@ -51,6 +51,7 @@ pub struct DialogueRunner {
     localizations: Option<Localizations>,
     pub(crate) is_running: bool,
     run_selected_options_as_lines: bool,
+    skip_seen_lines: bool,
     pub(crate) just_started: bool,
     pub(crate) popped_line_hints: Option<Vec<LineId>>,
     pub(crate) unsent_events: Vec<DialogueEvent>,
@@ -125,6 +126,21 @@ impl DialogueRunner {
         self.run_selected_options_as_lines
     }
 
+    /// If set, lines that were already presented before, i.e. those with [`LocalizedLine::is_seen`], are skipped:
+    /// They still emit a [`PresentLineEvent`], but the dialogue runner continues right away in the next update without waiting for
+    /// [`DialogueRunner::continue_in_next_update`]. Skipping stops, and this is reset to `false`, as soon as an unseen line or options are presented.
+    /// Defaults to `false`.
+    pub fn skip_seen_lines(&mut self, skip_seen_lines: bool) -> &mut Self {
+        self.skip_seen_lines = skip_seen_lines;
+        self
+    }
+
+    /// Returns whether the dialogue runner is currently skipping seen lines, see [`DialogueRunner::skip_seen_lines`].
+    #[must_use]
+    pub fn is_skipping_seen_lines(&self) -> bool {
+        self.skip_seen_lines
+    }
+
     /// Stops the execution of the dialogue. Any pending dialogue events will still be sent in the next update, including a [`DialogueCompleteEvent`].
     /// After this, [`DialogueRunner::start_node`] must be called before the dialogue can be advanced again.
     pub fn stop(&mut self) -> &mut Self {
@@ -200,6 +216,25 @@ impl DialogueRunner {
         self.dialogue.variable_storage_mut()
     }
 
+    /// Returns whether the line with the given ID was already presented, by this or an earlier run of the dialogue.
+    #[must_use]
+    pub fn seen(&self, line_id: &LineId) -> bool {
+        self.dialogue.seen(line_id)
+    }
+
+    /// Returns the IDs of all lines presented so far. Persist these separately from the variables, e.g. per player profile,
+    /// and restore them via [`DialogueRunner::seen_lines_mut`] so that [`DialogueRunner::skip_seen_lines`] works across sessions.
+    #[must_use]
+    pub fn seen_lines(&self) -> &SeenLines {
+        self.dialogue.seen_lines()
+    }
+
+    /// Mutably returns the IDs of all lines presented so far, see [`DialogueRunner::seen_lines`].
+    #[must_use]
+    pub fn seen_lines_mut(&mut self) -> &mut SeenLines {
+        self.dialogue.seen_lines_mut()
+    }
+
     /// Returns whether both the text and asset providers have loaded all their lines.
     #[must_use]
     pub fn update_line_availability(
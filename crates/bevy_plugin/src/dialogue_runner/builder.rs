@@ -101,6 +101,7 @@ impl DialogueRunnerBuilder {
             text_provider,
             popped_line_hints,
             run_selected_options_as_lines: false,
+            skip_seen_lines: false,
             asset_providers: self.asset_providers,
             commands: self.commands,
             is_running: default(),
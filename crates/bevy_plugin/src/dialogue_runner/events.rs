@@ -10,7 +10,8 @@ pub(crate) fn dialogue_runner_events_plugin(app: &mut App) {
         .add_event::<NodeStartEvent>()
         .add_event::<LineHintsEvent>()
         .add_event::<DialogueCompleteEvent>()
-        .add_event::<DialogueStartEvent>();
+        .add_event::<DialogueStartEvent>()
+        .add_event::<VariableChangedEvent>();
 }
 
 /// An event that is fired after a dialogue advances and wishes to present a line to the user.
@@ -81,6 +82,22 @@ pub struct LineHintsEvent {
     pub source: Entity,
 }
 
+/// An event that is fired when the dialogue changed a variable, e.g. through `<<set $gold to 5>>`.
+/// Only fired if the new value differs from the old one.
+/// Changes made through [`DialogueRunner::variable_storage_mut`] are not reported.
+/// Handling this event is **optional** for dialogue views.
+#[derive(Debug, Clone, PartialEq, Event)]
+pub struct VariableChangedEvent {
+    /// The name of the variable, including the leading `$`.
+    pub name: String,
+    /// The value before the change, or `None` if the variable had no value yet.
+    pub old: Option<YarnValue>,
+    /// The value after the change.
+    pub new: YarnValue,
+    /// The [`DialogueRunner`] whose dialogue changed the variable.
+    pub source: Entity,
+}
+
 /// An event that is fired when a dialogue has been started via [`DialogueRunner::start_node`]/
 /// Handling this event is **optional** for dialogue views.
 #[derive(Debug, Clone, PartialEq, Event)]
@@ -31,6 +31,12 @@ impl<'a> InnerDialogue<'a> {
         self.0.analyse(context);
         self
     }
+
+    /// Proxy for [`Dialogue::languages_with_plural_data`].
+    #[must_use]
+    pub fn languages_with_plural_data(&self) -> Vec<Language> {
+        self.0.languages_with_plural_data()
+    }
 }
 
 impl<'a> InnerDialogueMut<'a> {
@@ -57,4 +63,26 @@ impl<'a> InnerDialogueMut<'a> {
         self.0.analyse(context);
         self
     }
+
+    /// Proxy for [`Dialogue::languages_with_plural_data`].
+    #[must_use]
+    pub fn languages_with_plural_data(&self) -> Vec<Language> {
+        self.0.languages_with_plural_data()
+    }
+
+    /// Proxy for [`Dialogue::register_markup_processor`].
+    pub fn register_markup_processor(
+        &mut self,
+        name: impl Into<String>,
+        processor: Box<dyn AttributeMarkerProcessor>,
+    ) -> &mut Self {
+        self.0.register_markup_processor(name, processor);
+        self
+    }
+
+    /// Proxy for [`Dialogue::add_seen_line_function`].
+    pub fn add_seen_line_function(&mut self) -> &mut Self {
+        self.0.add_seen_line_function();
+        self
+    }
 }
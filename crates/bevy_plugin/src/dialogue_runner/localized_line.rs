@@ -1,7 +1,9 @@
 use crate::line_provider::LineAssets;
 use crate::prelude::*;
 use bevy::prelude::*;
-use yarnspinner::runtime::{CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY};
+use yarnspinner::runtime::{
+    markup_tree, text_runs, CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY,
+};
 
 pub(crate) fn localized_line_plugin(_app: &mut App) {}
 
@@ -21,6 +23,8 @@ pub struct LocalizedLine {
     pub metadata: Vec<String>,
     /// The assets associated with this line, provided by [`AssetProvider`]s that were added with [`DialogueRunnerBuilder::add_asset_provider`].
     pub assets: LineAssets,
+    /// Whether the line had already been presented before, see [`DialogueRunner::seen`].
+    pub is_seen: bool,
 }
 impl LocalizedLine {
     // Documentation taken from `YarnLine`
@@ -48,6 +52,7 @@ impl LocalizedLine {
     /// #    }],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
+    /// #    is_seen: false,
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!(Some("Alice"), line.character_name());
@@ -63,6 +68,7 @@ impl LocalizedLine {
     /// #    attributes: vec![],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
+    /// #    is_seen: false,
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert!(line.character_name().is_none());
@@ -101,6 +107,7 @@ impl LocalizedLine {
     /// #    }],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
+    /// #    is_seen: false,
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!("Hello! How are you today?", &line.text_without_character_name());
@@ -116,6 +123,7 @@ impl LocalizedLine {
     /// #    attributes: vec![],
     /// #    metadata: vec![],
     /// #    assets: Default::default(),
+    /// #    is_seen: false,
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert_eq!("Great, thanks", &line.text_without_character_name());
@@ -127,6 +135,17 @@ impl LocalizedLine {
         }
     }
 
+    /// Splits [`LocalizedLine::text`] into runs of text in which the same attributes are active, e.g. to map them onto styled text sections.
+    /// Attributes covering no text, like the self-closing `[pause/]`, get a run with empty text at their position.
+    pub fn text_runs(&self) -> Vec<TextRun<'_>> {
+        text_runs(&self.text, &self.attributes)
+    }
+
+    /// Nests [`LocalizedLine::text`] and [`LocalizedLine::attributes`] into a tree of [`MarkupNode`]s, like the elements of an HTML document.
+    pub fn markup_tree(&self) -> Vec<MarkupNode<'_>> {
+        markup_tree(&self.text, &self.attributes)
+    }
+
     // Documentation taken from `YarnLine`
     /// Returns the substring of [`YarnLine::text`] covered by the passed `attribute`s [`MarkupAttribute::position`] and [`MarkupAttribute::length`] fields.
     pub fn text_for_attribute(&self, attribute: &MarkupAttribute) -> &str {
@@ -181,6 +200,7 @@ impl From<LocalizedLine> for YarnLine {
             id: line.id,
             text: line.text,
             attributes: line.attributes,
+            is_seen: line.is_seen,
         }
     }
 }
@@ -197,6 +217,7 @@ impl LocalizedLine {
             attributes: line.attributes,
             metadata,
             assets,
+            is_seen: line.is_seen,
         }
     }
 }
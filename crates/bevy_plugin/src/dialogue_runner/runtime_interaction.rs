@@ -38,6 +38,7 @@ fn continue_runtime(
     mut line_hints_events: EventWriter<LineHintsEvent>,
     mut dialogue_complete_events: EventWriter<DialogueCompleteEvent>,
     mut dialogue_start_events: EventWriter<DialogueStartEvent>,
+    mut variable_changed_events: EventWriter<VariableChangedEvent>,
     mut last_options: Local<HashMap<Entity, Vec<DialogueOption>>>,
     loaded_untyped_assets: Res<Assets<LoadedUntypedAsset>>,
     project: Res<YarnProject>,
@@ -97,6 +98,13 @@ fn continue_runtime(
         for event in events {
             match event {
                 DialogueEvent::Line(line) => {
+                    if dialogue_runner.skip_seen_lines {
+                        if line.is_seen && dialogue_runner.is_running {
+                            dialogue_runner.continue_in_next_update();
+                        } else {
+                            dialogue_runner.skip_seen_lines = false;
+                        }
+                    }
                     let assets = dialogue_runner.get_assets(&line);
                     let metadata = project.line_metadata(&line.id).unwrap_or_default().to_vec();
                     present_line_events.send(PresentLineEvent {
@@ -105,6 +113,7 @@ fn continue_runtime(
                     });
                 }
                 DialogueEvent::Options(options) => {
+                    dialogue_runner.skip_seen_lines = false;
                     let options: Vec<DialogueOption> = options
                         .into_iter()
                         .map(|option| {
@@ -132,6 +141,14 @@ fn continue_runtime(
                 DialogueEvent::LineHints(line_ids) => {
                     line_hints_events.send(LineHintsEvent { line_ids, source });
                 }
+                DialogueEvent::VariableChanged { name, old, new } => {
+                    variable_changed_events.send(VariableChangedEvent {
+                        name,
+                        old,
+                        new,
+                        source,
+                    });
+                }
                 DialogueEvent::DialogueComplete => {
                     if !is_sending_missed_events {
                         dialogue_runner.is_running = false;
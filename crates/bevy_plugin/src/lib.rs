@@ -124,6 +124,7 @@ pub mod events {
     pub use crate::dialogue_runner::{
         DialogueCompleteEvent, DialogueStartEvent, ExecuteCommandEvent, LineHintsEvent,
         NodeCompleteEvent, NodeStartEvent, PresentLineEvent, PresentOptionsEvent,
+        VariableChangedEvent,
     };
 }
 
@@ -146,10 +147,12 @@ pub mod prelude {
     pub(crate) use crate::{localization::StringsFile, utils::*};
     pub(crate) use anyhow::{Context, Error, Result};
     pub(crate) use serde::{Deserialize, Serialize};
+    pub use yarnspinner::localization::{PseudoLocalization, PSEUDO_LOCALIZATION_LANGUAGE};
     pub(crate) use yarnspinner::prelude::*;
     pub use yarnspinner::prelude::{
-        IntoYarnValueFromNonYarnValue, Language, LineId, MarkupAttribute, MarkupValue, OptionId,
-        VariableStorage, YarnFn, YarnLibrary, YarnValue,
+        AttributeMarkerProcessor, IntoYarnValueFromNonYarnValue, Language, LanguageError, LineId,
+        MarkupAttribute, MarkupAttributeMarker, MarkupValue, OptionId, SeenLines, VariableStorage,
+        YarnFn, YarnLibrary, YarnValue,
     };
     pub(crate) type SystemResult = Result<()>;
 }
@@ -70,6 +70,10 @@ impl UnderlyingTextProvider for SharedTextProvider {
         self.0.read().unwrap().are_lines_available()
     }
 
+    fn available_languages(&self) -> Vec<Language> {
+        self.0.read().unwrap().available_languages()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -15,6 +15,7 @@ pub(crate) fn strings_file_text_provider_plugin(_app: &mut App) {}
 /// this will send the lines as they appear in the Yarn file. If [`DialogueRunner::set_language`] or [`DialogueRunner::set_text_language`] were used to
 /// set the language to a language supported by a translation in the [`Localizations`], this loads the strings file for that translation from the disk at the
 /// specified path. If this fails, the base language is used as a fallback.
+/// If the translation is a pseudo-localization (see [`Localization::with_pseudo_localization`]), its text is generated from the base language instead.
 #[derive(Debug, Clone)]
 pub struct StringsFileTextProvider {
     asset_server: SkipDebug<AssetServer>,
@@ -23,6 +24,7 @@ pub struct StringsFileTextProvider {
     base_string_table: HashMap<LineId, StringInfo>,
     strings_file_handle: Option<Handle<StringsFile>>,
     translation_string_table: Option<HashMap<LineId, String>>,
+    pseudo_localization: Option<PseudoLocalization>,
     event_reader: Arc<RwLock<ManualEventReader<AssetEvent<StringsFile>>>>,
 }
 
@@ -80,6 +82,11 @@ impl UnderlyingTextProvider for StringsFileTextProvider {
                 .join(", ");
             panic!("Set language to {language}, but that language is not supported. Expected one of {languages}.");
         };
+        if let Some(pseudo_localization) = localization.pseudo_localization.clone() {
+            self.pseudo_localization.replace(pseudo_localization);
+            self.update_pseudo_localization();
+            return;
+        }
         let path = localization.strings_file.as_path();
         let asset_path = path.to_string_lossy().replace('\\', "/");
         self.strings_file_handle
@@ -96,6 +103,14 @@ impl UnderlyingTextProvider for StringsFileTextProvider {
         is_base_language || has_fetched_translation()
     }
 
+    fn available_languages(&self) -> Vec<Language> {
+        self.localizations
+            .iter()
+            .flat_map(Localizations::supported_languages)
+            .cloned()
+            .collect()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -115,6 +130,7 @@ impl StringsFileTextProvider {
             base_string_table: yarn_project.compilation.string_table.clone(),
             strings_file_handle: None,
             translation_string_table: None,
+            pseudo_localization: None,
             event_reader: Default::default(),
         }
     }
@@ -122,6 +138,14 @@ impl StringsFileTextProvider {
         self.language = language.into();
         self.translation_string_table = None;
         self.strings_file_handle = None;
+        self.pseudo_localization = None;
+    }
+
+    fn update_pseudo_localization(&mut self) {
+        if let Some(pseudo_localization) = self.pseudo_localization.as_ref() {
+            let string_table = pseudo_localization.localize_string_table(&self.base_string_table);
+            self.translation_string_table.replace(string_table);
+        }
     }
 
     fn is_base_language(&self) -> bool {
@@ -137,10 +161,12 @@ impl StringsFileTextProvider {
 impl TextProvider for StringsFileTextProvider {
     fn set_base_string_table(&mut self, string_table: HashMap<LineId, StringInfo>) {
         self.base_string_table = string_table;
+        self.update_pseudo_localization();
     }
 
     fn extend_base_string_table(&mut self, string_table: HashMap<LineId, StringInfo>) {
         self.base_string_table.extend(string_table);
+        self.update_pseudo_localization();
     }
 
     fn take_fetched_assets(&mut self, asset: Box<dyn Any>) {
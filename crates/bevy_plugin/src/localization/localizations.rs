@@ -58,6 +58,13 @@ impl Localizations {
         )
     }
 
+    /// Iterates over the translations that are loaded from a strings file, i.e. all translations that are not a pseudo-localization.
+    pub(crate) fn file_based_translations(&self) -> impl Iterator<Item = &Localization> {
+        self.translations
+            .iter()
+            .filter(|localization| localization.pseudo_localization.is_none())
+    }
+
     pub(crate) fn strings_file_path(&self, language: impl Into<Language>) -> Option<&Path> {
         let language = language.into();
         self.translations
@@ -84,6 +91,10 @@ pub struct Localization {
     /// The path to the subdirectory containing the assets for this localization inside the `assets` folder.
     /// Defaults to `dialogue/{language}/`.  So, for the language "de-CH", you'd end up with "assets/dialogue/de-CH/".
     pub assets_sub_folder: PathBuf,
+    /// If set, this localization is a virtual language whose text is generated from the base language instead of being loaded from [`Localization::strings_file`].
+    /// Set with [`Localization::with_pseudo_localization`].
+    #[serde(default)]
+    pub pseudo_localization: Option<PseudoLocalization>,
 }
 
 impl<T> From<T> for Localization
@@ -111,6 +122,7 @@ impl Localization {
             language,
             strings_file,
             assets_sub_folder,
+            pseudo_localization: None,
         }
     }
 
@@ -125,4 +137,24 @@ impl Localization {
         self.assets_sub_folder = assets_sub_folder.into();
         self
     }
+
+    /// Turns this localization into a pseudo-localization, whose text is generated from the base language.
+    /// No strings file is read or generated for it. Since there are no assets for a pseudo-localization either,
+    /// you may want to point [`Localization::with_assets_sub_folder`] to the folder of the base localization.
+    /// ```rust
+    /// # use bevy::prelude::*;
+    /// # use bevy_yarnspinner::prelude::*;
+    /// let localizations = Localizations {
+    ///     base_localization: "en-US".into(),
+    ///     translations: vec![
+    ///         "de-CH".into(),
+    ///         Localization::with_language(PSEUDO_LOCALIZATION_LANGUAGE)
+    ///             .with_pseudo_localization(PseudoLocalization::default()),
+    ///     ],
+    /// };
+    /// ```
+    pub fn with_pseudo_localization(mut self, pseudo_localization: PseudoLocalization) -> Self {
+        self.pseudo_localization = Some(pseudo_localization);
+        self
+    }
 }
@@ -6,7 +6,6 @@ use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
 use bevy::prelude::*;
 use bevy::reflect::TypePath;
 use bevy::utils::HashMap;
-use sha2::{Digest, Sha256};
 use std::fs;
 use std::fs::File;
 use std::path::Path;
@@ -288,11 +287,7 @@ pub(crate) struct Lock(String);
 impl Lock {
     /// Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Unity/blob/462c735766a4c4881cd1ef1f15de28c83b2ba0a8/Editor/Importers/YarnImporter.cs#L149>
     pub(crate) fn compute_from(text: &str) -> Self {
-        const MAX_CHARS: usize = 8;
-        let hash = Sha256::digest(text);
-        let hex = format!("{hash:x}");
-        let lock = hex.chars().take(MAX_CHARS).collect();
-        Self(lock)
+        Self(yarnspinner::localization::compute_lock(text))
     }
 }
 
@@ -37,12 +37,12 @@ fn update_all_strings_files_for_string_table(
     asset_root: Res<AssetRoot>,
 ) -> SystemResult {
     let localizations = project.localizations.as_ref().unwrap();
-    if localizations.translations.is_empty() {
+    if localizations.file_based_translations().next().is_none() {
         events.clear();
         return Ok(());
     }
 
-    for localization in &localizations.translations {
+    for localization in localizations.file_based_translations() {
         let language = &localization.language;
         let path = localization.strings_file.as_path();
         let asset_path = path.to_string_lossy().replace('\\', "/");
@@ -206,7 +206,7 @@ fn compile_loaded_yarn_files(
             update_strings_files_writer.send(UpdateAllStringsFilesForStringTableEvent(
                 compilation.string_table.clone(),
             ));
-            for localization in &localizations.translations {
+            for localization in localizations.file_based_translations() {
                 let path = localization.strings_file.as_path();
                 let path = asset_root.0.join(path);
 
@@ -144,6 +144,54 @@ fn panics_on_continue_after_all_lines() {
     app.dialogue_runner_mut().continue_in_next_update();
 }
 
+#[test]
+fn marks_lines_presented_again_as_seen() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    setup_dialogue_runner_without_localizations(&mut app).start_node("Start");
+    app.update();
+    assert_events!(asserter, app contains
+        PresentLineEvent with |event| event.line.text == english_lines()[0] && !event.line.is_seen);
+
+    app.dialogue_runner_mut().stop().start_node("Start");
+    app.update();
+    app.update();
+    assert_events!(asserter, app contains
+        PresentLineEvent with |event| event.line.text == english_lines()[0] && event.line.is_seen);
+    assert_eq!(1, app.dialogue_runner().seen_lines().len());
+
+    Ok(())
+}
+
+#[test]
+fn skips_seen_lines_until_unseen_one() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    setup_dialogue_runner_without_localizations(&mut app).start_node("Start");
+    app.continue_dialogue_and_update_n_times(3);
+    app.dialogue_runner_mut()
+        .stop()
+        .start_node("Start")
+        .skip_seen_lines(true);
+    app.update();
+    asserter.clear_events(&mut app);
+
+    for _ in 0..3 {
+        app.update();
+        assert_events!(asserter, app contains PresentLineEvent with |event| event.line.is_seen);
+        assert!(app.dialogue_runner().is_skipping_seen_lines());
+    }
+    app.update();
+    assert_events!(asserter, app contains
+        PresentLineEvent with |event| event.line.text == english_lines()[3] && !event.line.is_seen);
+    assert!(!app.dialogue_runner().is_skipping_seen_lines());
+
+    app.update();
+    assert_events!(asserter, app contains PresentLineEvent (n = 0));
+
+    Ok(())
+}
+
 #[test]
 #[cfg(feature = "audio_assets")]
 fn serves_assets_after_loading() -> Result<()> {
@@ -34,6 +34,33 @@ fn delivers_option_set() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn stops_skipping_seen_lines_at_options() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    app.setup_dialogue_runner().start_node("Start");
+    app.continue_dialogue_and_update_n_times(4);
+    app.dialogue_runner_mut()
+        .stop()
+        .start_node("Start")
+        .skip_seen_lines(true);
+    app.update();
+    asserter.clear_events(&mut app);
+
+    for _ in 0..4 {
+        app.update();
+    }
+
+    assert_events!(asserter, app contains [
+        PresentLineEvent (n = 3),
+        PresentOptionsEvent with |event| event.options.len() == 2,
+    ]);
+    assert!(!app.dialogue_runner().is_skipping_seen_lines());
+    assert!(app.dialogue_runner().is_waiting_for_option_selection());
+
+    Ok(())
+}
+
 #[test]
 fn errs_on_unexpected_selection_timing() -> Result<()> {
     let mut app = App::new();
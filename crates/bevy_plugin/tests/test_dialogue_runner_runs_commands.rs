@@ -110,6 +110,31 @@ fn executes_commands_and_fns() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn sends_variable_changed_events() -> Result<()> {
+    let mut app = App::new();
+    let mut asserter = EventAsserter::new();
+    app.setup_dialogue_runner().start_node("Start");
+    app.update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Setting variable",
+        VariableChangedEvent (n = 0),
+    ]);
+
+    app.continue_dialogue_and_update();
+    assert_events!(asserter, app contains [
+        PresentLineEvent with |event| event.line.text == "Calling command",
+        VariableChangedEvent with |event|
+            event.name == "$data" &&
+            event.new == YarnValue::from("foo"),
+    ]);
+
+    app.continue_dialogue_and_update();
+    assert_events!(asserter, app contains VariableChangedEvent (n = 0));
+
+    Ok(())
+}
+
 #[derive(Debug, Resource)]
 struct Data(String);
 
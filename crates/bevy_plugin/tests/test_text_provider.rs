@@ -153,3 +153,34 @@ fn loads_line_from_translated_language() {
         .unwrap();
     assert_eq!("Mann: Also gut. Ich glaub das zwar nicht, aber es kann ja nicht schaden, wenn ich mir was wünsche. Ich möchte wissen, wer ich bin.", line);
 }
+
+#[test]
+fn loads_line_from_pseudo_localization() {
+    let mut app = App::new();
+
+    app.setup_default_plugins().add_plugins(
+        YarnSpinnerPlugin::with_yarn_source(YarnFileSource::file("lines_with_ids.yarn"))
+            .with_localizations(Localizations {
+                base_localization: "en-US".into(),
+                translations: vec![Localization::with_language(PSEUDO_LOCALIZATION_LANGUAGE)
+                    .with_pseudo_localization(PseudoLocalization::default())],
+            })
+            .with_development_file_generation(DevelopmentFileGeneration::None),
+    );
+
+    app.dialogue_runner_mut()
+        .set_text_language(PSEUDO_LOCALIZATION_LANGUAGE);
+
+    app.load_lines();
+
+    let line = app
+        .dialogue_runner()
+        .text_provider()
+        .get_text(&LineId("line:9".to_owned()))
+        .unwrap();
+    assert!(
+        line.starts_with("Man: ⟦Åļļ ŕîĝĥŧ. Î đöń'ŧ ƀéļîéṽé ŧĥîš;"),
+        "{line}"
+    );
+    assert!(line.ends_with("~~⟧"), "{line}");
+}
@@ -11,6 +11,13 @@ pub(crate) fn register_initial_variables(
     variables.extend(standard_library_declarations);
     let job_library_declarations = get_declarations_from_library(&state.job.library);
     variables.extend(job_library_declarations);
+    let job_function_declarations = state
+        .job
+        .function_declarations
+        .iter()
+        .cloned()
+        .map(Declaration::from);
+    variables.extend(job_function_declarations);
 
     state
 }
@@ -7,9 +7,12 @@ use yarnspinner_core::prelude::*;
 
 mod add_tags_to_lines;
 pub(crate) mod antlr_rust_ext;
+mod function_declaration;
 pub(crate) mod run_compilation;
 pub(crate) mod utils;
 
+pub use self::function_declaration::FunctionDeclaration;
+
 #[allow(missing_docs)]
 pub type Result<T> = std::result::Result<T, CompilerError>;
 
@@ -43,6 +46,9 @@ pub struct Compiler {
 
     /// The declarations for variables.
     pub variable_declarations: Vec<Declaration>,
+
+    /// The declarations for functions that are not part of the [`Compiler::library`].
+    pub function_declarations: Vec<FunctionDeclaration>,
 }
 
 impl Compiler {
@@ -97,6 +103,34 @@ impl Compiler {
         self
     }
 
+    /// Declares a function without providing an implementation for it, so that calls to it can be type-checked.
+    /// See [`FunctionDeclaration`] for more information.
+    pub fn declare_function(&mut self, declaration: FunctionDeclaration) -> &mut Self {
+        self.function_declarations.push(declaration);
+        self
+    }
+
+    /// Declares multiple functions without providing implementations for them.
+    pub fn declare_functions(
+        &mut self,
+        declarations: impl IntoIterator<Item = FunctionDeclaration>,
+    ) -> &mut Self {
+        self.function_declarations.extend(declarations);
+        self
+    }
+
+    /// Declares the functions found in a Yarn Spinner language server definitions file (`.ysls.json`) by reading it from disk.
+    /// See [`FunctionDeclaration::from_ysls`].
+    #[cfg(feature = "serde")]
+    pub fn try_read_ysls_file(
+        &mut self,
+        file_path: impl AsRef<Path>,
+    ) -> std::io::Result<&mut Self> {
+        let json = std::fs::read_to_string(file_path)?;
+        let declarations = FunctionDeclaration::from_ysls(&json)?;
+        Ok(self.declare_functions(declarations))
+    }
+
     /// Compiles the Yarn files previously added into a [`Compilation`].
     pub fn compile(&self) -> Result<Compilation> {
         run_compilation::compile(self)
@@ -170,4 +204,101 @@ a {1 + 3} cool expression
         };
         Compiler::new().add_file(file).compile().unwrap();
     }
+
+    #[test]
+    fn can_call_declared_function_without_implementation() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+You rolled {roll(6)}.
+<<if is_raining(\"Paris\")>>
+    Bring an umbrella.
+<<endif>>
+==="
+            .to_string(),
+        };
+        let result = Compiler::new()
+            .add_file(file)
+            .declare_function(
+                FunctionDeclaration::new("roll", Type::Number).with_parameter(Type::Number),
+            )
+            .declare_function(
+                FunctionDeclaration::new("is_raining", Type::Boolean)
+                    .with_parameter(Type::String)
+                    .with_description("Whether it is currently raining in the given city"),
+            )
+            .compile()
+            .unwrap();
+
+        // Declared functions are known beforehand, so no implicit declarations are derived for them
+        assert!(result.declarations.is_empty());
+    }
+
+    #[test]
+    fn catches_wrong_parameter_types_of_declared_function() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+You rolled {roll(\"six\")}.
+==="
+            .to_string(),
+        };
+        let result = Compiler::new()
+            .add_file(file)
+            .declare_function(
+                FunctionDeclaration::new("roll", Type::Number).with_parameter(Type::Number),
+            )
+            .compile();
+
+        let diagnostics = result.unwrap_err().0;
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(
+            "roll parameter 1 expects a Number, not a String",
+            diagnostics[0].message
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn reads_function_declarations_from_ysls() {
+        let json = r#"{
+            "Functions": [
+                {
+                    "YarnName": "roll",
+                    "DefinitionName": "Dice.Roll",
+                    "Documentation": "Rolls a die with the given number of sides",
+                    "Parameters": [{ "Name": "sides", "Type": "number" }],
+                    "ReturnType": "number"
+                },
+                {
+                    "YarnName": "greet",
+                    "Parameters": [{ "Name": "name", "Type": "string" }, { "Name": "formal", "Type": "bool" }],
+                    "ReturnType": "string"
+                }
+            ],
+            "Commands": [{ "YarnName": "shake" }]
+        }"#;
+        let declarations = FunctionDeclaration::from_ysls(json).unwrap();
+
+        assert_eq!(
+            vec![
+                FunctionDeclaration::new("roll", Type::Number)
+                    .with_parameter(Type::Number)
+                    .with_description("Rolls a die with the given number of sides"),
+                FunctionDeclaration::new("greet", Type::String)
+                    .with_parameter(Type::String)
+                    .with_parameter(Type::Boolean),
+            ],
+            declarations
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rejects_unknown_types_in_ysls() {
+        let json = r#"{ "Functions": [{ "YarnName": "roll", "ReturnType": "dice" }] }"#;
+        assert!(FunctionDeclaration::from_ysls(json).is_err());
+    }
 }
@@ -48,8 +48,11 @@ impl Compiler {
         }
 
         // Create the line listener, which will produce TextReplacements for each new line tag.
-        let untagged_line_listener =
-            Box::new(UntaggedLineListener::new(existing_line_tags, parse_source));
+        let untagged_line_listener = Box::new(UntaggedLineListener::new(
+            existing_line_tags,
+            parse_source,
+            &file.source,
+        ));
         let rewritten_nodes = untagged_line_listener.rewritten_lines.clone();
         let rewrote_anything = untagged_line_listener.rewrote_anything.clone();
 
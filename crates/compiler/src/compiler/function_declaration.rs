@@ -0,0 +1,160 @@
+//! Contains [`FunctionDeclaration`], which lets the compiler know about functions whose implementation lives outside of the [`Library`].
+//!
+//! ## Implementation notes
+//!
+//! The original implementation reads these from `.ysls.json` files through the language server.
+//! Here, they can be constructed by hand or, with the `serde` feature, read from the same JSON format.
+
+use crate::prelude::*;
+use yarnspinner_core::prelude::*;
+use yarnspinner_core::types::{FunctionType, Type};
+
+/// The signature of a function that is callable from Yarn, but whose implementation is not available to the compiler.
+///
+/// This is useful for tooling that compiles Yarn files without having access to the game,
+/// e.g. editors, linters or CI pipelines. The compiler will type-check calls to these functions
+/// just like calls to functions registered in a [`Library`].
+/// Note that the [`Dialogue`](https://docs.rs/yarnspinner_runtime/latest/yarnspinner_runtime/struct.Dialogue.html)
+/// running the resulting program still needs an actual implementation.
+///
+/// Add them to a compilation with [`Compiler::declare_function`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "bevy", reflect(Debug, PartialEq))]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serde"),
+    reflect(Serialize, Deserialize)
+)]
+pub struct FunctionDeclaration {
+    /// The name of the function as it is called from Yarn.
+    pub name: String,
+
+    /// The types of the parameters of this function, in order.
+    pub parameter_types: Vec<Type>,
+
+    /// The type of the value this function returns.
+    pub return_type: Type,
+
+    /// A string describing the purpose of this function.
+    pub description: Option<String>,
+}
+
+impl FunctionDeclaration {
+    /// Creates a new [`FunctionDeclaration`] without parameters.
+    pub fn new(name: impl Into<String>, return_type: impl Into<Type>) -> Self {
+        Self {
+            name: name.into(),
+            parameter_types: Vec::new(),
+            return_type: return_type.into(),
+            description: None,
+        }
+    }
+
+    /// Appends a parameter of the given type to the signature.
+    pub fn with_parameter(mut self, parameter_type: impl Into<Type>) -> Self {
+        self.parameter_types.push(parameter_type.into());
+        self
+    }
+
+    /// Sets the description of this function.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// The [`FunctionType`] described by this declaration.
+    pub fn function_type(&self) -> FunctionType {
+        let mut function_type = FunctionType::default();
+        for parameter_type in &self.parameter_types {
+            function_type.add_parameter(parameter_type.clone());
+        }
+        function_type.set_return_type(self.return_type.clone());
+        function_type
+    }
+}
+
+impl From<FunctionDeclaration> for Declaration {
+    fn from(function_declaration: FunctionDeclaration) -> Self {
+        Declaration::new(
+            function_declaration.name.clone(),
+            function_declaration.function_type(),
+        )
+        .with_description_optional(function_declaration.description)
+        .with_source_file_name(DeclarationSource::External)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod ysls {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct YslsFile {
+        #[serde(default)]
+        functions: Vec<YslsFunction>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct YslsFunction {
+        yarn_name: String,
+        #[serde(default)]
+        documentation: Option<String>,
+        #[serde(default)]
+        parameters: Vec<YslsParameter>,
+        #[serde(default)]
+        return_type: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct YslsParameter {
+        #[serde(rename = "Type", default)]
+        r#type: Option<String>,
+    }
+
+    fn parse_type(function_name: &str, type_name: Option<&str>) -> serde_json::Result<Type> {
+        let Some(type_name) = type_name else {
+            return Ok(Type::Any);
+        };
+        match type_name.to_ascii_lowercase().as_str() {
+            "any" => Ok(Type::Any),
+            "bool" | "boolean" => Ok(Type::Boolean),
+            "number" => Ok(Type::Number),
+            "string" => Ok(Type::String),
+            _ => Err(serde::de::Error::custom(format!(
+                "Unknown type \"{type_name}\" in declaration of function \"{function_name}\""
+            ))),
+        }
+    }
+
+    impl FunctionDeclaration {
+        /// Reads the function declarations from the contents of a Yarn Spinner language server definitions file (`.ysls.json`).
+        /// Commands in the file are ignored.
+        ///
+        /// Parameters and return values without a type are treated as [`Type::Any`].
+        pub fn from_ysls(json: &str) -> serde_json::Result<Vec<Self>> {
+            let file: YslsFile = serde_json::from_str(json)?;
+            file.functions
+                .into_iter()
+                .map(|function| {
+                    let name = function.yarn_name;
+                    let parameter_types = function
+                        .parameters
+                        .iter()
+                        .map(|parameter| parse_type(&name, parameter.r#type.as_deref()))
+                        .collect::<serde_json::Result<_>>()?;
+                    let return_type = parse_type(&name, function.return_type.as_deref())?;
+                    Ok(Self {
+                        name,
+                        parameter_types,
+                        return_type,
+                        description: function.documentation,
+                    })
+                })
+                .collect()
+        }
+    }
+}
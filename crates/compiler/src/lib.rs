@@ -31,7 +31,7 @@ pub mod prelude {
         token_ext::*,
     };
     pub use crate::{
-        compiler::{CompilationType, Compiler, File},
+        compiler::{CompilationType, Compiler, File, FunctionDeclaration},
         listeners::{Diagnostic, DiagnosticSeverity, DiagnosticVec},
         output::*,
     };
@@ -25,13 +25,12 @@ pub(crate) struct UntaggedLineListener<'input> {
 }
 
 impl<'input> UntaggedLineListener<'input> {
-    pub fn new(existing_line_tags: Vec<LineId>, file: FileParseResult<'input>) -> Self {
-        let original_source = file
-            .tokens()
-            .get_all_text()
-            .lines()
-            .map(|s| s.to_owned())
-            .collect();
+    pub fn new(
+        existing_line_tags: Vec<LineId>,
+        file: FileParseResult<'input>,
+        original_source: &str,
+    ) -> Self {
+        let original_source = original_source.lines().map(|s| s.to_owned()).collect();
         Self {
             existing_line_tags,
             file,
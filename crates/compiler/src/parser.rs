@@ -6,3 +6,4 @@ mod indent_aware_lexer;
 
 pub(crate) use actual_types::*;
 pub(crate) use indent_aware_lexer::IndentAwareYarnSpinnerLexer as YarnSpinnerLexer;
+pub(crate) use indent_aware_lexer::{parse_format_specifier, FORMAT_SPECIFIER, FORMAT_SPECIFIERS};
@@ -14,6 +14,7 @@ use crate::prelude::{create_common_token, DiagnosticSeverity, TokenExt};
 use antlr_rust::token::CommonToken;
 use antlr_rust::{
     char_stream::CharStream,
+    int_stream::IntStream,
     token::{Token, TOKEN_DEFAULT_CHANNEL},
     token_factory::{CommonTokenFactory, TokenFactory},
     Lexer, TokenSource,
@@ -27,6 +28,27 @@ use yarnspinner_core::prelude::*;
 #[allow(dead_code)]
 type YarnSpinnerLexer = ();
 
+/// The token type of format specifiers like the ` : N0` in `{$gold : N0}`, including the colon and the whitespace around it.
+/// The generated lexer knows nothing about them, so they are lexed by [`IndentAwareYarnSpinnerLexer`]
+/// and numbered after the token types of the generated lexer.
+pub(crate) const FORMAT_SPECIFIER: isize = yarnspinnerlexer::_SYMBOLIC_NAMES.len() as isize;
+
+/// The channel of [`FORMAT_SPECIFIER`] tokens, which keeps them away from the parser.
+/// The compiler reads them from the token stream instead, like the comments on [`yarnspinnerlexer::COMMENTS`].
+pub(crate) const FORMAT_SPECIFIERS: usize = yarnspinnerlexer::channelNames.len();
+
+/// Parses the text of a [`FORMAT_SPECIFIER`] token.
+pub(crate) fn parse_format_specifier(
+    token_text: &str,
+) -> Result<FormatSpecifier, InvalidFormatSpecifierError> {
+    let token_text = token_text.trim_start();
+    token_text
+        .strip_prefix(':')
+        .unwrap_or(token_text)
+        .trim()
+        .parse()
+}
+
 antlr_rust::tid! { impl<'input, Input> TidAble<'input> for IndentAwareYarnSpinnerLexer<'input, Input> where Input:CharStream<From<'input>> }
 
 /// A Lexer subclass that detects newlines and generates indent and dedent tokens accordingly.
@@ -61,6 +83,9 @@ pub(crate) struct IndentAwareYarnSpinnerLexer<
     /// holds the line number of the last seen option.
     /// Lets us work out if the blank line needs to end the option.
     last_seen_option_content: Option<isize>,
+    /// Whether we are inside an inline expression of a line, like `{$gold}` in `You have {$gold} gold`.
+    /// Only these can end with a format specifier.
+    is_in_line_expression: bool,
     file_name: String,
     pub(crate) diagnostics: Rc<RefCell<Vec<Diagnostic>>>,
 }
@@ -136,12 +161,18 @@ where
             last_indent: Default::default(),
             unbalanced_indents: Default::default(),
             last_seen_option_content: None,
+            is_in_line_expression: false,
             diagnostics: Default::default(),
         }
     }
 
     fn check_next_token(&mut self) {
-        let current = self.base.next_token();
+        let format_specifier = if self.is_in_line_expression {
+            self.lex_format_specifier()
+        } else {
+            None
+        };
+        let current = format_specifier.unwrap_or_else(|| self.base.next_token());
 
         match current.token_type {
             // Insert indents or dedents depending on the next token's
@@ -161,6 +192,14 @@ where
                 self.diagnose_newlines_in_commands(&current);
                 self.pending_tokens.enqueue(current.clone());
             }
+            yarnspinnerlexer::EXPRESSION_START => {
+                self.is_in_line_expression = true;
+                self.pending_tokens.enqueue(current.clone());
+            }
+            yarnspinnerlexer::EXPRESSION_END => {
+                self.is_in_line_expression = false;
+                self.pending_tokens.enqueue(current.clone());
+            }
             yarnspinnerlexer::BODY_END => {
                 self.line_contains_shortcut = false;
                 self.last_indent = 0;
@@ -351,6 +390,77 @@ where
         self.pending_tokens.enqueue(token);
     }
 
+    /// Lexes a format specifier like the `:N0` in `{$gold:N0}` if it comes next.
+    /// It starts with a colon and ends right before the `}` that closes the expression.
+    /// Unterminated expressions are left to the generated lexer, which reports them.
+    fn lex_format_specifier(&mut self) -> Option<Box<CommonToken<'input>>> {
+        let input = self.base.input();
+        let mut length = 0;
+        while matches!(char_at(input, length + 1), Some(' ' | '\t')) {
+            length += 1;
+        }
+        if char_at(input, length + 1) != Some(':') {
+            return None;
+        }
+        while char_at(input, length + 1) != Some('}') {
+            if matches!(char_at(input, length + 1), None | Some('\n' | '\r')) {
+                return None;
+            }
+            length += 1;
+        }
+
+        let start_index = input.index();
+        let line = self.get_line();
+        let char_position_in_line = self.get_char_position_in_line();
+        let lexer = &mut *self.base;
+        let input = lexer.input.as_mut().unwrap();
+        let text: String = (1..=length)
+            .filter_map(|offset| char_at(input, offset))
+            .collect();
+        for _ in 0..length {
+            // Goes through the interpreter so that the line and column of the following tokens stay correct
+            lexer.interpreter.as_ref().unwrap().consume(input);
+        }
+        self.diagnose_invalid_format_specifier(&text, line, char_position_in_line);
+
+        let token = CommonTokenFactory.create(
+            self.base.input.as_mut(),
+            FORMAT_SPECIFIER,
+            Some(text),
+            FORMAT_SPECIFIERS as isize,
+            start_index,
+            start_index + length - 1,
+            line,
+            char_position_in_line,
+        );
+        Some(token)
+    }
+
+    fn diagnose_invalid_format_specifier(&mut self, text: &str, line: isize, column: isize) {
+        let Err(error) = parse_format_specifier(text) else {
+            return;
+        };
+        let line = line as usize - 1;
+        let column = column as usize;
+        let whitespace_length = text.chars().take_while(|c| c.is_whitespace()).count();
+        self.diagnostics.borrow_mut().push(
+            Diagnostic::from_message(error.to_string())
+                .with_range(
+                    Position {
+                        line,
+                        character: column + whitespace_length,
+                    }..Position {
+                        line,
+                        character: column + text.chars().count(),
+                    },
+                )
+                .with_context(text.trim())
+                .with_start_line(line)
+                .with_file_name(self.file_name.clone())
+                .with_severity(DiagnosticSeverity::Error),
+        );
+    }
+
     fn diagnose_newlines_in_commands(&mut self, token: &CommonToken<'input>) {
         if token.get_text().contains('\n') {
             let line_len = token.get_text().lines().count();
@@ -375,6 +485,13 @@ where
     }
 }
 
+/// Looks ahead `offset` characters, starting at 1 for the next one. `None` at the end of the input.
+fn char_at(input: &mut impl IntStream, offset: isize) -> Option<char> {
+    u32::try_from(input.la(offset))
+        .ok()
+        .and_then(char::from_u32)
+}
+
 fn get_newline_indentation_range(token: &CommonToken<'_>) -> Range<Position> {
     // +1 compared to similar code because we don't want to start at the newline
     let line = token.get_line_as_usize();
@@ -567,6 +684,58 @@ This is the one and only line
         assert_eq!(expected, symbols);
     }
 
+    #[test]
+    fn lexes_format_specifiers_on_their_own_channel() {
+        let input = "title: Start
+---
+You have {$gold : N0} gold and {\"a:b\"}
+{$luck:X1}
+===";
+        let indent_aware_lexer =
+            IndentAwareYarnSpinnerLexer::new(InputStream::new(input), "input.yarn".to_owned());
+        let diagnostics = indent_aware_lexer.diagnostics.clone();
+        let mut indent_aware_token_stream = CommonTokenStream::new(indent_aware_lexer);
+
+        while indent_aware_token_stream.la(1) != TOKEN_EOF {
+            indent_aware_token_stream.iter().next();
+        }
+
+        let format_specifiers: Vec<_> = indent_aware_token_stream
+            .get_tokens()
+            .iter()
+            .filter(|token| token.get_channel() == FORMAT_SPECIFIERS as isize)
+            .map(|token| {
+                (
+                    token.get_token_type(),
+                    token.get_text().to_owned(),
+                    token.get_line(),
+                    token.get_column(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            vec![
+                (FORMAT_SPECIFIER, " : N0".to_owned(), 3, 15),
+                (FORMAT_SPECIFIER, ":X1".to_owned(), 4, 6),
+            ],
+            format_specifiers
+        );
+        let diagnostics = diagnostics.borrow();
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(
+            Some(
+                Position {
+                    line: 3,
+                    character: 6
+                }..Position {
+                    line: 3,
+                    character: 9
+                }
+            ),
+            diagnostics[0].range
+        );
+    }
+
     #[test]
     fn generated_lexer_output_is_same_as_reference() {
         let option_indentation_relevant_input: &str = include_str!("significant_whitespace.yarn");
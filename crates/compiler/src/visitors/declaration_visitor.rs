@@ -238,6 +238,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            function_declarations: vec![],
         }
         .compile()
         .unwrap();
@@ -328,6 +329,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            function_declarations: vec![],
         }
         .compile();
 
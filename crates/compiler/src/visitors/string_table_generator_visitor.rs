@@ -119,7 +119,8 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
         let line_number = ctx.start().get_line_as_usize();
         let hashtag_texts = get_hashtag_texts(&hashtags);
 
-        let composed_string = generate_formatted_text(&ctx.line_formatted_text().unwrap());
+        let composed_string =
+            generate_formatted_text(&ctx.line_formatted_text().unwrap(), self.file.tokens());
 
         let string_id = self.string_table_manager.insert(
             line_id.map(|t| t.get_text().into()),
@@ -140,10 +141,13 @@ impl<'input> YarnSpinnerParserVisitorCompat<'input> for StringTableGeneratorVisi
 }
 
 /// Takes a string like
-/// `Hi there { some_expression }, how are you { another_expression } doing?`
+/// `Hi there { some_expression }, how are you { another_expression:N0 } doing?`
 /// and turns it into
-/// `Hi there {0}, how are you {1}? doing`
-fn generate_formatted_text(ctx: &Line_formatted_textContext) -> String {
+/// `Hi there {0}, how are you {1:N0}? doing`
+fn generate_formatted_text<'input>(
+    ctx: &Line_formatted_textContext<'input>,
+    tokens: &ActualTokenStream<'input>,
+) -> String {
     let mut expression_count = 0;
     let mut composed_string = String::new();
     // First, visit all of the nodes, which are either terminal
@@ -161,6 +165,19 @@ fn generate_formatted_text(ctx: &Line_formatted_textContext) -> String {
             // captured already has them. So, we just need to write
             // the expression count.
             composed_string.push_str(&expression_count.to_string());
+            // The lexer puts format specifiers on their own channel right after the expression.
+            // Invalid ones were already reported by the lexer.
+            let format_specifier = tokens
+                .get_hidden_tokens_to_right(
+                    child.stop().get_token_index(),
+                    FORMAT_SPECIFIERS as isize,
+                )
+                .iter()
+                .filter(|token| token.get_token_type() == FORMAT_SPECIFIER)
+                .find_map(|token| parse_format_specifier(&token.get_text()).ok());
+            if let Some(format_specifier) = format_specifier {
+                composed_string.push_str(&format!(":{format_specifier}"));
+            }
             expression_count += 1;
         }
     }
@@ -184,8 +201,6 @@ pub(crate) fn get_hashtag_texts(hashtags: &[Rc<HashtagContext>]) -> Vec<String>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use antlr_rust::common_token_stream::CommonTokenStream;
-    use antlr_rust::InputStream;
     use yarnspinner_core::prelude::Position;
 
     #[test]
@@ -224,12 +239,27 @@ A line with {$many} many {(1 -(1 * 2))}{$cool} expressions
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn formats_lines_with_format_specifiers() {
+        let input = "title: Title
+---
+You have {$gold:N0} gold, {$gold / 100} kg and {$luck : P1} luck
+===
+";
+        let result = process_input(input);
+        let expected = "You have {0:N0} gold, {1} kg and {2:P1} luck";
+        assert_eq!(result, expected);
+    }
+
     fn process_input(input: &str) -> String {
-        let lexer = YarnSpinnerLexer::new(InputStream::new(input), "input.yarn".to_owned());
-        let mut parser = YarnSpinnerParser::new(CommonTokenStream::new(lexer));
-        let line_formatted_text = parser
-            .dialogue()
-            .unwrap()
+        let file = File {
+            file_name: "input.yarn".to_owned(),
+            source: input.to_owned(),
+        };
+        let chars: Vec<_> = input.chars().map(|c| c as u32).collect();
+        let parse_result = parse_syntax_tree(&file, &chars, &mut Vec::new());
+        let line_formatted_text = parse_result
+            .tree
             .node(0)
             .unwrap()
             .body()
@@ -240,7 +270,7 @@ A line with {$many} many {(1 -(1 * 2))}{$cool} expressions
             .unwrap()
             .line_formatted_text()
             .unwrap();
-        generate_formatted_text(&line_formatted_text)
+        generate_formatted_text(&line_formatted_text, parse_result.tokens())
     }
 
     #[test]
@@ -260,6 +290,7 @@ a {1 + 3} cool expression
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            function_declarations: vec![],
         }
         .compile()
         .unwrap();
@@ -301,6 +332,56 @@ a {1 + 3} cool expression
         );
     }
 
+    #[test]
+    fn writes_format_specifiers_into_string_table() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+<<declare $gold = 1234.5>>
+You have {$gold:N0} gold #line:gold
+-> Pay {$gold : f2} #line:pay
+==="
+            .to_string(),
+        };
+        let result = Compiler::new().add_file(file).compile().unwrap();
+
+        let string_table = result.string_table;
+        assert_eq!(
+            "You have {0:N0} gold",
+            string_table[&"line:gold".into()].text
+        );
+        assert_eq!("Pay {0:F2}", string_table[&"line:pay".into()].text);
+    }
+
+    #[test]
+    fn catches_invalid_format_specifiers() {
+        let file = File {
+            file_name: "test.yarn".to_string(),
+            source: "title: test
+---
+<<declare $gold = 0>>
+You have {$gold:Y} gold
+==="
+            .to_string(),
+        };
+        let diagnostics = Compiler::new().add_file(file).compile().unwrap_err().0;
+
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(
+            Some(
+                Position {
+                    line: 3,
+                    character: 15,
+                }..Position {
+                    line: 3,
+                    character: 17,
+                }
+            ),
+            diagnostics[0].range
+        );
+    }
+
     #[test]
     fn catches_expression_errors() {
         let file = File {
@@ -318,6 +399,7 @@ a {very} cool expression
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            function_declarations: vec![],
         }
         .compile();
 
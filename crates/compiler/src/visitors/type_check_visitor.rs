@@ -629,6 +629,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            function_declarations: vec![],
         }
         .compile()
         .unwrap();
@@ -654,6 +655,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            function_declarations: vec![],
         }
         .compile();
 
@@ -741,6 +743,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            function_declarations: vec![],
         }
         .compile()
         .unwrap();
@@ -764,6 +767,7 @@ mod tests {
             library: Default::default(),
             compilation_type: CompilationType::FullCompilation,
             variable_declarations: vec![],
+            function_declarations: vec![],
         }
         .compile();
 
@@ -0,0 +1,117 @@
+//! Format specifiers for inline expressions, e.g. the `N0` in `{$gold:N0}`.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Describes how the value of an inline expression is formatted, e.g. `N0` in `{$gold:N0}`.
+///
+/// The specifiers follow the standard numeric format strings of .NET. The letter is case-insensitive and
+/// may be followed by up to two digits, which set the precision. Numbers are formatted with the digits,
+/// separators and signs of the dialogue's language. Values that are not numbers are inserted as they are.
+///
+/// The compiler writes the specifier into the string table, e.g. `You have {0:N0} gold`,
+/// so translators can pick a different one for their language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FormatSpecifier {
+    /// `N`: A number with grouping separators and the given number of decimals, e.g. `1,234.50` for `N2`. Defaults to 2 decimals.
+    Number {
+        /// The number of decimals.
+        decimals: u8,
+    },
+    /// `F`: A number without grouping separators and with the given number of decimals, e.g. `1234.50` for `F2`. Defaults to 2 decimals.
+    FixedPoint {
+        /// The number of decimals.
+        decimals: u8,
+    },
+    /// `P`: A number multiplied by 100 and followed by a percent sign, e.g. `12.5%` for `P1` and a value of `0.125`. Defaults to 2 decimals.
+    Percent {
+        /// The number of decimals.
+        decimals: u8,
+    },
+    /// `D`: A whole number padded with zeros to the given number of digits, e.g. `007` for `D3`. Defaults to no padding.
+    Decimal {
+        /// The minimal number of digits.
+        digits: u8,
+    },
+}
+
+impl FormatSpecifier {
+    /// The number of decimals used by `N`, `F` and `P` if none are given.
+    pub const DEFAULT_DECIMALS: u8 = 2;
+}
+
+impl FromStr for FormatSpecifier {
+    type Err = InvalidFormatSpecifierError;
+
+    fn from_str(specifier: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidFormatSpecifierError(specifier.to_owned());
+        let mut chars = specifier.chars();
+        let letter = chars.next().ok_or_else(error)?;
+        let precision = chars.as_str();
+        let precision = match precision {
+            "" => None,
+            digits if digits.len() <= 2 && digits.chars().all(|c| c.is_ascii_digit()) => {
+                Some(digits.parse().map_err(|_| error())?)
+            }
+            _ => return Err(error()),
+        };
+        let decimals = precision.unwrap_or(Self::DEFAULT_DECIMALS);
+        match letter.to_ascii_uppercase() {
+            'N' => Ok(Self::Number { decimals }),
+            'F' => Ok(Self::FixedPoint { decimals }),
+            'P' => Ok(Self::Percent { decimals }),
+            'D' => Ok(Self::Decimal {
+                digits: precision.unwrap_or_default(),
+            }),
+            _ => Err(error()),
+        }
+    }
+}
+
+impl Display for FormatSpecifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number { decimals } => write!(f, "N{decimals}"),
+            Self::FixedPoint { decimals } => write!(f, "F{decimals}"),
+            Self::Percent { decimals } => write!(f, "P{decimals}"),
+            Self::Decimal { digits } => write!(f, "D{digits}"),
+        }
+    }
+}
+
+/// The error returned when a string is not a valid [`FormatSpecifier`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidFormatSpecifierError(pub String);
+
+impl Error for InvalidFormatSpecifierError {}
+
+impl Display for InvalidFormatSpecifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "\"{}\" is not a valid format specifier. Expected N, F, P or D, optionally followed by up to two digits, e.g. N0",
+            self.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_specifiers() {
+        assert_eq!(Ok(FormatSpecifier::Number { decimals: 0 }), "N0".parse());
+        assert_eq!(Ok(FormatSpecifier::Percent { decimals: 2 }), "p".parse());
+        assert_eq!(
+            Ok(FormatSpecifier::FixedPoint { decimals: 12 }),
+            "F12".parse()
+        );
+        assert_eq!(Ok(FormatSpecifier::Decimal { digits: 0 }), "D".parse());
+        assert!("X".parse::<FormatSpecifier>().is_err());
+        assert!("N123".parse::<FormatSpecifier>().is_err());
+        assert!("N-1".parse::<FormatSpecifier>().is_err());
+        assert!("".parse::<FormatSpecifier>().is_err());
+    }
+}
@@ -7,6 +7,7 @@
 
 #![warn(missing_docs, missing_debug_implementations)]
 mod feature_gates;
+mod format_specifier;
 mod generated;
 mod internal_value;
 mod library;
@@ -23,6 +24,7 @@ pub mod prelude {
     pub use crate::feature_gates::*;
 
     pub use crate::{
+        format_specifier::*,
         generated::{
             instruction::OpCode, operand::Value as OperandValue, Header, Instruction,
             InvalidOpCodeError, Node, Operand, Program,
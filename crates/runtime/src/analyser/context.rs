@@ -33,7 +33,12 @@ impl Context {
 
     /// Sets up a [`Context`] with the default analysers. These are:
     /// - Variable Lister: Adds a [`DiagnosisSeverity::Note`] diagnosis for each variable in the program.
-    /// - Unused Variable Checker: Adds a [`DiagnosisSeverity::Warning`] diagnosis for each unused variable in the program.
+    /// - Unused Variable Checker: Adds a [`DiagnosisSeverity::Warning`] diagnosis for each node that writes to a variable that is never read.
+    /// - [`DanglingJumpChecker`]: Adds a [`DiagnosisSeverity::Error`] diagnosis for each jump to a node that does not exist.
+    /// - [`DeadOptionChecker`]: Adds a [`DiagnosisSeverity::Warning`] diagnosis for each option whose condition is always false.
+    /// - [`InfiniteJumpLoopChecker`]: Adds a [`DiagnosisSeverity::Error`] diagnosis for each node that can only end up jumping between nodes forever.
+    ///
+    /// The [`UnreachableNodeChecker`] is not included, since it needs to know which nodes your game starts dialogue at.
     #[must_use]
     pub fn default_analysers() -> Self {
         let mut context = Self::empty();
@@ -1,6 +1,15 @@
+pub use self::{
+    dangling_jump_checker::*, dead_option_checker::*, infinite_jump_loop_checker::*,
+    unreachable_node_checker::*,
+};
 use self::{unused_variable_checker::*, variable_lister::*};
 use crate::prelude::*;
 
+mod dangling_jump_checker;
+mod dead_option_checker;
+mod infinite_jump_loop_checker;
+mod node_flow;
+mod unreachable_node_checker;
 mod unused_variable_checker;
 mod variable_lister;
 
@@ -10,5 +19,200 @@ macro_rules! boxes {
     };
 }
 pub(crate) fn default_analysers() -> Vec<Box<dyn CompiledProgramAnalyser>> {
-    boxes![VariableLister, UnusedVariableChecker]
+    boxes![
+        VariableLister,
+        UnusedVariableChecker,
+        DanglingJumpChecker,
+        DeadOptionChecker,
+        InfiniteJumpLoopChecker
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use yarnspinner_core::prelude::*;
+
+    fn analyse(mut analyser: impl CompiledProgramAnalyser, program: &Program) -> Vec<Diagnosis> {
+        analyser.diagnose(program);
+        analyser.collect_diagnoses()
+    }
+
+    #[test]
+    fn finds_dangling_jumps() {
+        let program = program([
+            ("Start", [jump("Nowhere").as_slice(), &[stop()]].concat()),
+            ("Other", [jump("Start").as_slice(), &[stop()]].concat()),
+        ]);
+
+        let diagnoses = analyse(DanglingJumpChecker::new(), &program);
+
+        assert_eq!(
+            vec![Diagnosis::new(
+                DiagnosisSeverity::Error,
+                "Node Start jumps to node Nowhere, which does not exist".to_owned()
+            )
+            .with_node_name("Start")],
+            diagnoses
+        );
+    }
+
+    #[test]
+    fn finds_unreachable_nodes() {
+        let program = program([
+            (
+                "Start",
+                [&[line("line:a")], jump("Shop").as_slice()].concat(),
+            ),
+            ("Shop", vec![line("line:b"), stop()]),
+            ("Secret", vec![line("line:c"), stop()]),
+        ]);
+
+        let diagnoses = analyse(UnreachableNodeChecker::new(), &program);
+        assert_eq!(1, diagnoses.len());
+        assert_eq!(Some("Secret".to_owned()), diagnoses[0].node_name);
+
+        let diagnoses = analyse(
+            UnreachableNodeChecker::new().with_entry_nodes(["Start", "Secret"]),
+            &program,
+        );
+        assert!(diagnoses.is_empty());
+    }
+
+    #[test]
+    fn does_not_report_unreachable_nodes_with_dynamic_jumps() {
+        let program = program([
+            (
+                "Start",
+                vec![
+                    instruction(OpCode::PushVariable, ["$destination".to_owned().into()]),
+                    instruction(OpCode::RunNode, []),
+                ],
+            ),
+            ("Secret", vec![line("line:c"), stop()]),
+        ]);
+
+        let diagnoses = analyse(UnreachableNodeChecker::new(), &program);
+
+        assert!(diagnoses.is_empty());
+    }
+
+    #[test]
+    fn finds_options_with_constant_false_conditions() {
+        let add_option = |line_id: &str, expression_count: usize, has_condition: bool| {
+            instruction(
+                OpCode::AddOption,
+                [
+                    line_id.to_owned().into(),
+                    "destination".to_owned().into(),
+                    expression_count.into(),
+                    has_condition.into(),
+                ],
+            )
+        };
+        let program = program([(
+            "Start",
+            vec![
+                instruction(OpCode::PushBool, [false.into()]),
+                add_option("line:never", 0, true),
+                instruction(OpCode::PushBool, [false.into()]),
+                instruction(OpCode::PushVariable, ["$name".to_owned().into()]),
+                add_option("line:never_with_expression", 1, true),
+                instruction(OpCode::PushVariable, ["$condition".to_owned().into()]),
+                add_option("line:maybe", 0, true),
+                add_option("line:always", 0, false),
+                instruction(OpCode::ShowOptions, []),
+                instruction(OpCode::Jump, []),
+                stop(),
+            ],
+        )]);
+
+        let diagnoses = analyse(DeadOptionChecker::new(), &program);
+
+        let messages: Vec<_> = diagnoses.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            vec![
+                "Option line:never has a condition that is always false, so it can never be selected",
+                "Option line:never_with_expression has a condition that is always false, so it can never be selected",
+            ],
+            messages
+        );
+    }
+
+    #[test]
+    fn finds_infinite_jump_loops() {
+        let mut program = program([
+            ("Ping", [jump("Pong").as_slice(), &[stop()]].concat()),
+            ("Pong", [jump("Ping").as_slice(), &[stop()]].concat()),
+            (
+                "Talk",
+                [&[line("line:a")], jump("Ping").as_slice()].concat(),
+            ),
+            (
+                "Branch",
+                [
+                    &[
+                        instruction(OpCode::PushVariable, ["$flag".to_owned().into()]),
+                        instruction(OpCode::JumpIfFalse, ["skip".to_owned().into()]),
+                    ],
+                    jump("Ping").as_slice(),
+                    &[instruction(OpCode::Pop, []), line("line:b"), stop()],
+                ]
+                .concat(),
+            ),
+        ]);
+        program
+            .nodes
+            .get_mut("Branch")
+            .unwrap()
+            .labels
+            .insert("skip".to_owned(), 4);
+
+        let diagnoses = analyse(InfiniteJumpLoopChecker::new(), &program);
+
+        let node_names: Vec<_> = diagnoses
+            .iter()
+            .map(|d| d.node_name.clone().unwrap())
+            .collect();
+        assert_eq!(vec!["Ping", "Pong"], node_names);
+        assert!(diagnoses
+            .iter()
+            .all(|d| d.severity == DiagnosisSeverity::Error));
+    }
+
+    #[test]
+    fn reports_unused_variables_with_node() {
+        let store = |variable: &str| {
+            [
+                instruction(OpCode::PushBool, [true.into()]),
+                instruction(OpCode::StoreVariable, [variable.to_owned().into()]),
+                instruction(OpCode::Pop, []),
+            ]
+        };
+        let program = program([
+            (
+                "Start",
+                [store("$unused").as_slice(), &store("$used")].concat(),
+            ),
+            (
+                "Other",
+                vec![instruction(
+                    OpCode::PushVariable,
+                    ["$used".to_owned().into()],
+                )],
+            ),
+        ]);
+
+        let diagnoses = analyse(UnusedVariableChecker::new(), &program);
+
+        assert_eq!(
+            vec![Diagnosis::new(
+                DiagnosisSeverity::Warning,
+                "Variable $unused is assigned, but never read from".to_owned()
+            )
+            .with_node_name("Start")],
+            diagnoses
+        );
+    }
 }
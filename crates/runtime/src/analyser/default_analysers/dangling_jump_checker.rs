@@ -0,0 +1,50 @@
+use super::node_flow::NodeFlow;
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use yarnspinner_core::prelude::*;
+
+/// Reports a [`DiagnosisSeverity::Error`] for every jump to a node that does not exist, like `<<jump Nowhere>>`.
+/// Jumps to nodes whose name is only known at runtime are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct DanglingJumpChecker {
+    flows: BTreeMap<String, NodeFlow>,
+}
+
+impl DanglingJumpChecker {
+    /// Creates a new [`DanglingJumpChecker`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CompiledProgramAnalyser for DanglingJumpChecker {
+    fn diagnose(&mut self, program: &Program) {
+        self.flows.extend(
+            program
+                .nodes
+                .iter()
+                .map(|(name, node)| (name.clone(), NodeFlow::new(node))),
+        );
+    }
+
+    fn collect_diagnoses(&self) -> Vec<Diagnosis> {
+        self.flows
+            .iter()
+            .flat_map(|(name, flow)| {
+                let unique_targets: HashSet<_> = flow.jump_targets.iter().collect();
+                let mut missing_targets: Vec<_> = unique_targets
+                    .into_iter()
+                    .filter(|target| !self.flows.contains_key(*target))
+                    .collect();
+                missing_targets.sort();
+                missing_targets.into_iter().map(move |target| {
+                    Diagnosis::new(
+                        DiagnosisSeverity::Error,
+                        format!("Node {name} jumps to node {target}, which does not exist"),
+                    )
+                    .with_node_name(name)
+                })
+            })
+            .collect()
+    }
+}
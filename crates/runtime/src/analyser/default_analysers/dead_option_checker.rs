@@ -0,0 +1,45 @@
+use super::node_flow::options_with_constant_false_condition;
+use crate::prelude::*;
+use std::collections::BTreeMap;
+use yarnspinner_core::prelude::*;
+
+/// Reports a [`DiagnosisSeverity::Warning`] for every option whose condition is always false, like `-> Never <<if false>>`,
+/// since such an option can never be selected.
+#[derive(Debug, Clone, Default)]
+pub struct DeadOptionChecker {
+    dead_options: BTreeMap<String, Vec<String>>,
+}
+
+impl DeadOptionChecker {
+    /// Creates a new [`DeadOptionChecker`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CompiledProgramAnalyser for DeadOptionChecker {
+    fn diagnose(&mut self, program: &Program) {
+        self.dead_options.extend(
+            program
+                .nodes
+                .iter()
+                .map(|(name, node)| (name.clone(), options_with_constant_false_condition(node)))
+                .filter(|(_, line_ids)| !line_ids.is_empty()),
+        );
+    }
+
+    fn collect_diagnoses(&self) -> Vec<Diagnosis> {
+        self.dead_options
+            .iter()
+            .flat_map(|(name, line_ids)| {
+                line_ids.iter().map(move |line_id| {
+                    Diagnosis::new(
+                        DiagnosisSeverity::Warning,
+                        format!("Option {line_id} has a condition that is always false, so it can never be selected"),
+                    )
+                    .with_node_name(name)
+                })
+            })
+            .collect()
+    }
+}
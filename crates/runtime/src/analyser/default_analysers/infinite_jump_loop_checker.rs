@@ -0,0 +1,89 @@
+use super::node_flow::NodeFlow;
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use yarnspinner_core::prelude::*;
+
+/// Reports a [`DiagnosisSeverity::Error`] for every node that, once entered, can only keep jumping between nodes forever
+/// without ever presenting a line, options or a command. Running such a node hangs the [`Dialogue`].
+///
+/// Example:
+/// ```text
+/// title: Ping
+/// ---
+/// <<jump Pong>>
+/// ===
+/// title: Pong
+/// ---
+/// <<jump Ping>>
+/// ===
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InfiniteJumpLoopChecker {
+    flows: BTreeMap<String, NodeFlow>,
+}
+
+impl InfiniteJumpLoopChecker {
+    /// Creates a new [`InfiniteJumpLoopChecker`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CompiledProgramAnalyser for InfiniteJumpLoopChecker {
+    fn diagnose(&mut self, program: &Program) {
+        self.flows.extend(
+            program
+                .nodes
+                .iter()
+                .map(|(name, node)| (name.clone(), NodeFlow::new(node))),
+        );
+    }
+
+    fn collect_diagnoses(&self) -> Vec<Diagnosis> {
+        // A node escapes if some path through it presents content or leaves the dialogue,
+        // either directly or by silently jumping to a node that escapes.
+        // Jumps to missing nodes escape as well, since they lead to an error instead of a loop.
+        let mut escaping: HashSet<&str> = self
+            .flows
+            .iter()
+            .filter(|(_, flow)| {
+                flow.can_exit_without_silent_jump
+                    || flow
+                        .silent_jump_targets
+                        .iter()
+                        .any(|target| !self.flows.contains_key(target))
+            })
+            .map(|(name, _)| name.as_str())
+            .collect();
+        loop {
+            let newly_escaping: Vec<_> = self
+                .flows
+                .iter()
+                .filter(|(name, flow)| {
+                    !escaping.contains(name.as_str())
+                        && flow
+                            .silent_jump_targets
+                            .iter()
+                            .any(|target| escaping.contains(target.as_str()))
+                })
+                .map(|(name, _)| name.as_str())
+                .collect();
+            if newly_escaping.is_empty() {
+                break;
+            }
+            escaping.extend(newly_escaping);
+        }
+
+        self.flows
+            .keys()
+            .filter(|name| !escaping.contains(name.as_str()))
+            .map(|name| {
+                Diagnosis::new(
+                    DiagnosisSeverity::Error,
+                    format!("Node {name} can only end up in an infinite loop of jumps that never presents any content"),
+                )
+                .with_node_name(name)
+            })
+            .collect()
+    }
+}
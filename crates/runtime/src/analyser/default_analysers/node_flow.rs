@@ -0,0 +1,80 @@
+//! Helpers for the analysers that need to know how execution moves through and between nodes.
+
+use crate::analyser::{NodeWalker, StackValue, WalkStep};
+use yarnspinner_core::prelude::*;
+
+/// How the execution of a node can continue, as far as can be told without running it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NodeFlow {
+    /// The names of all nodes this node jumps to with a constant target, like `<<jump NodeName>>`.
+    pub(crate) jump_targets: Vec<String>,
+    /// Whether this node jumps to a node whose name is only known at runtime, like `<<jump {$destination}>>`.
+    pub(crate) has_dynamic_jump: bool,
+    /// The names of the nodes this node can jump to before presenting any content.
+    pub(crate) silent_jump_targets: Vec<String>,
+    /// Whether there is a path through this node that presents content, i.e. a line, options or a command,
+    /// or that ends the dialogue or leaves it to a jump target that is only known at runtime.
+    pub(crate) can_exit_without_silent_jump: bool,
+}
+
+impl NodeFlow {
+    pub(crate) fn new(node: &Node) -> Self {
+        let walker = NodeWalker::new(node);
+        let mut flow = Self::default();
+        for (index, instruction) in node.instructions.iter().enumerate() {
+            if instruction.opcode() != OpCode::RunNode {
+                continue;
+            }
+            match walker.jump_target(index) {
+                Some(target) => flow.jump_targets.push(target.to_owned()),
+                None => flow.has_dynamic_jump = true,
+            }
+        }
+
+        walker.walk(0, |step| {
+            let index = match step {
+                WalkStep::Instruction { index, .. } => index,
+                // Running past the last instruction completes the dialogue
+                WalkStep::Exit { .. } => {
+                    flow.can_exit_without_silent_jump = true;
+                    return false;
+                }
+            };
+            match node.instructions[index].opcode() {
+                OpCode::RunLine
+                | OpCode::RunCommand
+                | OpCode::AddOption
+                | OpCode::ShowOptions
+                | OpCode::Jump
+                | OpCode::Stop => {
+                    flow.can_exit_without_silent_jump = true;
+                    false
+                }
+                OpCode::RunNode => {
+                    match walker.jump_target(index) {
+                        Some(target) => flow.silent_jump_targets.push(target.to_owned()),
+                        None => flow.can_exit_without_silent_jump = true,
+                    }
+                    false
+                }
+                _ => true,
+            }
+        });
+        flow
+    }
+}
+
+/// Returns the line IDs of all options in the node whose condition is the constant `false`, e.g. `-> Never <<if false>>`.
+pub(crate) fn options_with_constant_false_condition(node: &Node) -> Vec<String> {
+    let walker = NodeWalker::new(node);
+    node.instructions
+        .iter()
+        .enumerate()
+        .filter(|(index, instruction)| {
+            instruction.opcode() == OpCode::AddOption
+                && walker.value_at(*index)
+                    == Some(&StackValue::Constant(OperandValue::BoolValue(false)))
+        })
+        .filter_map(|(_, instruction)| instruction.operands.first().cloned()?.try_into().ok())
+        .collect()
+}
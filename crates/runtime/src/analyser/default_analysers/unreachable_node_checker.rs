@@ -0,0 +1,88 @@
+use super::node_flow::NodeFlow;
+use crate::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use yarnspinner_core::prelude::*;
+
+/// Reports a [`DiagnosisSeverity::Warning`] for every node that cannot be reached by jumping from one of the entry nodes.
+/// Entry nodes are the nodes your game starts dialogue at with [`Dialogue::set_node`]. By default, this is only `Start`.
+///
+/// If a reachable node jumps to a node whose name is only known at runtime, like `<<jump {$destination}>>`,
+/// no diagnoses are reported, since any node might be reachable.
+///
+/// Not part of [`Context::default_analysers`], since it needs to know your entry nodes. Add it with [`Context::add_analyser`]:
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// let context = Context::default_analysers()
+///     .add_analyser(Box::new(UnreachableNodeChecker::new().with_entry_nodes(["Start", "Shop"])));
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnreachableNodeChecker {
+    entry_nodes: Vec<String>,
+    flows: BTreeMap<String, NodeFlow>,
+}
+
+impl Default for UnreachableNodeChecker {
+    fn default() -> Self {
+        Self {
+            entry_nodes: vec!["Start".to_owned()],
+            flows: Default::default(),
+        }
+    }
+}
+
+impl UnreachableNodeChecker {
+    /// Creates a new [`UnreachableNodeChecker`] whose only entry node is `Start`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the nodes that your game starts dialogue at, replacing the previous ones.
+    #[must_use]
+    pub fn with_entry_nodes(
+        mut self,
+        entry_nodes: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.entry_nodes = entry_nodes.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl CompiledProgramAnalyser for UnreachableNodeChecker {
+    fn diagnose(&mut self, program: &Program) {
+        self.flows.extend(
+            program
+                .nodes
+                .iter()
+                .map(|(name, node)| (name.clone(), NodeFlow::new(node))),
+        );
+    }
+
+    fn collect_diagnoses(&self) -> Vec<Diagnosis> {
+        let mut reachable = HashSet::new();
+        let mut pending: Vec<_> = self.entry_nodes.iter().map(String::as_str).collect();
+        while let Some(name) = pending.pop() {
+            let Some(flow) = self.flows.get(name) else {
+                continue;
+            };
+            if !reachable.insert(name) {
+                continue;
+            }
+            if flow.has_dynamic_jump {
+                return vec![];
+            }
+            pending.extend(flow.jump_targets.iter().map(String::as_str));
+        }
+        let entry_nodes = self.entry_nodes.join(", ");
+        self.flows
+            .keys()
+            .filter(|name| !reachable.contains(name.as_str()))
+            .map(|name| {
+                Diagnosis::new(
+                    DiagnosisSeverity::Warning,
+                    format!("Node {name} cannot be reached from the entry nodes ({entry_nodes})"),
+                )
+                .with_node_name(name)
+            })
+            .collect()
+    }
+}
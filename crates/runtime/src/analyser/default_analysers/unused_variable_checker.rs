@@ -2,13 +2,14 @@
 //! which was split into multiple files.
 
 use crate::prelude::*;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use yarnspinner_core::prelude::*;
 
 #[derive(Debug, Default)]
 pub(crate) struct UnusedVariableChecker {
     read_variables: HashSet<String>,
-    written_variables: HashSet<String>,
+    /// The names of the nodes that write to each variable.
+    written_variables: BTreeMap<String, BTreeSet<String>>,
 }
 
 impl UnusedVariableChecker {
@@ -29,15 +30,18 @@ impl CompiledProgramAnalyser for UnusedVariableChecker {
                     }
                     _ => None,
                 })
-                .map(|(opcode, operand)| (opcode, operand.try_into().unwrap()))
+                .map(|(opcode, operand)| (&node.name, opcode, operand.try_into().unwrap()))
         });
-        for (opcode, variable) in new_variables {
+        for (node_name, opcode, variable) in new_variables {
             match opcode {
                 OpCode::PushVariable => {
                     self.read_variables.insert(variable);
                 }
                 OpCode::StoreVariable => {
-                    self.written_variables.insert(variable);
+                    self.written_variables
+                        .entry(variable)
+                        .or_default()
+                        .insert(node_name.clone());
                 }
                 _ => unreachable!(),
             }
@@ -45,14 +49,18 @@ impl CompiledProgramAnalyser for UnusedVariableChecker {
     }
 
     fn collect_diagnoses(&self) -> Vec<Diagnosis> {
-        // Report the write-only variables
+        // Report the write-only variables, once for every node they are written in
         self.written_variables
-            .difference(&self.read_variables)
-            .map(|variable| {
-                Diagnosis::new(
-                    DiagnosisSeverity::Warning,
-                    format!("Variable {variable} is assigned, but never read from"),
-                )
+            .iter()
+            .filter(|(variable, _)| !self.read_variables.contains(*variable))
+            .flat_map(|(variable, node_names)| {
+                node_names.iter().map(move |node_name| {
+                    Diagnosis::new(
+                        DiagnosisSeverity::Warning,
+                        format!("Variable {variable} is assigned, but never read from"),
+                    )
+                    .with_node_name(node_name)
+                })
             })
             .collect()
     }
@@ -0,0 +1,285 @@
+//! Follows the compiled instructions of a node without running them.
+
+use std::collections::HashSet;
+use yarnspinner_core::prelude::*;
+
+/// Follows the compiled instructions of a single [`Node`] without running them,
+/// reconstructing what the stack of the virtual machine holds and which paths execution can take.
+///
+/// This is the basis of the analysers that reason about how execution moves through and between nodes,
+/// and can be used to build tools like flow graphs on top of compiled programs.
+///
+/// The stack is reconstructed by following the instructions in order, ignoring jumps,
+/// which is exact for the code the compiler emits for expressions.
+#[derive(Debug, Clone)]
+pub struct NodeWalker<'a> {
+    node: &'a Node,
+    /// The value each instruction operates on, see [`NodeWalker::value_at`].
+    values: Vec<Option<StackValue>>,
+}
+
+/// A value on the stack of the virtual machine, reconstructed from the instructions that pushed it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StackValue {
+    /// A constant pushed by [`OpCode::PushString`], [`OpCode::PushFloat`] or [`OpCode::PushBool`].
+    Constant(OperandValue),
+    /// The `null` pushed by [`OpCode::PushNull`].
+    Null,
+    /// The value of the variable with the given name, like `$gold`.
+    Variable(String),
+    /// The return value of a function. Operators are called as functions named like `Number.Add`.
+    FunctionCall {
+        /// The name of the function.
+        name: String,
+        /// The values passed to the function.
+        parameters: Vec<StackValue>,
+    },
+    /// A value that is only known at runtime and not derived from other values, like the index of a selected option.
+    Unknown,
+}
+
+/// A condition of an `<<if>>` statement that decides whether a [`NodeWalker`] path is taken.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathCondition {
+    /// The value that is checked.
+    pub value: StackValue,
+    /// Whether the path is taken when the value is `true` rather than `false`.
+    pub holds: bool,
+}
+
+/// A point reached by [`NodeWalker::walk`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WalkStep<'b> {
+    /// The instruction at the given index is about to be executed.
+    Instruction {
+        /// The index of the instruction in [`Node::instructions`].
+        index: usize,
+        /// The conditions that hold on the way to the instruction, from the first to the last one checked.
+        conditions: &'b [PathCondition],
+    },
+    /// Execution leaves the node without a jump, by running past its last instruction or jumping to a label that does not exist.
+    Exit {
+        /// The conditions that hold on the way out of the node, from the first to the last one checked.
+        conditions: &'b [PathCondition],
+    },
+}
+
+impl<'a> NodeWalker<'a> {
+    /// Reconstructs the stack of the virtual machine for every instruction of the node.
+    pub fn new(node: &'a Node) -> Self {
+        let mut stack: Vec<StackValue> = Vec::new();
+        let pop = |stack: &mut Vec<StackValue>| stack.pop().unwrap_or(StackValue::Unknown);
+        let discard = |stack: &mut Vec<StackValue>, count: usize| {
+            stack.truncate(stack.len().saturating_sub(count));
+        };
+        let mut values = Vec::with_capacity(node.instructions.len());
+        for instruction in &node.instructions {
+            values.push(stack.last().cloned());
+            match instruction.opcode() {
+                OpCode::PushString | OpCode::PushFloat | OpCode::PushBool => {
+                    let value = instruction
+                        .operands
+                        .first()
+                        .and_then(|operand| operand.value.clone())
+                        .map_or(StackValue::Unknown, StackValue::Constant);
+                    stack.push(value);
+                }
+                OpCode::PushNull => stack.push(StackValue::Null),
+                OpCode::PushVariable => {
+                    let value =
+                        operand(instruction, 0).map_or(StackValue::Unknown, StackValue::Variable);
+                    stack.push(value);
+                }
+                OpCode::CallFunc => {
+                    let parameter_count = match pop(&mut stack) {
+                        StackValue::Constant(OperandValue::FloatValue(count)) => count as usize,
+                        _ => 0,
+                    };
+                    let parameters = stack.split_off(stack.len().saturating_sub(parameter_count));
+                    stack.push(StackValue::FunctionCall {
+                        name: operand(instruction, 0).unwrap_or_default(),
+                        parameters,
+                    });
+                }
+                OpCode::Pop | OpCode::RunNode => discard(&mut stack, 1),
+                OpCode::RunLine | OpCode::RunCommand => {
+                    discard(&mut stack, operand(instruction, 1).unwrap_or_default());
+                }
+                OpCode::AddOption => {
+                    discard(&mut stack, operand(instruction, 2).unwrap_or_default());
+                    // The condition is below the substitutions of the option's line
+                    let has_condition: bool = operand(instruction, 3).unwrap_or_default();
+                    *values.last_mut().unwrap() = has_condition.then(|| pop(&mut stack));
+                }
+                OpCode::ShowOptions => stack.push(StackValue::Unknown),
+                _ => {}
+            }
+        }
+        Self { node, values }
+    }
+
+    /// The node that is walked.
+    pub fn node(&self) -> &'a Node {
+        self.node
+    }
+
+    /// The value the instruction at the given index operates on:
+    /// the destination of an [`OpCode::RunNode`], the condition of an [`OpCode::JumpIfFalse`]
+    /// or the condition of an [`OpCode::AddOption`], if the option has one.
+    /// For other instructions, this is the value on top of the stack, if any.
+    pub fn value_at(&self, index: usize) -> Option<&StackValue> {
+        self.values.get(index)?.as_ref()
+    }
+
+    /// The name of the node that the [`OpCode::RunNode`] at the given index jumps to, if it is known without running the program,
+    /// like in `<<jump NodeName>>` as opposed to `<<jump {$destination}>>`.
+    pub fn jump_target(&self, index: usize) -> Option<&str> {
+        match self.value_at(index)? {
+            StackValue::Constant(OperandValue::StringValue(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The index of the instruction with the given label, if the node has it.
+    pub fn label_index(&self, label: &str) -> Option<usize> {
+        let index = self.node.labels.get(label)?;
+        usize::try_from(*index).ok()
+    }
+
+    /// Follows every path of execution from the instruction at the given index, calling `visit` for every [`WalkStep`] reached on the way.
+    /// A path is not followed any further when `visit` returns `false`.
+    ///
+    /// A path ends at instructions that hand control away from the node's code:
+    /// [`OpCode::ShowOptions`], [`OpCode::Stop`], [`OpCode::Jump`] and [`OpCode::RunNode`].
+    /// Every instruction is visited at most once, on the first path that reaches it.
+    pub fn walk(&self, start: usize, mut visit: impl FnMut(WalkStep<'_>) -> bool) {
+        let mut visited = HashSet::new();
+        let mut pending = vec![(start, Vec::<PathCondition>::new())];
+        while let Some((index, conditions)) = pending.pop() {
+            if !visited.insert(index) {
+                continue;
+            }
+            let Some(instruction) = self.node.instructions.get(index) else {
+                visit(WalkStep::Exit {
+                    conditions: &conditions,
+                });
+                continue;
+            };
+            let step = WalkStep::Instruction {
+                index,
+                conditions: &conditions,
+            };
+            if !visit(step) {
+                continue;
+            }
+            match instruction.opcode() {
+                OpCode::ShowOptions | OpCode::Stop | OpCode::Jump | OpCode::RunNode => {}
+                OpCode::JumpTo => match self.destination(instruction) {
+                    Some(destination) => pending.push((destination, conditions)),
+                    None => {
+                        visit(WalkStep::Exit {
+                            conditions: &conditions,
+                        });
+                    }
+                },
+                OpCode::JumpIfFalse => {
+                    let value = self.value_at(index).cloned().unwrap_or(StackValue::Unknown);
+                    let mut else_conditions = conditions.clone();
+                    else_conditions.push(PathCondition {
+                        value: value.clone(),
+                        holds: false,
+                    });
+                    match self.destination(instruction) {
+                        Some(destination) => pending.push((destination, else_conditions)),
+                        None => {
+                            visit(WalkStep::Exit {
+                                conditions: &else_conditions,
+                            });
+                        }
+                    }
+                    let mut then_conditions = conditions;
+                    then_conditions.push(PathCondition { value, holds: true });
+                    pending.push((index + 1, then_conditions));
+                }
+                _ => pending.push((index + 1, conditions)),
+            }
+        }
+    }
+
+    fn destination(&self, instruction: &Instruction) -> Option<usize> {
+        self.label_index(&operand::<String>(instruction, 0)?)
+    }
+}
+
+fn operand<T: TryFrom<Operand>>(instruction: &Instruction, index: usize) -> Option<T> {
+    let operand = instruction.operands.get(index)?.clone();
+    T::try_from(operand).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn follows_branches_with_their_conditions() {
+        let gold_is_enough = StackValue::FunctionCall {
+            name: "Number.GreaterThan".to_owned(),
+            parameters: vec![
+                StackValue::Variable("$gold".to_owned()),
+                StackValue::Constant(OperandValue::FloatValue(10.0)),
+            ],
+        };
+        let mut program = program([(
+            "Start",
+            [
+                &[
+                    instruction(OpCode::PushVariable, ["$gold".to_owned().into()]),
+                    instruction(OpCode::PushFloat, [10.0.into()]),
+                    instruction(OpCode::PushFloat, [2.0.into()]),
+                    instruction(OpCode::CallFunc, ["Number.GreaterThan".to_owned().into()]),
+                    instruction(OpCode::JumpIfFalse, ["poor".to_owned().into()]),
+                ],
+                jump("Shop").as_slice(),
+                &[line("line:poor")],
+            ]
+            .concat(),
+        )]);
+        let node = program.nodes.get_mut("Start").unwrap();
+        node.labels.insert("poor".to_owned(), 7);
+        let walker = NodeWalker::new(node);
+
+        let mut jumps = Vec::new();
+        let mut exits = Vec::new();
+        walker.walk(0, |step| {
+            match step {
+                WalkStep::Instruction { index, conditions } => {
+                    if let Some(target) = walker.jump_target(index) {
+                        jumps.push((target.to_owned(), conditions.to_vec()));
+                    }
+                }
+                WalkStep::Exit { conditions } => exits.push(conditions.to_vec()),
+            }
+            true
+        });
+
+        assert_eq!(Some(&gold_is_enough), walker.value_at(4));
+        assert_eq!(
+            vec![(
+                "Shop".to_owned(),
+                vec![PathCondition {
+                    value: gold_is_enough.clone(),
+                    holds: true,
+                }]
+            )],
+            jumps
+        );
+        assert_eq!(
+            vec![vec![PathCondition {
+                value: gold_is_enough,
+                holds: false,
+            }]],
+            exits
+        );
+    }
+}
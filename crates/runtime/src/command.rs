@@ -3,9 +3,14 @@
 //! ## Implementation notes
 //! The original delegates command parsing to the Unity plugin, but we think it's foundational enough to do it directly in the runtime.
 
+mod signature;
+
+pub use self::signature::*;
 use crate::markup::normalize;
 #[cfg(any(feature = "bevy", feature = "serde"))]
 use crate::prelude::*;
+use std::fmt::{self, Display};
+use std::ops::Range;
 use yarnspinner_core::prelude::YarnValue;
 
 /// A custom command found in a Yarn file within the `<<` and `>>` characters.
@@ -31,7 +36,8 @@ pub struct Command {
     ///
     /// ## Return value
     ///
-    /// The parameters are returned without underlying type information, so you will have to convert them using `YarnValue::try_into`.
+    /// The parameters are returned without underlying type information, so you will have to convert them using `YarnValue::try_into`
+    /// or check and convert them all at once with a [`CommandSignature`].
     pub parameters: Vec<YarnValue>,
 
     /// The raw, unprocessed command as it appeared in the Yarn file between the `<<` and `>>` characters.
@@ -61,6 +67,13 @@ impl Command {
     }
 }
 
+/// Renders the command the way it is written in Yarn, e.g. `<<set_sprite ship "happy">>`.
+impl Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<<{}>>", self.raw)
+    }
+}
+
 /// Splits input into a number of non-empty sub-strings, separated
 /// by whitespace, and grouping double-quoted strings into a single
 /// sub-string.
@@ -76,18 +89,48 @@ impl Command {
 /// - When inside a pair of double-quote characters, the string
 /// `\\` will be converted to `\`, and the string `\"` will be converted to `"`.
 fn split_command_text(input: &str) -> Vec<String> {
-    let input = normalize(input);
-    let mut chars = input.chars().peekable();
+    split_command_components(input)
+        .into_iter()
+        .map(|component| component.text)
+        .collect()
+}
+
+/// A sub-string of a command as found by [`split_command_components`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommandComponent {
+    /// The normalized text, without quotes and escapes.
+    pub(crate) text: String,
+    /// The byte range of the component in the command, including its quotes.
+    pub(crate) range: Range<usize>,
+    /// Whether the component was written as a double-quoted string.
+    pub(crate) is_quoted: bool,
+}
+
+impl CommandComponent {
+    fn new(text: String, range: Range<usize>, is_quoted: bool) -> Self {
+        Self {
+            text: normalize(&text),
+            range,
+            is_quoted,
+        }
+    }
+}
+
+/// Like [`split_command_text`], but keeps track of where each sub-string is in the input and whether it was quoted.
+pub(crate) fn split_command_components(input: &str) -> Vec<CommandComponent> {
+    let mut chars = input.char_indices().peekable();
     let mut results = Vec::new();
     let mut current_component = String::new();
-    while let Some(mut char) = chars.next() {
+    let mut start = 0;
+    while let Some((index, char)) = chars.next() {
         match char {
             _ if char.is_whitespace() => {
                 if !current_component.is_empty() {
                     // We've reached the end of a run of visible
                     // characters. Add this run to the result list and
                     // prepare for the next one.
-                    results.push(std::mem::take(&mut current_component));
+                    let text = std::mem::take(&mut current_component);
+                    results.push(CommandComponent::new(text, start..index, false));
                 } else {
                     // We encountered a whitespace character, but
                     // didn't have any characters queued up. Skip this
@@ -96,24 +139,28 @@ fn split_command_text(input: &str) -> Vec<String> {
             }
             '\"' => {
                 // We've entered a quoted string!
-                loop {
-                    char = match chars.next() {
-                        Some(c) => c,
-                        None => {
-                            // Oops, we ended the input while parsing a
-                            // quoted string! Dump our current word
-                            // immediately and return.
-                            results.push(current_component);
-                            return results;
-                        }
+                if current_component.is_empty() {
+                    start = index;
+                }
+                let end = loop {
+                    let Some((index, char)) = chars.next() else {
+                        // Oops, we ended the input while parsing a
+                        // quoted string! Dump our current word
+                        // immediately and return.
+                        results.push(CommandComponent::new(
+                            current_component,
+                            start..input.len(),
+                            true,
+                        ));
+                        return results;
                     };
                     match char {
                         '\\' => {
                             // Possibly an escaped character!
                             match chars.peek() {
-                                Some('\\') | Some('\"') => {
+                                Some((_, '\\' | '\"')) => {
                                     // It's an escaped character! Consume it and add it to the current component.
-                                    let next = chars.next().unwrap();
+                                    let (_, next) = chars.next().unwrap();
                                     current_component.push(next);
                                 }
                                 _ => {
@@ -125,23 +172,31 @@ fn split_command_text(input: &str) -> Vec<String> {
                         }
                         '\"' => {
                             // The end of a string!
-                            break;
+                            break index + char.len_utf8();
                         }
                         _ => {
                             // Any other character. Add it to the buffer.
                             current_component.push(char);
                         }
                     }
-                }
-                results.push(std::mem::take(&mut current_component));
+                };
+                let text = std::mem::take(&mut current_component);
+                results.push(CommandComponent::new(text, start..end, true));
             }
             _ => {
+                if current_component.is_empty() {
+                    start = index;
+                }
                 current_component.push(char);
             }
         }
     }
     if !current_component.is_empty() {
-        results.push(current_component);
+        results.push(CommandComponent::new(
+            current_component,
+            start..input.len(),
+            false,
+        ));
     }
     results
 }
@@ -151,6 +206,13 @@ mod tests {
     //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner-Unity/blob/5944b0e03d319303cd185b08140772a5804a2762/Tests/Runtime/DialogueRunnerTests/DialogueRunnerTests.cs#L465>
     use super::*;
 
+    #[test]
+    fn displays_command_as_written_in_yarn() {
+        let command = Command::parse("set_sprite ship \"very happy\"".to_owned());
+
+        assert_eq!("<<set_sprite ship \"very happy\">>", command.to_string());
+    }
+
     #[test]
     fn split_command_text_splits_text_correctly() {
         for (input, expected_components) in [
@@ -181,6 +243,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn split_command_components_tracks_ranges_and_quotes() {
+        let components = split_command_components("say \"hi \\\"you\\\"\" 12");
+
+        assert_eq!(
+            vec![
+                CommandComponent {
+                    text: "say".to_owned(),
+                    range: 0..3,
+                    is_quoted: false,
+                },
+                CommandComponent {
+                    text: "hi \"you\"".to_owned(),
+                    range: 4..16,
+                    is_quoted: true,
+                },
+                CommandComponent {
+                    text: "12".to_owned(),
+                    range: 17..19,
+                    is_quoted: false,
+                },
+            ],
+            components
+        );
+    }
+
     #[test]
     fn parses_command() {
         for (input, expected_command) in [
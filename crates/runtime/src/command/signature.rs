@@ -0,0 +1,472 @@
+//! Typed parameters for [`Command`]s, which are otherwise passed on as plain strings.
+
+use crate::command::split_command_components;
+use crate::prelude::Command;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::Range;
+use yarnspinner_core::prelude::YarnValue;
+
+/// The type a parameter of a [`CommandSignature`] is converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandParameterType {
+    /// Accepts any text. Quotes are removed, e.g. `"very happy"` becomes `very happy`.
+    String,
+    /// Accepts a number as written in Yarn, e.g. `12` or `-0.5`.
+    Number,
+    /// Accepts `true` or `false`, ignoring case.
+    Boolean,
+    /// Accepts anything. Unquoted booleans and numbers are converted to [`YarnValue::Boolean`] and [`YarnValue::Number`],
+    /// everything else is a [`YarnValue::String`]. Quote a parameter to keep it a string, e.g. `"12"`.
+    Any,
+}
+
+impl Display for CommandParameterType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String => write!(f, "string"),
+            Self::Number => write!(f, "number"),
+            Self::Boolean => write!(f, "boolean"),
+            Self::Any => write!(f, "any"),
+        }
+    }
+}
+
+/// A parameter of a [`CommandSignature`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommandParameter {
+    /// The name of the parameter, used in errors and by [`TypedCommand::get`].
+    pub name: String,
+    /// The type the parameter is converted to.
+    pub parameter_type: CommandParameterType,
+    /// Whether the parameter may be left out. Optional parameters can only be followed by other optional parameters.
+    pub is_optional: bool,
+}
+
+/// Describes the parameters of a [`Command`], so that they can be checked and converted to the right [`YarnValue`]s.
+///
+/// [`Command::parameters`] are always strings, e.g. `<<wait 2.5>>` has the parameter `"2.5"`.
+/// A signature turns these into the values the command expects and reports any mismatches as a [`CommandParseError`].
+/// This is mainly useful for engines without an integration that already does this, like the one of `bevy_yarnspinner`.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # use yarnspinner_core::prelude::*;
+/// let signature = CommandSignature::new("set_sprite")
+///     .with_parameter("character", CommandParameterType::String)
+///     .with_parameter("sprite", CommandParameterType::String)
+///     .with_optional_parameter("scale", CommandParameterType::Number);
+/// # let command = |raw: &str| Command { name: "set_sprite".to_owned(), parameters: vec![], raw: raw.to_owned() };
+///
+/// let typed_command = signature.parse(&command(r#"set_sprite ship "very happy" 1.5"#)).unwrap();
+/// assert_eq!(Some(&YarnValue::from("very happy")), typed_command.get("sprite"));
+/// assert_eq!(Some(&YarnValue::from(1.5)), typed_command.get("scale"));
+///
+/// let error = signature.parse(&command("set_sprite ship happy large")).unwrap_err();
+/// assert_eq!(Some(22..27), error.range());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CommandSignature {
+    /// The name of the command, e.g. `set_sprite` for `<<set_sprite ship "happy">>`.
+    pub name: String,
+    /// The parameters in the order they are passed to the command.
+    pub parameters: Vec<CommandParameter>,
+}
+
+impl CommandSignature {
+    /// Creates a signature for a command without any parameters.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Adds a parameter that must be passed to the command.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if an optional parameter was added before, since the parameters are matched by position.
+    pub fn with_parameter(
+        mut self,
+        name: impl Into<String>,
+        parameter_type: CommandParameterType,
+    ) -> Self {
+        let name = name.into();
+        assert!(
+            self.parameters.iter().all(|parameter| !parameter.is_optional),
+            "Cannot add the required parameter \"{name}\" to the command \"{}\" after an optional parameter.",
+            self.name
+        );
+        self.parameters.push(CommandParameter {
+            name,
+            parameter_type,
+            is_optional: false,
+        });
+        self
+    }
+
+    /// Adds a parameter that may be left out of the command.
+    pub fn with_optional_parameter(
+        mut self,
+        name: impl Into<String>,
+        parameter_type: CommandParameterType,
+    ) -> Self {
+        self.parameters.push(CommandParameter {
+            name: name.into(),
+            parameter_type,
+            is_optional: true,
+        });
+        self
+    }
+
+    /// Checks the parameters of the command against this signature and converts them to their types.
+    ///
+    /// The parameters are read from [`Command::raw`], so quotes and the positions in the errors are taken into account.
+    pub fn parse(&self, command: &Command) -> Result<TypedCommand, CommandParseError> {
+        let components = split_command_components(&command.raw);
+        let arguments = components.get(1..).unwrap_or_default();
+        if let Some(extra_argument) = arguments.get(self.parameters.len()) {
+            let last_argument = arguments.last().unwrap_or(extra_argument);
+            return Err(CommandParseError::TooManyParameters {
+                command: self.name.clone(),
+                expected: self.parameters.len(),
+                found: arguments.len(),
+                range: extra_argument.range.start..last_argument.range.end,
+            });
+        }
+
+        let mut parameters = Vec::with_capacity(self.parameters.len());
+        for (index, parameter) in self.parameters.iter().enumerate() {
+            let Some(argument) = arguments.get(index) else {
+                if parameter.is_optional {
+                    break;
+                }
+                return Err(CommandParseError::MissingParameter {
+                    command: self.name.clone(),
+                    parameter: parameter.name.clone(),
+                    position: command.raw.len(),
+                });
+            };
+            let value = convert(parameter.parameter_type, &argument.text, argument.is_quoted)
+                .ok_or_else(|| CommandParseError::InvalidParameter {
+                    command: self.name.clone(),
+                    parameter: parameter.name.clone(),
+                    expected: parameter.parameter_type,
+                    value: argument.text.clone(),
+                    range: argument.range.clone(),
+                })?;
+            parameters.push(value);
+        }
+
+        Ok(TypedCommand {
+            name: command.name.clone(),
+            parameter_names: self.parameters[..parameters.len()]
+                .iter()
+                .map(|parameter| parameter.name.clone())
+                .collect(),
+            parameters,
+            raw: command.raw.clone(),
+        })
+    }
+}
+
+fn convert(parameter_type: CommandParameterType, text: &str, is_quoted: bool) -> Option<YarnValue> {
+    match parameter_type {
+        CommandParameterType::String => Some(text.into()),
+        CommandParameterType::Number => parse_number(text).map(YarnValue::from),
+        CommandParameterType::Boolean => parse_boolean(text).map(YarnValue::from),
+        CommandParameterType::Any if is_quoted => Some(text.into()),
+        CommandParameterType::Any => Some(
+            parse_boolean(text)
+                .map(YarnValue::from)
+                .or_else(|| parse_number(text).map(YarnValue::from))
+                .unwrap_or_else(|| text.into()),
+        ),
+    }
+}
+
+fn parse_number(text: &str) -> Option<f32> {
+    text.parse().ok().filter(|number: &f32| number.is_finite())
+}
+
+fn parse_boolean(text: &str) -> Option<bool> {
+    if text.eq_ignore_ascii_case("true") {
+        Some(true)
+    } else if text.eq_ignore_ascii_case("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// A [`Command`] whose parameters were checked and converted by a [`CommandSignature`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedCommand {
+    /// The command name, see [`Command::name`].
+    pub name: String,
+    /// The converted parameters in the order of the signature. Optional parameters that were left out are missing.
+    pub parameters: Vec<YarnValue>,
+    /// The raw, unprocessed command, see [`Command::raw`].
+    pub raw: String,
+    parameter_names: Vec<String>,
+}
+
+impl TypedCommand {
+    /// Gets a parameter by the name it has in the [`CommandSignature`].
+    /// Returns [`None`] for optional parameters that were left out.
+    #[must_use]
+    pub fn get(&self, parameter: &str) -> Option<&YarnValue> {
+        self.parameter_names
+            .iter()
+            .position(|name| name == parameter)
+            .map(|index| &self.parameters[index])
+    }
+}
+
+/// A set of [`CommandSignature`]s, used to parse all commands of a dialogue in one place.
+///
+/// Commands without a signature are reported as [`CommandParseError::UnknownCommand`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandSignatures(HashMap<String, CommandSignature>);
+
+impl CommandSignatures {
+    /// Creates an empty set of signatures.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a signature. Overwrites any signature for a command of the same name.
+    pub fn add(&mut self, signature: CommandSignature) -> &mut Self {
+        self.0.insert(signature.name.clone(), signature);
+        self
+    }
+
+    /// Registers a signature, see [`CommandSignatures::add`].
+    pub fn with(mut self, signature: CommandSignature) -> Self {
+        self.add(signature);
+        self
+    }
+
+    /// Gets the signature of a command by name.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&CommandSignature> {
+        self.0.get(name)
+    }
+
+    /// Iterates over all registered signatures.
+    pub fn iter(&self) -> impl Iterator<Item = &CommandSignature> {
+        self.0.values()
+    }
+
+    /// Parses a command with the signature registered for its name, see [`CommandSignature::parse`].
+    pub fn parse(&self, command: &Command) -> Result<TypedCommand, CommandParseError> {
+        self.get(&command.name)
+            .ok_or_else(|| CommandParseError::UnknownCommand {
+                command: command.name.clone(),
+            })?
+            .parse(command)
+    }
+}
+
+/// An error that occurred while parsing a [`Command`] with a [`CommandSignature`].
+///
+/// Positions are byte offsets into [`Command::raw`], see [`CommandParseError::range`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandParseError {
+    /// No [`CommandSignature`] was registered for the command.
+    UnknownCommand {
+        /// The name of the command.
+        command: String,
+    },
+    /// The command has fewer parameters than its signature requires.
+    MissingParameter {
+        /// The name of the command.
+        command: String,
+        /// The name of the first missing parameter.
+        parameter: String,
+        /// The end of the command, where the parameter is missing.
+        position: usize,
+    },
+    /// The command has more parameters than its signature allows.
+    TooManyParameters {
+        /// The name of the command.
+        command: String,
+        /// The maximum number of parameters of the signature.
+        expected: usize,
+        /// The number of parameters of the command.
+        found: usize,
+        /// Spans all parameters that are too many.
+        range: Range<usize>,
+    },
+    /// A parameter cannot be converted to the type its signature declares.
+    InvalidParameter {
+        /// The name of the command.
+        command: String,
+        /// The name of the parameter.
+        parameter: String,
+        /// The type the signature declares for the parameter.
+        expected: CommandParameterType,
+        /// The text of the parameter, without quotes.
+        value: String,
+        /// Spans the parameter, including its quotes.
+        range: Range<usize>,
+    },
+}
+
+impl CommandParseError {
+    /// The byte range in [`Command::raw`] the error refers to. Empty for missing parameters.
+    /// Returns [`None`] if the error concerns the whole command.
+    #[must_use]
+    pub fn range(&self) -> Option<Range<usize>> {
+        match self {
+            Self::UnknownCommand { .. } => None,
+            Self::MissingParameter { position, .. } => Some(*position..*position),
+            Self::TooManyParameters { range, .. } | Self::InvalidParameter { range, .. } => {
+                Some(range.clone())
+            }
+        }
+    }
+}
+
+impl Error for CommandParseError {}
+
+impl Display for CommandParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use CommandParseError::*;
+        match self {
+            UnknownCommand { command } => {
+                write!(f, "No signature was registered for the command \"{command}\"")
+            }
+            MissingParameter {
+                command, parameter, ..
+            } => write!(
+                f,
+                "The command \"{command}\" is missing the parameter \"{parameter}\""
+            ),
+            TooManyParameters {
+                command,
+                expected,
+                found,
+                ..
+            } => write!(
+                f,
+                "The command \"{command}\" expects at most {expected} parameters, but got {found}"
+            ),
+            InvalidParameter {
+                command,
+                parameter,
+                expected,
+                value,
+                range,
+            } => write!(
+                f,
+                "The parameter \"{parameter}\" of the command \"{command}\" expects a {expected}, but got \"{value}\" at {}..{}",
+                range.start, range.end
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(raw: &str) -> Command {
+        Command::parse(raw.to_owned())
+    }
+
+    fn signature() -> CommandSignature {
+        CommandSignature::new("move")
+            .with_parameter("character", CommandParameterType::String)
+            .with_parameter("distance", CommandParameterType::Number)
+            .with_optional_parameter("run", CommandParameterType::Boolean)
+    }
+
+    #[test]
+    fn converts_parameters_to_their_types() {
+        let typed_command = signature()
+            .parse(&command(r#"move "Mae \"the cat\"" -2.5 TRUE"#))
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                YarnValue::from("Mae \"the cat\""),
+                YarnValue::from(-2.5),
+                YarnValue::from(true)
+            ],
+            typed_command.parameters
+        );
+        assert_eq!(Some(&YarnValue::from(-2.5)), typed_command.get("distance"));
+    }
+
+    #[test]
+    fn leaves_out_optional_parameters() {
+        let typed_command = signature().parse(&command("move Mae 3")).unwrap();
+
+        assert_eq!(2, typed_command.parameters.len());
+        assert_eq!(None, typed_command.get("run"));
+    }
+
+    #[test]
+    fn infers_types_of_any_parameters_unless_quoted() {
+        let signature = CommandSignature::new("log")
+            .with_parameter("a", CommandParameterType::Any)
+            .with_parameter("b", CommandParameterType::Any)
+            .with_parameter("c", CommandParameterType::Any)
+            .with_parameter("d", CommandParameterType::Any);
+
+        let typed_command = signature
+            .parse(&command(r#"log 12 false "12" hello"#))
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                YarnValue::from(12.0),
+                YarnValue::from(false),
+                YarnValue::from("12"),
+                YarnValue::from("hello")
+            ],
+            typed_command.parameters
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_positions() {
+        let invalid = signature().parse(&command(r#"move Mae "far""#));
+        assert_eq!(
+            Err(CommandParseError::InvalidParameter {
+                command: "move".to_owned(),
+                parameter: "distance".to_owned(),
+                expected: CommandParameterType::Number,
+                value: "far".to_owned(),
+                range: 9..14,
+            }),
+            invalid
+        );
+
+        let missing = signature().parse(&command("move Mae")).unwrap_err();
+        assert_eq!(Some(8..8), missing.range());
+
+        let too_many = signature()
+            .parse(&command("move Mae 1 true now please"))
+            .unwrap_err();
+        assert_eq!(Some(16..26), too_many.range());
+    }
+
+    #[test]
+    fn parses_commands_by_name() {
+        let signatures = CommandSignatures::new().with(signature());
+
+        assert!(signatures.parse(&command("move Mae 1")).is_ok());
+        assert_eq!(
+            Err(CommandParseError::UnknownCommand {
+                command: "jump".to_owned()
+            }),
+            signatures.parse(&command("jump"))
+        );
+    }
+}
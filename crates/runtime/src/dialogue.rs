@@ -1,9 +1,11 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/Dialogue.cs>
 
-use crate::markup::{DialogueTextProcessor, LineParser, MarkupParseError};
+use crate::markup::{
+    AttributeMarkerProcessor, DialogueTextProcessor, LineParser, MarkupParseError,
+};
 use crate::prelude::*;
 use log::error;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::error::Error;
 use std::fmt::{self, Debug, Display};
 use yarnspinner_core::prelude::*;
@@ -15,6 +17,7 @@ use yarnspinner_core::prelude::*;
 pub struct Dialogue {
     vm: VirtualMachine,
     language_code: Option<Language>,
+    history: DialogueHistory,
 }
 
 #[allow(missing_docs)]
@@ -44,6 +47,9 @@ pub enum DialogueError {
         function_name: String,
         library: Library,
     },
+    HistoryEntryNotFound {
+        id: HistoryEntryId,
+    },
 }
 
 impl Error for DialogueError {
@@ -71,6 +77,7 @@ impl Display for DialogueError {
             InvalidNode { node_name } => write!(f, "No node named \"{node_name}\" has been loaded."),
             VariableStorageError(e) => Display::fmt(e, f),
             FunctionNotFound { function_name, library } => write!(f, "Function \"{function_name}\" not found in library: {library}"),
+            HistoryEntryNotFound { id } => write!(f, "Cannot rewind to {id:?}: The entry was never recorded, dropped because the history was full, or discarded by an earlier rewind."),
         }
     }
 }
@@ -112,6 +119,7 @@ impl Dialogue {
         Self {
             vm: VirtualMachine::new(library, variable_storage, line_parser, text_provider),
             language_code: Default::default(),
+            history: Default::default(),
         }
     }
 }
@@ -144,7 +152,9 @@ impl Iterator for Dialogue {
     /// Panicking version of [`Dialogue::continue_`].
     #[must_use = "All dialogue events that are returned by the dialogue must be handled or explicitly ignored"]
     fn next(&mut self) -> Option<Self::Item> {
-        self.vm.next()
+        let events = self.vm.next()?;
+        self.record_line_in_history(&events);
+        Some(events)
     }
 }
 
@@ -177,6 +187,38 @@ impl Dialogue {
         std::mem::replace(&mut self.language_code, language_code)
     }
 
+    /// Returns the languages of the [`TextProvider`] that have plural data, i.e. for which the `plural` and `ordinal` markers work.
+    /// This is a good starting point for a language selection menu.
+    ///
+    /// See [`TextProvider::available_languages`] for which languages are considered and [`Language::has_plural_data`] for which have plural data.
+    #[must_use]
+    pub fn languages_with_plural_data(&self) -> Vec<Language> {
+        self.text_provider()
+            .available_languages()
+            .into_iter()
+            .filter(Language::has_plural_data)
+            .collect()
+    }
+
+    /// Registers a processor that produces replacement text for the marker with the given name, e.g. `keybind` for `[keybind action=jump/]`.
+    /// This is how the built-in `select`, `plural` and `ordinal` markers are implemented.
+    ///
+    /// The processor immediately receives the current [`Dialogue::language_code`] and is notified whenever it changes,
+    /// so it can produce localized text. Replaces the processor previously registered for that name, including the built-in ones.
+    ///
+    /// See [`AttributeMarkerProcessor`] for an example.
+    pub fn register_markup_processor(
+        &mut self,
+        name: impl Into<String>,
+        mut processor: Box<dyn AttributeMarkerProcessor>,
+    ) -> &mut Self {
+        processor.set_language_code(self.language_code.clone());
+        self.vm
+            .line_parser_mut()
+            .insert_marker_processor(name, processor);
+        self
+    }
+
     /// Gets the [`Library`] that this Dialogue uses to locate functions.
     ///
     /// When the Dialogue is constructed, the Library is initialized with
@@ -206,6 +248,20 @@ impl Dialogue {
         self
     }
 
+    /// Gets whether the [`Dialogue`] keeps track of which instructions it executed, see [`Dialogue::pop_executed_instructions`].
+    /// The default is `false`.
+    #[must_use]
+    pub fn instruction_tracking_enabled(&self) -> bool {
+        self.vm.instruction_tracking_enabled
+    }
+
+    /// Mutable gets whether the [`Dialogue`] keeps track of which instructions it executed, see [`Dialogue::pop_executed_instructions`].
+    /// The default is `false`.
+    pub fn set_instruction_tracking_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.vm.instruction_tracking_enabled = enabled;
+        self
+    }
+
     /// Gets the currently registered [`TextProvider`].
     pub fn text_provider(&self) -> &dyn TextProvider {
         self.vm.text_provider()
@@ -225,6 +281,59 @@ impl Dialogue {
     pub fn variable_storage_mut(&mut self) -> &mut dyn VariableStorage {
         self.vm.variable_storage_mut()
     }
+
+    /// Gets how many entries the [`Dialogue::history`] keeps.
+    /// The default is `0`, which means that no history is recorded.
+    #[must_use]
+    pub fn history_capacity(&self) -> usize {
+        self.history.capacity()
+    }
+
+    /// Sets how many entries the [`Dialogue::history`] keeps. If it currently holds more, the oldest ones are dropped.
+    /// The default is `0`, which means that no history is recorded.
+    pub fn set_history_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.history.set_capacity(capacity);
+        self
+    }
+
+    /// Gets the lines and option selections delivered so far, which can be rewound to with [`Dialogue::rewind_to`].
+    /// Only recorded if [`Dialogue::history_capacity`] is greater than `0`.
+    #[must_use]
+    pub fn history(&self) -> &DialogueHistory {
+        &self.history
+    }
+
+    /// Returns `true` if the line has already been delivered, see [`Line::is_seen`].
+    #[must_use]
+    pub fn seen(&self, line_id: &LineId) -> bool {
+        self.vm.seen_lines.contains(line_id)
+    }
+
+    /// Gets the IDs of all lines delivered so far. These are not reset by [`Dialogue::set_node`] or [`Dialogue::stop`],
+    /// so persist them alongside the player's profile to keep them across sessions.
+    #[must_use]
+    pub fn seen_lines(&self) -> &SeenLines {
+        &self.vm.seen_lines
+    }
+
+    /// Mutable gets the IDs of all lines delivered so far, e.g. to restore them when loading a profile.
+    pub fn seen_lines_mut(&mut self) -> &mut SeenLines {
+        &mut self.vm.seen_lines
+    }
+
+    /// Adds the `seen_line` function to the [`Library`], which returns whether the line with the given ID was already delivered,
+    /// e.g. `<<if seen_line("line:intro")>>`. Not added by default so that it cannot clash with a function of the same name in existing projects.
+    pub fn add_seen_line_function(&mut self) -> &mut Self {
+        let seen_line = self.vm.seen_lines.seen_line_function();
+        self.library_mut().add_function("seen_line", seen_line);
+        self
+    }
+
+    /// Drops all entries of the [`Dialogue::history`], e.g. when loading a save game.
+    pub fn clear_history(&mut self) -> &mut Self {
+        self.history.clear();
+        self
+    }
 }
 
 // VM proxy
@@ -248,15 +357,92 @@ impl Dialogue {
     /// Specifically, we cannot guarantee [`Send`] and [`Sync`] properly without a lot of [`std::sync::RwLock`] boilerplate. The original implementation
     /// also allows unsound parallel mutation of [`Dialogue`]'s state, which would result in a deadlock in our case.
     pub fn continue_(&mut self) -> Result<Vec<DialogueEvent>> {
-        self.vm.continue_()
+        let events = self.vm.continue_()?;
+        self.record_line_in_history(&events);
+        Ok(events)
+    }
+
+    fn record_line_in_history(&mut self, events: &[DialogueEvent]) {
+        if self.history.capacity() == 0 {
+            return;
+        }
+        // A line always ends the batch, so there is at most one per batch.
+        let line = events.iter().find_map(|event| match event {
+            DialogueEvent::Line(line) => Some(line.clone()),
+            _ => None,
+        });
+        if let Some(line) = line {
+            self.record_in_history(HistoryEntryKind::Line(line));
+        }
+    }
+
+    fn record_in_history(&mut self, kind: HistoryEntryKind) {
+        let variables = self.variable_storage().variables();
+        self.history.record(kind, self.vm.snapshot(), variables);
+    }
+
+    /// Moves the [`Dialogue`] back to the moment the given entry of the [`Dialogue::history`] was recorded:
+    /// The position in the program as well as the variables in the [`VariableStorage`] are restored, and all newer entries are discarded.
+    /// See [`HistoryEntryKind`] for where exactly the dialogue resumes when [`Dialogue::continue_`] is called next.
+    ///
+    /// Variables that did not exist yet at that moment are removed by clearing the [`VariableStorage`] and filling it again,
+    /// so games sharing the storage should re-read any values they cache.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the entry is no longer in the history, or if its node is no longer in the [`Program`].
+    /// Also returns an error if the [`VariableStorage`] fails to take the variables of that moment.
+    /// The dialogue then stays at its current position and keeps its history, but the storage may already have been cleared.
+    pub fn rewind_to(&mut self, id: HistoryEntryId) -> Result<&mut Self> {
+        let (snapshot, variables) = self
+            .history
+            .state_at(id)
+            .ok_or(DialogueError::HistoryEntryNotFound { id })?;
+        let current_snapshot = self.vm.snapshot();
+        self.vm.restore(snapshot)?;
+        if let Err(error) = self.restore_variables(&variables) {
+            // Going back to where the dialogue was a moment ago only fails if its node was removed from the program
+            self.vm.restore(current_snapshot)?;
+            return Err(error.into());
+        }
+        self.history.truncate_after(id, variables);
+        Ok(self)
+    }
+
+    fn restore_variables(
+        &mut self,
+        variables: &HashMap<String, YarnValue>,
+    ) -> std::result::Result<(), VariableStorageError> {
+        let current_variables = self.variable_storage().variables();
+        let variable_storage = self.variable_storage_mut();
+        if current_variables
+            .keys()
+            .any(|name| !variables.contains_key(name))
+        {
+            variable_storage.clear();
+            variable_storage.extend(variables.clone())
+        } else {
+            let changed_variables = variables
+                .iter()
+                .filter(|(name, value)| current_variables.get(*name) != Some(value))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            variable_storage.extend(changed_variables)
+        }
     }
 
     fn extend_variable_storage_from(&mut self, program: &Program) {
+        let variable_storage = self.variable_storage();
         let initial: HashMap<String, YarnValue> = program
             .initial_values
             .iter()
+            // Keep values that are already set, e.g. because they were loaded from a save game
+            .filter(|(k, _)| !variable_storage.contains(k))
             .map(|(k, v)| (k.clone(), v.clone().into()))
             .collect();
+        if initial.is_empty() {
+            return;
+        }
 
         // Extend the VariableStorage with the initial values from the program
         if let Err(e) = self.variable_storage_mut().extend(initial) {
@@ -267,7 +453,9 @@ impl Dialogue {
         }
     }
 
-    /// Sets or replaces the [`Dialogue`]'s current [`Program`]. The program is replaced, all current state is reset.
+    /// Sets or replaces the [`Dialogue`]'s current [`Program`]. The program is replaced and the state of the running dialogue is reset.
+    /// Variables already in the [`VariableStorage`] keep their values, e.g. after loading a save game or hot reloading the program.
+    /// All others are set to their initial values.
     pub fn replace_program(&mut self, program: Program) -> &mut Self {
         self.vm.program.replace(program.clone());
         self.vm.reset_state();
@@ -276,6 +464,7 @@ impl Dialogue {
     }
 
     /// Merges the currently set [`Program`] with the given one. If there is no program set, the given one is set.
+    /// Variables already in the [`VariableStorage`] keep their values, all others are set to their initial values.
     pub fn add_program(&mut self, program: Program) -> &mut Self {
         if let Some(existing_program) = self.vm.program.as_mut() {
             *existing_program =
@@ -315,6 +504,14 @@ impl Dialogue {
         self.vm.pop_line_hints()
     }
 
+    /// Returns the indices of all instructions that were executed since the last call, grouped by the name of the node they belong to.
+    /// This is the value of the program counter at the time an instruction ran, so it can be mapped back to the source via the compiler's debug info.
+    ///
+    /// Always empty if [`Dialogue::instruction_tracking_enabled`] is `false`.
+    pub fn pop_executed_instructions(&mut self) -> HashMap<String, BTreeSet<usize>> {
+        self.vm.pop_executed_instructions()
+    }
+
     /// Immediately stops the [`Dialogue`]
     ///
     /// Returns unfinished [`DialogueEvent`]s that should be handled by the caller. The last is guaranteed to be [`DialogueEvent::DialogueComplete`].
@@ -442,7 +639,14 @@ impl Dialogue {
     /// ## See Also
     /// - [`Dialogue::continue_`]
     pub fn set_selected_option(&mut self, selected_option_id: OptionId) -> Result<&mut Self> {
+        let options = (self.history.capacity() > 0).then(|| self.vm.current_options().to_vec());
         self.vm.set_selected_option(selected_option_id)?;
+        if let Some(options) = options {
+            self.record_in_history(HistoryEntryKind::OptionSelected {
+                options,
+                selected_option_id,
+            });
+        }
         Ok(self)
     }
 
@@ -473,4 +677,95 @@ mod tests {
     }
 
     fn accept_send_sync(_: impl Send + Sync) {}
+
+    #[test]
+    fn keeps_existing_variables_when_loading_programs() {
+        let mut dialogue = Dialogue::new(
+            Box::new(MemoryVariableStorage::new()),
+            Box::new(StringTableTextProvider::new()),
+        );
+        dialogue
+            .variable_storage_mut()
+            .set("$gold".to_owned(), 5.0.into())
+            .unwrap();
+
+        dialogue.replace_program(Program {
+            initial_values: HashMap::from([
+                ("$gold".to_owned(), 0.0.into()),
+                ("$name".to_owned(), "Mae".to_owned().into()),
+            ]),
+            ..Default::default()
+        });
+        dialogue.add_program(Program {
+            initial_values: HashMap::from([
+                ("$gold".to_owned(), 100.0.into()),
+                ("$level".to_owned(), 1.0.into()),
+            ]),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            HashMap::from([
+                ("$gold".to_owned(), YarnValue::from(5.0)),
+                ("$name".to_owned(), YarnValue::from("Mae")),
+                ("$level".to_owned(), YarnValue::from(1.0)),
+            ]),
+            dialogue.variable_storage().variables()
+        );
+    }
+
+    #[test]
+    fn markup_processors_receive_language_code() {
+        let variable_storage = Box::new(MemoryVariableStorage::new());
+        let text_provider = Box::new(StringTableTextProvider::new());
+        let mut dialogue = Dialogue::new(variable_storage, text_provider);
+        dialogue.set_language_code(Language::from("de"));
+
+        dialogue.register_markup_processor("language", Box::new(LanguageProcessor::default()));
+        let text_after_registering = dialogue.vm.parse_markup("[language/]").unwrap().text;
+        dialogue.set_language_code(Language::from("fr"));
+        let text_after_changing = dialogue.vm.parse_markup("[language/]").unwrap().text;
+
+        assert_eq!("de", text_after_registering);
+        assert_eq!("fr", text_after_changing);
+    }
+
+    #[test]
+    fn reports_languages_with_plural_data() {
+        let mut text_provider = StringTableTextProvider::new();
+        for language in ["de-CH", "xx", "pt"] {
+            text_provider.extend_translation(language, StringTable::new());
+        }
+        let dialogue = Dialogue::new(
+            Box::new(MemoryVariableStorage::new()),
+            Box::new(text_provider),
+        );
+
+        let mut languages = dialogue.languages_with_plural_data();
+        languages.sort_by_key(ToString::to_string);
+
+        assert_eq!(vec![Language::new("de-CH"), Language::new("pt")], languages);
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct LanguageProcessor {
+        language_code: Option<Language>,
+    }
+
+    impl AttributeMarkerProcessor for LanguageProcessor {
+        fn replacement_text_for_marker(
+            &self,
+            _marker: &crate::markup::MarkupAttributeMarker,
+        ) -> String {
+            self.language_code.as_ref().unwrap().to_string()
+        }
+
+        fn set_language_code(&mut self, language_code: Option<Language>) {
+            self.language_code = language_code;
+        }
+
+        fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor> {
+            Box::new(self.clone())
+        }
+    }
 }
@@ -37,6 +37,17 @@ pub struct DialogueOption {
     pub is_available: bool,
 }
 
+/// Renders the option as the text of its line, followed by `[disabled]` if it is not available, e.g. `Buy a sword [disabled]`.
+impl Display for DialogueOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.line)?;
+        if !self.is_available {
+            write!(f, " [disabled]")?;
+        }
+        Ok(())
+    }
+}
+
 /// The identifying number for an option. You should not need to create these yourself, since you get them from [`DialogueOption`]s.
 ///
 /// Since the IDs are just zero-based indices, you can also derive them yourself. Note that the index numeration includes options which
@@ -0,0 +1,389 @@
+//! An asynchronous driver for [`Dialogue`], only available with the `async` feature.
+
+use crate::prelude::*;
+use crate::Result;
+use futures_core::{FusedStream, Stream};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+type OptionSelector = Box<dyn FnMut(Vec<DialogueOption>) -> BoxFuture<OptionId> + Send>;
+type AsyncCommandHandler = Box<dyn FnMut(Command) -> BoxFuture<()> + Send>;
+
+/// Runs a [`Dialogue`] as a [`Stream`] of [`DialogueEvent`]s, which takes care of calling [`Dialogue::continue_`].
+///
+/// The dialogue only continues when the next event is polled, so a consumer can take as long as it wants to present a line.
+/// Set the start node with [`Dialogue::set_node`] before creating the stream.
+///
+/// - [`DialogueEvent::Options`]: The option is chosen by the selector passed to [`DialogueStream::with_option_selector`],
+///   which is awaited before the dialogue continues. Without one, call [`DialogueStream::set_selected_option`] before polling the next event.
+/// - [`DialogueEvent::Command`]: The handler registered for the command with [`DialogueStream::with_command_handler`] is awaited
+///   before the dialogue continues. Commands without a handler don't hold up the dialogue.
+///
+/// The stream ends after [`DialogueEvent::DialogueComplete`]. It also ends if the dialogue returns an error,
+/// which can then be retrieved with [`DialogueStream::take_error`].
+///
+/// The stream does not depend on any particular async runtime.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::prelude::*;
+/// # use futures_lite::StreamExt;
+/// # let mut dialogue = Dialogue::new(Box::new(MemoryVariableStorage::new()), Box::new(StringTableTextProvider::new()));
+/// # dialogue.add_program(yarnspinner_core::prelude::Program::default());
+/// let mut stream = DialogueStream::new(dialogue)
+///     .with_option_selector(|options| async move { options[0].id })
+///     .with_command_handler("wait", |command| async move {
+///         // e.g. await a timer for as long as `command.parameters[0]` says
+///     });
+/// # futures_lite::future::block_on(async {
+/// while let Some(event) = stream.next().await {
+///     // present the event
+/// }
+/// # });
+/// ```
+pub struct DialogueStream {
+    dialogue: Dialogue,
+    option_selector: Option<OptionSelector>,
+    command_handlers: HashMap<String, AsyncCommandHandler>,
+    pending_events: VecDeque<DialogueEvent>,
+    /// The selector or command handler that must finish before the dialogue continues.
+    /// Outputs the option to select, if any.
+    awaited: Option<BoxFuture<Option<OptionId>>>,
+    error: Option<DialogueError>,
+    is_finished: bool,
+}
+
+impl Debug for DialogueStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DialogueStream")
+            .field("dialogue", &self.dialogue)
+            .field(
+                "option_selector",
+                &self.option_selector.as_ref().map(|_| "<OptionSelector>"),
+            )
+            .field(
+                "command_handlers",
+                &self.command_handlers.keys().collect::<Vec<_>>(),
+            )
+            .field("pending_events", &self.pending_events)
+            .field("awaited", &self.awaited.as_ref().map(|_| "<Future>"))
+            .field("error", &self.error)
+            .field("is_finished", &self.is_finished)
+            .finish()
+    }
+}
+
+impl DialogueStream {
+    /// Creates a stream that runs the dialogue from its current node.
+    pub fn new(dialogue: Dialogue) -> Self {
+        Self {
+            dialogue,
+            option_selector: None,
+            command_handlers: HashMap::new(),
+            pending_events: VecDeque::new(),
+            awaited: None,
+            error: None,
+            is_finished: false,
+        }
+    }
+
+    /// Sets the function that chooses an option whenever [`DialogueEvent::Options`] is emitted.
+    /// The returned future is awaited before the dialogue continues, so it can e.g. wait for the player's input.
+    pub fn with_option_selector<F, Fut>(mut self, mut option_selector: F) -> Self
+    where
+        F: FnMut(Vec<DialogueOption>) -> Fut + Send + 'static,
+        Fut: Future<Output = OptionId> + Send + 'static,
+    {
+        self.option_selector = Some(Box::new(move |options| Box::pin(option_selector(options))));
+        self
+    }
+
+    /// Registers a handler for the command with the given name, e.g. `wait` for `<<wait 2>>`.
+    /// The returned future is awaited before the dialogue continues. Overwrites any handler for a command of the same name.
+    pub fn with_command_handler<F, Fut>(
+        mut self,
+        name: impl Into<String>,
+        mut command_handler: F,
+    ) -> Self
+    where
+        F: FnMut(Command) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.command_handlers.insert(
+            name.into(),
+            Box::new(move |command| Box::pin(command_handler(command))),
+        );
+        self
+    }
+
+    /// Selects an option for the last [`DialogueEvent::Options`] if no option selector was set. See [`Dialogue::set_selected_option`].
+    pub fn set_selected_option(&mut self, selected_option_id: OptionId) -> Result<&mut Self> {
+        self.dialogue.set_selected_option(selected_option_id)?;
+        Ok(self)
+    }
+
+    /// Stops the dialogue like [`Dialogue::stop`]. A selector or command handler that is currently awaited is dropped.
+    ///
+    /// The stream then emits the unfinished events returned by [`Dialogue::stop`], ending with [`DialogueEvent::DialogueComplete`].
+    pub fn stop(&mut self) -> &mut Self {
+        self.awaited = None;
+        self.pending_events.clear();
+        self.pending_events.extend(self.dialogue.stop());
+        self.is_finished = false;
+        self
+    }
+
+    /// Takes the error that ended the stream, if any.
+    pub fn take_error(&mut self) -> Option<DialogueError> {
+        self.error.take()
+    }
+
+    /// Gets the [`Dialogue`] that is being run.
+    #[must_use]
+    pub fn dialogue(&self) -> &Dialogue {
+        &self.dialogue
+    }
+
+    /// Gets the [`Dialogue`] that is being run mutably, e.g. to access its [`VariableStorage`].
+    #[must_use]
+    pub fn dialogue_mut(&mut self) -> &mut Dialogue {
+        &mut self.dialogue
+    }
+
+    /// Returns the [`Dialogue`], dropping any selector or command handler that is currently awaited.
+    #[must_use]
+    pub fn into_dialogue(self) -> Dialogue {
+        self.dialogue
+    }
+
+    fn await_handler_for(&mut self, event: &DialogueEvent) {
+        match event {
+            DialogueEvent::Options(options) => {
+                if let Some(option_selector) = &mut self.option_selector {
+                    let selection = option_selector(options.clone());
+                    self.awaited = Some(Box::pin(async move { Some(selection.await) }));
+                }
+            }
+            DialogueEvent::Command(command) => {
+                if let Some(command_handler) = self.command_handlers.get_mut(&command.name) {
+                    let handling = command_handler(command.clone());
+                    self.awaited = Some(Box::pin(async move {
+                        handling.await;
+                        None
+                    }));
+                }
+            }
+            DialogueEvent::DialogueComplete => self.is_finished = true,
+            _ => {}
+        }
+    }
+
+    fn fail(&mut self, error: DialogueError) {
+        self.error = Some(error);
+        self.pending_events.clear();
+        self.is_finished = true;
+    }
+}
+
+impl Stream for DialogueStream {
+    type Item = DialogueEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(awaited) = &mut this.awaited {
+                let selected_option = ready!(awaited.as_mut().poll(cx));
+                this.awaited = None;
+                if let Some(selected_option) = selected_option {
+                    if let Err(error) = this.dialogue.set_selected_option(selected_option) {
+                        this.fail(error);
+                    }
+                }
+            }
+            if let Some(event) = this.pending_events.pop_front() {
+                this.await_handler_for(&event);
+                return Poll::Ready(Some(event));
+            }
+            if this.is_finished {
+                return Poll::Ready(None);
+            }
+            match this.dialogue.continue_() {
+                // Guard against looping forever should the dialogue have nothing to say
+                Ok(events) if events.is_empty() => this.is_finished = true,
+                Ok(events) => this.pending_events.extend(events),
+                Err(error) => this.fail(error),
+            }
+        }
+    }
+}
+
+impl FusedStream for DialogueStream {
+    fn is_terminated(&self) -> bool {
+        self.is_finished && self.pending_events.is_empty() && self.awaited.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use futures_lite::future::{block_on, pending, poll_once, yield_now};
+    use futures_lite::StreamExt;
+    use std::sync::{Arc, Mutex};
+
+    /// A dialogue equivalent to
+    /// ```yarn
+    /// Hello
+    /// -> Wait
+    ///     <<wait 2>>
+    /// -> Leave
+    /// Bye
+    /// ```
+    fn dialogue() -> Dialogue {
+        let mut program = program([(
+            "Start",
+            vec![
+                line("line:hello"),
+                option("line:wait", "wait"),
+                option("line:leave", "leave"),
+                instruction(OpCode::ShowOptions, []),
+                instruction(OpCode::Jump, []),
+                instruction(
+                    OpCode::RunCommand,
+                    ["wait 2".to_owned().into(), 0_usize.into()],
+                ),
+                line("line:bye"),
+                stop(),
+            ],
+        )]);
+        program.nodes.get_mut("Start").unwrap().labels =
+            HashMap::from([("wait".to_owned(), 5), ("leave".to_owned(), 6)]);
+        crate::test_utils::dialogue(
+            program,
+            [
+                ("line:hello", "Hello"),
+                ("line:wait", "Wait"),
+                ("line:leave", "Leave"),
+                ("line:bye", "Bye"),
+            ],
+        )
+    }
+
+    fn describe(event: &DialogueEvent) -> String {
+        match event {
+            DialogueEvent::Line(line) => line.text.clone(),
+            DialogueEvent::Options(options) => format!("{} options", options.len()),
+            DialogueEvent::Command(command) => format!("<<{}>>", command.raw),
+            DialogueEvent::NodeStart(node) => format!("start {node}"),
+            DialogueEvent::NodeComplete(node) => format!("complete {node}"),
+            DialogueEvent::DialogueComplete => "done".to_owned(),
+            other => format!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn awaits_option_selector_and_command_handlers() {
+        let handled_commands = Arc::new(Mutex::new(Vec::new()));
+        let handled = handled_commands.clone();
+        let stream = DialogueStream::new(dialogue())
+            .with_option_selector(|options| async move {
+                yield_now().await;
+                options[0].id
+            })
+            .with_command_handler("wait", move |command| {
+                let handled = handled.clone();
+                async move {
+                    yield_now().await;
+                    handled.lock().unwrap().push(command.parameters);
+                }
+            });
+
+        let events: Vec<_> = block_on(stream.map(|event| describe(&event)).collect());
+
+        assert_eq!(
+            vec![
+                "start Start",
+                "Hello",
+                "2 options",
+                "<<wait 2>>",
+                "Bye",
+                "complete Start",
+                "done"
+            ],
+            events
+        );
+        assert_eq!(
+            vec![vec![YarnValue::from("2")]],
+            *handled_commands.lock().unwrap()
+        );
+    }
+
+    #[test]
+    fn selects_options_between_events_without_selector() {
+        let mut stream = DialogueStream::new(dialogue());
+
+        let mut events = Vec::new();
+        block_on(async {
+            while let Some(event) = stream.next().await {
+                if let DialogueEvent::Options(options) = &event {
+                    stream.set_selected_option(options[1].id).unwrap();
+                }
+                events.push(describe(&event));
+            }
+        });
+
+        assert_eq!(
+            vec![
+                "start Start",
+                "Hello",
+                "2 options",
+                "Bye",
+                "complete Start",
+                "done"
+            ],
+            events
+        );
+        assert!(stream.is_terminated());
+        assert!(stream.take_error().is_none());
+    }
+
+    #[test]
+    fn ends_with_error_if_option_is_not_selected() {
+        let mut stream = DialogueStream::new(dialogue());
+
+        let events: Vec<_> = block_on((&mut stream).collect());
+
+        assert!(matches!(events.last(), Some(DialogueEvent::Options(_))));
+        assert!(matches!(
+            stream.take_error(),
+            Some(DialogueError::ContinueOnOptionSelectionError)
+        ));
+    }
+
+    #[test]
+    fn stopping_cancels_awaited_command_handler() {
+        let mut stream = DialogueStream::new(dialogue())
+            .with_option_selector(|options| async move { options[0].id })
+            .with_command_handler("wait", |_| pending());
+
+        block_on(async {
+            while let Some(event) = stream.next().await {
+                if matches!(event, DialogueEvent::Command(_)) {
+                    break;
+                }
+            }
+            assert_eq!(None, poll_once(stream.next()).await);
+
+            stream.stop();
+            let remaining: Vec<_> = (&mut stream).collect().await;
+
+            assert_eq!(vec![DialogueEvent::DialogueComplete], remaining);
+        });
+        assert!(!stream.dialogue().is_active());
+    }
+}
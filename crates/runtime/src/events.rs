@@ -45,6 +45,18 @@ pub enum DialogueEvent {
     ///
     /// Corresponds to Yarn Spinner's `PrepareForLinesHandler`
     LineHints(Vec<LineId>),
+    /// A variable was changed by the script, e.g. through `<<set $gold to 5>>`.
+    /// Only emitted if the new value differs from the old one.
+    /// Changes made directly through the [`VariableStorage`] and changes to the variables the dialogue uses internally,
+    /// like the ones starting with `$Yarn.Internal.` that track node visits for `visited()`, are not reported.
+    VariableChanged {
+        /// The name of the variable, including the leading `$`.
+        name: String,
+        /// The value before the change, or `None` if the variable had no value yet.
+        old: Option<YarnValue>,
+        /// The value after the change.
+        new: YarnValue,
+    },
     /// The dialogue was completed. Set it to a new node via [`Dialogue::set_node`] before calling [`Dialogue::continue_`] again.
     DialogueComplete,
 }
@@ -0,0 +1,442 @@
+//! A bounded backlog of the lines and option selections a [`Dialogue`] delivered, which can be rewound to, e.g. for visual novels.
+
+use crate::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+/// The lines and option selections a [`Dialogue`] delivered, oldest first.
+///
+/// Disabled by default. Enable it with [`Dialogue::set_history_capacity`], read it with [`Dialogue::history`]
+/// and go back to an earlier entry with [`Dialogue::rewind_to`].
+/// Once the capacity is reached, the oldest entry is dropped whenever a new one is recorded.
+#[derive(Debug, Clone, Default)]
+pub struct DialogueHistory {
+    capacity: usize,
+    entries: VecDeque<HistoryEntry>,
+    next_id: usize,
+    /// The variables at the time the newest entry was recorded.
+    variables: HashMap<String, YarnValue>,
+}
+
+/// Something the [`Dialogue`] delivered, along with what is needed to rewind to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// Identifies the entry for [`Dialogue::rewind_to`].
+    pub id: HistoryEntryId,
+    /// What was delivered.
+    pub kind: HistoryEntryKind,
+    /// The variables that changed between the previous entry and this one, whether by the script or by the game.
+    /// Always empty for the oldest entry in the history.
+    pub variable_changes: Vec<VariableChange>,
+    pub(crate) snapshot: Snapshot,
+}
+
+/// Identifies a [`HistoryEntry`]. IDs are handed out in increasing order and never reused by the same [`Dialogue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HistoryEntryId(pub usize);
+
+/// What a [`HistoryEntry`] records.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryEntryKind {
+    /// A line was delivered through [`DialogueEvent::Line`].
+    /// Rewinding to it resumes right after the line, so the game can show it again and call [`Dialogue::continue_`] when the player is done reading.
+    Line(Line),
+    /// An option was selected through [`Dialogue::set_selected_option`].
+    /// Rewinding to it resumes right after the selection. To choose differently, rewind to the line before the options instead.
+    OptionSelected {
+        /// The options that were presented.
+        options: Vec<DialogueOption>,
+        /// The option that was selected.
+        selected_option_id: OptionId,
+    },
+}
+
+/// A variable that changed in the [`VariableStorage`]. Mirrors [`DialogueEvent::VariableChanged`],
+/// except that the variable may also have been removed, e.g. by [`VariableStorage::clear`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableChange {
+    /// The name of the variable, including the leading `$`.
+    pub name: String,
+    /// The previous value, or [`None`] if the variable was not set.
+    pub old: Option<YarnValue>,
+    /// The new value, or [`None`] if the variable was removed.
+    pub new: Option<YarnValue>,
+}
+
+impl DialogueHistory {
+    /// The maximum number of entries kept. `0` means that the history is disabled.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of entries currently kept.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no entries are kept.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries, oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &HistoryEntry> + ExactSizeIterator {
+        self.entries.iter()
+    }
+
+    /// Gets the entry with the given ID, if it is still kept.
+    pub fn get(&self, id: HistoryEntryId) -> Option<&HistoryEntry> {
+        self.position(id).map(|index| &self.entries[index])
+    }
+
+    /// Gets the newest entry.
+    pub fn last(&self) -> Option<&HistoryEntry> {
+        self.entries.back()
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict();
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.variables.clear();
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        kind: HistoryEntryKind,
+        snapshot: Snapshot,
+        variables: HashMap<String, YarnValue>,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        // The changes leading up to the oldest entry are never undone, so there's no need to compute them.
+        let variable_changes = if self.entries.is_empty() {
+            Vec::new()
+        } else {
+            diff_variables(&self.variables, &variables)
+        };
+        self.entries.push_back(HistoryEntry {
+            id: HistoryEntryId(self.next_id),
+            kind,
+            variable_changes,
+            snapshot,
+        });
+        self.next_id += 1;
+        self.variables = variables;
+        self.evict();
+    }
+
+    /// Returns the snapshot of the entry and the variables at the time it was recorded.
+    pub(crate) fn state_at(
+        &self,
+        id: HistoryEntryId,
+    ) -> Option<(Snapshot, HashMap<String, YarnValue>)> {
+        let index = self.position(id)?;
+        let mut variables = self.variables.clone();
+        for change in self
+            .entries
+            .range(index + 1..)
+            .rev()
+            .flat_map(|entry| entry.variable_changes.iter())
+        {
+            match &change.old {
+                Some(old) => variables.insert(change.name.clone(), old.clone()),
+                None => variables.remove(&change.name),
+            };
+        }
+        Some((self.entries[index].snapshot.clone(), variables))
+    }
+
+    /// Drops all entries newer than the given one, which must have been rewound to with the given variables.
+    pub(crate) fn truncate_after(
+        &mut self,
+        id: HistoryEntryId,
+        variables: HashMap<String, YarnValue>,
+    ) {
+        if let Some(index) = self.position(id) {
+            self.entries.truncate(index + 1);
+            self.variables = variables;
+        }
+    }
+
+    fn position(&self, id: HistoryEntryId) -> Option<usize> {
+        self.entries
+            .binary_search_by_key(&id, |entry| entry.id)
+            .ok()
+    }
+
+    fn evict(&mut self) {
+        let excess = self.entries.len().saturating_sub(self.capacity);
+        self.entries.drain(..excess);
+        if let Some(oldest) = self.entries.front_mut() {
+            oldest.variable_changes.clear();
+        }
+        if self.entries.is_empty() {
+            self.variables.clear();
+        }
+    }
+}
+
+fn diff_variables(
+    old: &HashMap<String, YarnValue>,
+    new: &HashMap<String, YarnValue>,
+) -> Vec<VariableChange> {
+    let changed = new
+        .iter()
+        .filter(|(name, value)| old.get(*name) != Some(value))
+        .map(|(name, value)| VariableChange {
+            name: name.clone(),
+            old: old.get(name).cloned(),
+            new: Some(value.clone()),
+        });
+    let removed = old
+        .iter()
+        .filter(|(name, _)| !new.contains_key(*name))
+        .map(|(name, value)| VariableChange {
+            name: name.clone(),
+            old: Some(value.clone()),
+            new: None,
+        });
+    let mut changes: Vec<_> = changed.chain(removed).collect();
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use std::any::Any;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    /// A dialogue equivalent to
+    /// ```yarn
+    /// Hello
+    /// <<set $gold to 5>>
+    /// Here's some gold
+    /// -> Thanks
+    /// Bye
+    /// ```
+    fn dialogue() -> Dialogue {
+        dialogue_with_storage(MemoryVariableStorage::new())
+    }
+
+    fn dialogue_with_storage(variable_storage: impl VariableStorage + 'static) -> Dialogue {
+        let mut program = program([(
+            "Start",
+            vec![
+                line("line:hello"),
+                instruction(OpCode::PushFloat, [5.0_f32.into()]),
+                instruction(OpCode::StoreVariable, ["$gold".to_owned().into()]),
+                instruction(OpCode::Pop, []),
+                line("line:gold"),
+                option("line:thanks", "thanks"),
+                instruction(OpCode::ShowOptions, []),
+                instruction(OpCode::Jump, []),
+                line("line:bye"),
+                stop(),
+            ],
+        )]);
+        program.nodes.get_mut("Start").unwrap().labels = HashMap::from([("thanks".to_owned(), 8)]);
+        crate::test_utils::dialogue_with_storage(
+            program,
+            [
+                ("line:hello", "Hello"),
+                ("line:gold", "Here's some gold"),
+                ("line:thanks", "Thanks"),
+                ("line:bye", "Bye"),
+            ],
+            variable_storage,
+        )
+    }
+
+    /// Continues until the dialogue waits for input and returns the text of the delivered line, if any.
+    fn next_line(dialogue: &mut Dialogue) -> Option<String> {
+        dialogue
+            .continue_()
+            .unwrap()
+            .into_iter()
+            .find_map(|event| match event {
+                DialogueEvent::Line(line) => Some(line.text),
+                _ => None,
+            })
+    }
+
+    fn play_to_end(dialogue: &mut Dialogue) {
+        assert_eq!(Some("Hello"), next_line(dialogue).as_deref());
+        assert_eq!(Some("Here's some gold"), next_line(dialogue).as_deref());
+        assert_eq!(None, next_line(dialogue));
+        dialogue.set_selected_option(OptionId(0)).unwrap();
+        assert_eq!(Some("Bye"), next_line(dialogue).as_deref());
+    }
+
+    fn gold(dialogue: &Dialogue) -> Option<YarnValue> {
+        dialogue.variable_storage().get("$gold").ok()
+    }
+
+    #[test]
+    fn records_nothing_by_default() {
+        let mut dialogue = dialogue();
+
+        play_to_end(&mut dialogue);
+
+        assert!(dialogue.history().is_empty());
+    }
+
+    #[test]
+    fn records_lines_options_and_variable_changes() {
+        let mut dialogue = dialogue();
+        dialogue.set_history_capacity(10);
+
+        play_to_end(&mut dialogue);
+
+        let kinds: Vec<_> = dialogue
+            .history()
+            .iter()
+            .map(|entry| match &entry.kind {
+                HistoryEntryKind::Line(line) => line.text.clone(),
+                HistoryEntryKind::OptionSelected {
+                    options,
+                    selected_option_id,
+                } => format!("-> {}", options[selected_option_id.0].line.text),
+            })
+            .collect();
+        assert_eq!(vec!["Hello", "Here's some gold", "-> Thanks", "Bye"], kinds);
+        let gold_entry = dialogue.history().get(HistoryEntryId(1)).unwrap();
+        assert_eq!(
+            vec![VariableChange {
+                name: "$gold".to_owned(),
+                old: None,
+                new: Some(5.0.into()),
+            }],
+            gold_entry.variable_changes
+        );
+    }
+
+    #[test]
+    fn rewinds_position_and_variables() {
+        let mut dialogue = dialogue();
+        dialogue.set_history_capacity(10);
+        play_to_end(&mut dialogue);
+
+        dialogue.rewind_to(HistoryEntryId(0)).unwrap();
+
+        assert_eq!(None, gold(&dialogue));
+        assert_eq!(1, dialogue.history().len());
+        assert_eq!(
+            Some("Here's some gold"),
+            next_line(&mut dialogue).as_deref()
+        );
+        assert_eq!(Some(YarnValue::from(5.0)), gold(&dialogue));
+        assert_eq!(HistoryEntryId(4), dialogue.history().last().unwrap().id);
+    }
+
+    #[test]
+    fn rewinds_to_option_selection() {
+        let mut dialogue = dialogue();
+        dialogue.set_history_capacity(10);
+        play_to_end(&mut dialogue);
+        dialogue
+            .variable_storage_mut()
+            .set("$gold".to_owned(), 10.0.into())
+            .unwrap();
+
+        dialogue.rewind_to(HistoryEntryId(2)).unwrap();
+
+        assert_eq!(Some(YarnValue::from(5.0)), gold(&dialogue));
+        assert!(!dialogue.is_waiting_for_option_selection());
+        assert_eq!(Some("Bye"), next_line(&mut dialogue).as_deref());
+    }
+
+    #[test]
+    fn drops_oldest_entries_when_full() {
+        let mut dialogue = dialogue();
+        dialogue.set_history_capacity(2);
+        play_to_end(&mut dialogue);
+
+        let ids: Vec<_> = dialogue.history().iter().map(|entry| entry.id).collect();
+        assert_eq!(vec![HistoryEntryId(2), HistoryEntryId(3)], ids);
+        assert!(matches!(
+            dialogue.rewind_to(HistoryEntryId(1)),
+            Err(DialogueError::HistoryEntryNotFound { .. })
+        ));
+
+        dialogue.set_history_capacity(1);
+        assert_eq!(1, dialogue.history().len());
+    }
+
+    #[test]
+    fn stays_in_place_when_variables_cannot_be_rewound() {
+        let variable_storage = FailingVariableStorage::default();
+        let fail = variable_storage.fail.clone();
+        let mut dialogue = dialogue_with_storage(variable_storage);
+        dialogue.set_history_capacity(10);
+        assert_eq!(Some("Hello"), next_line(&mut dialogue).as_deref());
+        assert_eq!(
+            Some("Here's some gold"),
+            next_line(&mut dialogue).as_deref()
+        );
+
+        fail.store(true, Ordering::Relaxed);
+        let result = dialogue.rewind_to(HistoryEntryId(0));
+        fail.store(false, Ordering::Relaxed);
+
+        assert!(matches!(
+            result,
+            Err(DialogueError::VariableStorageError(_))
+        ));
+        assert_eq!(2, dialogue.history().len());
+        assert_eq!(None, next_line(&mut dialogue));
+        assert!(dialogue.is_waiting_for_option_selection());
+    }
+
+    /// A [`MemoryVariableStorage`] that refuses to be extended while `fail` is set.
+    #[derive(Debug, Clone, Default)]
+    struct FailingVariableStorage {
+        variables: MemoryVariableStorage,
+        fail: Arc<AtomicBool>,
+    }
+
+    impl VariableStorage for FailingVariableStorage {
+        fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+            Box::new(self.clone())
+        }
+
+        fn set(&mut self, name: String, value: YarnValue) -> Result<()> {
+            self.variables.set(name, value)
+        }
+
+        fn get(&self, name: &str) -> Result<YarnValue> {
+            self.variables.get(name)
+        }
+
+        fn extend(&mut self, values: HashMap<String, YarnValue>) -> Result<()> {
+            if self.fail.load(Ordering::Relaxed) {
+                return Err(VariableStorageError::InternalError {
+                    error: "the storage is read-only".into(),
+                });
+            }
+            self.variables.extend(values)
+        }
+
+        fn variables(&self) -> HashMap<String, YarnValue> {
+            self.variables.variables()
+        }
+
+        fn clear(&mut self) {
+            self.variables.clear()
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+    }
+}
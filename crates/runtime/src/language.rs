@@ -1,7 +1,9 @@
+use crate::pluralization::has_plural_data;
 #[cfg(any(feature = "bevy", feature = "serde"))]
 use crate::prelude::*;
-use core::fmt::Display;
+use core::fmt::{self, Display};
 use icu_locid::LanguageIdentifier;
+use std::error::Error;
 
 /// IETF BCP 47 code.
 /// The default is "en-US".
@@ -11,14 +13,76 @@ use icu_locid::LanguageIdentifier;
 pub struct Language(pub(crate) LanguageIdentifier);
 impl Language {
     /// Creates a new `Language` from a string. Panics if the string is not a valid IETF BCP 47 code.
+    ///
+    /// Use [`Language::try_new`] for codes that are not known at compile time, e.g. ones read from a settings file.
     pub fn new(language: impl Into<String>) -> Self {
         let language = language.into();
         Self(language.parse().unwrap())
     }
+
+    /// Creates a new `Language` from a string, checking that it is a valid IETF BCP 47 code
+    /// and that plural data for it is available, which the `plural` and `ordinal` markers need.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_runtime::prelude::*;
+    /// assert_eq!(Language::new("en-US"), Language::try_new("en_US").unwrap());
+    /// assert!(matches!(Language::try_new("en-US "), Err(LanguageError::InvalidCode { .. })));
+    /// assert!(matches!(Language::try_new("xx"), Err(LanguageError::NoPluralData { .. })));
+    /// ```
+    pub fn try_new(language: impl Into<String>) -> std::result::Result<Self, LanguageError> {
+        let code = language.into();
+        let language = match code.parse() {
+            Ok(language) => Self(language),
+            Err(error) => {
+                return Err(LanguageError::InvalidCode {
+                    code,
+                    message: error.to_string(),
+                })
+            }
+        };
+        if !language.has_plural_data() {
+            return Err(LanguageError::NoPluralData { language });
+        }
+        Ok(language)
+    }
+
+    /// Whether plural rules are compiled in for this language, e.g. `true` for `de-CH` because of `de`.
+    /// These come from ICU, or from a smaller table when the `compact_plurals` feature is enabled.
+    /// Without them, the `plural` and `ordinal` markers always use the `other` case.
+    pub fn has_plural_data(&self) -> bool {
+        has_plural_data(self)
+    }
+
+    /// Returns the more general language this one falls back to, which is determined by removing its most specific subtag.
+    /// Variants are removed first, then the region, then the script. Returns [`None`] if only the language subtag is left.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// # use yarnspinner_runtime::prelude::*;
+    /// assert_eq!(Some(Language::new("zh-Hant")), Language::new("zh-Hant-TW").parent());
+    /// assert_eq!(Some(Language::new("zh")), Language::new("zh-Hant").parent());
+    /// assert_eq!(None, Language::new("zh").parent());
+    /// ```
+    pub fn parent(&self) -> Option<Self> {
+        let mut parent = self.0.clone();
+        if !parent.variants.is_empty() {
+            parent.variants.clear();
+        } else if parent.region.is_some() {
+            parent.region = None;
+        } else if parent.script.is_some() {
+            parent.script = None;
+        } else {
+            return None;
+        }
+        Some(Self(parent))
+    }
 }
 
 impl Display for Language {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
 }
@@ -38,3 +102,38 @@ where
         Self::new(language)
     }
 }
+
+/// The error returned by [`Language::try_new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageError {
+    /// The string is not a valid IETF BCP 47 language code.
+    InvalidCode {
+        /// The string that was passed to [`Language::try_new`].
+        code: String,
+        /// Why the code is invalid.
+        message: String,
+    },
+    /// The code is valid, but there are no plural rules for the language, so the `plural` and `ordinal` markers cannot be used with it.
+    NoPluralData {
+        /// The parsed language.
+        language: Language,
+    },
+}
+
+impl Error for LanguageError {}
+
+impl Display for LanguageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCode { code, message } => {
+                write!(
+                    f,
+                    "\"{code}\" is not a valid IETF BCP 47 language code: {message}"
+                )
+            }
+            Self::NoPluralData { language } => {
+                write!(f, "No plural data is available for the language {language}")
+            }
+        }
+    }
+}
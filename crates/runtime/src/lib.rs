@@ -10,12 +10,19 @@ mod analyser;
 mod command;
 mod dialogue;
 mod dialogue_option;
+#[cfg(feature = "async")]
+mod dialogue_stream;
 mod events;
+mod history;
 mod language;
 mod line;
 pub mod markup;
 mod pluralization;
+mod seen_lines;
+#[cfg(test)]
+mod test_utils;
 mod text_provider;
+mod value_formatting;
 mod variable_storage;
 mod virtual_machine;
 
@@ -23,15 +30,19 @@ pub use dialogue::Result;
 
 pub mod prelude {
     //! Everything you need to get starting using the Yarn Spinner runtime.
+    #[cfg(feature = "async")]
+    pub use crate::dialogue_stream::*;
     pub use crate::{
         analyser::*,
         command::*,
         dialogue::{Dialogue, DialogueError},
         dialogue_option::*,
         events::*,
+        history::*,
         language::*,
         line::*,
         markup::MarkupParseError,
+        seen_lines::*,
         text_provider::*,
         variable_storage::*,
     };
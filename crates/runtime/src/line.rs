@@ -4,9 +4,11 @@
 //! Introduced `LineId` newtype for better type safety
 
 use crate::markup::{
-    MarkupAttribute, MarkupValue, CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY,
+    self, MarkupAttribute, MarkupNode, MarkupValue, TextRun, CHARACTER_ATTRIBUTE,
+    CHARACTER_ATTRIBUTE_NAME_PROPERTY,
 };
 use crate::prelude::*;
+use std::fmt::{self, Display};
 
 /// A line of dialogue, sent from the [`Dialogue`] to the game.
 ///
@@ -37,6 +39,9 @@ pub struct Line {
     pub text: String,
     /// The list of [`MarkupAttribute`] in this parse result.
     pub attributes: Vec<MarkupAttribute>,
+    /// Whether the line had already been delivered before, according to the [`Dialogue`]'s [`SeenLines`].
+    /// Always `false` the first time a line is delivered. Lines of [`DialogueOption`]s are checked, but not marked as seen.
+    pub is_seen: bool,
 }
 
 impl Line {
@@ -67,6 +72,7 @@ impl Line {
     /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
     /// #        source_position: 0,
     /// #    }],
+    /// #    is_seen: false,
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!(Some("Alice"), line.character_name());
@@ -82,6 +88,7 @@ impl Line {
     /// #    id: "line".into(),
     /// #    text: "Great, thanks".to_owned(),
     /// #    attributes: vec![],
+    /// #    is_seen: false,
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert!(line.character_name().is_none());
@@ -119,6 +126,7 @@ impl Line {
     /// #        properties: HashMap::from([("name".to_owned(), "Alice".into())]),
     /// #        source_position: 0,
     /// #    }],
+    /// #    is_seen: false,
     /// # };
     /// assert_eq!("Alice: Hello! How are you today?", line.text);
     /// assert_eq!("Hello! How are you today?", &line.text_without_character_name());
@@ -134,6 +142,7 @@ impl Line {
     /// #    id: "line".into(),
     /// #    text: "Great, thanks".to_owned(),
     /// #    attributes: vec![],
+    /// #    is_seen: false,
     /// # };
     /// assert_eq!("Great, thanks", line.text);
     /// assert_eq!("Great, thanks", &line.text_without_character_name());
@@ -145,6 +154,21 @@ impl Line {
         }
     }
 
+    /// Splits [`Line::text`] into runs of text in which the same attributes are active, e.g. to map them onto styled text sections.
+    /// Attributes covering no text, like the self-closing `[pause/]`, get a run with empty text at their position.
+    ///
+    /// See [`markup::text_runs`] for an example.
+    pub fn text_runs(&self) -> Vec<TextRun<'_>> {
+        markup::text_runs(&self.text, &self.attributes)
+    }
+
+    /// Nests [`Line::text`] and [`Line::attributes`] into a tree of [`MarkupNode`]s, like the elements of an HTML document.
+    ///
+    /// See [`markup::markup_tree`] for an example.
+    pub fn markup_tree(&self) -> Vec<MarkupNode<'_>> {
+        markup::markup_tree(&self.text, &self.attributes)
+    }
+
     /// Returns the substring of [`Line::text`] covered by the passed `attribute`s [`MarkupAttribute::position`] and [`MarkupAttribute::length`] fields.
     pub fn text_for_attribute(&self, attribute: &MarkupAttribute) -> &str {
         assert!(
@@ -200,6 +224,7 @@ impl Line {
                 id: self.id.clone(),
                 text: self.text.to_string(),
                 attributes,
+                is_seen: self.is_seen,
             };
         }
         let deletion_start = attribute_to_delete.position;
@@ -275,6 +300,14 @@ impl Line {
             id: self.id.clone(),
             text: edited_substring,
             attributes,
+            is_seen: self.is_seen,
         }
     }
 }
+
+/// Renders the line as its [`Line::text`], which includes the character name if there is one, e.g. `Mae: Hello!`.
+impl Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
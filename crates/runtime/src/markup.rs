@@ -9,12 +9,15 @@ mod attribute_marker_processor;
 mod line_parser;
 mod markup_parse_error;
 mod parsed_markup;
+mod text_runs;
 
+pub use self::attribute_marker_processor::AttributeMarkerProcessor;
 pub use self::line_parser::{
-    CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY, TRIM_WHITESPACE_PROPERTY,
+    LineParser, CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY,
+    REPLACEMENT_MARKER_CONTENTS, TRIM_WHITESPACE_PROPERTY,
 };
 pub(crate) use self::{attribute_marker_processor::*, line_parser::*};
-pub use self::{markup_parse_error::*, parsed_markup::*};
+pub use self::{markup_parse_error::*, parsed_markup::*, text_runs::*};
 
 #[cfg(test)]
 mod tests {
@@ -359,6 +362,180 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_custom_marker_processors() {
+        let mut line_parser = line_parser()
+            .register_marker_processor("keybind", Box::new(KeybindProcessor::default()));
+        line_parser.set_language_code(Language::from("de"));
+
+        let markup = line_parser
+            .parse_markup("Press [keybind action=jump/] to [b]jump[/b], or [keybind]Escape[/keybind] to quit.")
+            .unwrap();
+
+        assert_eq!("Press Leertaste to jump, or ESCAPE to quit.", markup.text);
+        assert_eq!(3, markup.attributes.len());
+        assert_eq!("keybind", markup.attributes[0].name);
+        assert_eq!(6, markup.attributes[0].position);
+        assert_eq!(0, markup.attributes[0].length);
+        assert_eq!("b", markup.attributes[1].name);
+        assert_eq!(19, markup.attributes[1].position);
+        assert_eq!("keybind", markup.attributes[2].name);
+        assert_eq!(28, markup.attributes[2].position);
+        assert_eq!("ESCAPE".len(), markup.attributes[2].length);
+    }
+
+    #[test]
+    fn test_text_runs() {
+        let line = "[a]Ä[b]👩‍👩‍👧 [pause/]x[/a]y[/b]z";
+        let markup = line_parser().parse_markup(line).unwrap();
+        let (a, b, pause) = (
+            &markup.attributes[0],
+            &markup.attributes[1],
+            &markup.attributes[2],
+        );
+
+        let runs = text_runs(&markup.text, &markup.attributes);
+
+        let runs: Vec<_> = runs
+            .into_iter()
+            .map(|run| (run.text, run.position, run.length, run.attributes))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Ä", 0, 1, vec![a]),
+                ("👩‍👩‍👧 ", 1, 2, vec![a, b]),
+                ("", 3, 0, vec![a, b, pause]),
+                ("x", 3, 1, vec![a, b]),
+                ("y", 4, 1, vec![b]),
+                ("z", 5, 1, vec![]),
+            ],
+            runs
+        );
+    }
+
+    #[test]
+    fn test_markup_tree() {
+        let line = "[a]Ä[b]👩‍👩‍👧 [pause/]x[/a]y[/b]z";
+        let markup = line_parser().parse_markup(line).unwrap();
+        let (a, b, pause) = (
+            &markup.attributes[0],
+            &markup.attributes[1],
+            &markup.attributes[2],
+        );
+
+        let tree = markup_tree(&markup.text, &markup.attributes);
+
+        assert_eq!(
+            vec![
+                MarkupNode::Attribute {
+                    attribute: a,
+                    children: vec![
+                        MarkupNode::Text("Ä"),
+                        MarkupNode::Attribute {
+                            attribute: b,
+                            children: vec![
+                                MarkupNode::Text("👩‍👩‍👧 "),
+                                MarkupNode::Attribute {
+                                    attribute: pause,
+                                    children: vec![],
+                                },
+                                MarkupNode::Text("x"),
+                            ],
+                        },
+                    ],
+                },
+                // `b` overlaps the end of `a`, so it is split
+                MarkupNode::Attribute {
+                    attribute: b,
+                    children: vec![MarkupNode::Text("y")],
+                },
+                MarkupNode::Text("z"),
+            ],
+            tree
+        );
+    }
+
+    #[test]
+    fn test_adjacent_self_closing_markers_are_siblings() {
+        let markup = line_parser()
+            .parse_markup("Hi [pause/][wave/]there")
+            .unwrap();
+        let (pause, wave) = (&markup.attributes[0], &markup.attributes[1]);
+
+        let runs = text_runs(&markup.text, &markup.attributes);
+        let tree = markup_tree(&markup.text, &markup.attributes);
+
+        let runs: Vec<_> = runs
+            .into_iter()
+            .map(|run| (run.text, run.attributes))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Hi ", vec![]),
+                ("", vec![pause]),
+                ("", vec![wave]),
+                ("there", vec![]),
+            ],
+            runs
+        );
+        assert_eq!(
+            vec![
+                MarkupNode::Text("Hi "),
+                MarkupNode::Attribute {
+                    attribute: pause,
+                    children: vec![],
+                },
+                MarkupNode::Attribute {
+                    attribute: wave,
+                    children: vec![],
+                },
+                MarkupNode::Text("there"),
+            ],
+            tree
+        );
+    }
+
+    #[test]
+    fn test_text_runs_of_empty_text() {
+        let markup = line_parser().parse_markup("[pause/]").unwrap();
+
+        let runs = text_runs(&markup.text, &markup.attributes);
+
+        assert_eq!(1, runs.len());
+        assert_eq!("", runs[0].text);
+        assert!(runs[0].has_attribute("pause"));
+        assert!(text_runs("", &[]).is_empty());
+    }
+
+    #[derive(Debug, Clone, Default)]
+    struct KeybindProcessor {
+        language_code: Option<Language>,
+    }
+
+    impl AttributeMarkerProcessor for KeybindProcessor {
+        fn replacement_text_for_marker(&self, marker: &MarkupAttributeMarker) -> String {
+            if let Some(contents) = marker.properties.get(REPLACEMENT_MARKER_CONTENTS) {
+                return contents.to_string().to_uppercase();
+            }
+            let action = marker.properties.get("action").unwrap().to_string();
+            match (action.as_str(), self.language_code.as_ref()) {
+                ("jump", Some(language)) if *language == Language::from("de") => {
+                    "Leertaste".to_owned()
+                }
+                ("jump", _) => "Space".to_owned(),
+                _ => panic!("Unknown action {action}"),
+            }
+        }
+
+        fn set_language_code(&mut self, language_code: Option<Language>) {
+            self.language_code = language_code;
+        }
+
+        fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor> {
+            Box::new(self.clone())
+        }
+    }
+
     fn line_parser() -> LineParser {
         let dialogue_text_processor = Box::new(DialogueTextProcessor::new());
 
@@ -394,6 +571,7 @@ mod tests {
                 id: "test".into(),
                 text: self.text.clone(),
                 attributes: self.attributes.clone(),
+                is_seen: false,
             }
         }
     }
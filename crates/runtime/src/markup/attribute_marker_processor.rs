@@ -9,15 +9,55 @@ mod dialogue_text_processor;
 mod no_markup_text_processor;
 
 /// Provides a mechanism for producing replacement text for a marker.
-pub(crate) trait AttributeMarkerProcessor: Debug + Send + Sync {
+///
+/// Register an implementation with [`Dialogue::register_markup_processor`] to make a marker rewrite the text it appears in,
+/// the same way the built-in `select`, `plural` and `ordinal` markers do. A self-closing marker like `[keybind action=jump/]`
+/// is replaced by the returned text, while an open marker like `[shout]Hello[/shout]` is replaced together with everything up to its closing marker.
+/// The marker stays in the [`Line::attributes`], covering the inserted text.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::markup::*;
+/// # use yarnspinner_runtime::prelude::*;
+/// #[derive(Debug, Clone)]
+/// struct KeybindProcessor;
+///
+/// impl AttributeMarkerProcessor for KeybindProcessor {
+///     fn replacement_text_for_marker(&self, marker: &MarkupAttributeMarker) -> String {
+///         match marker.properties.get("action").map(|action| action.to_string()).as_deref() {
+///             Some("jump") => "Space".to_owned(),
+///             _ => "???".to_owned(),
+///         }
+///     }
+///
+///     fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor> {
+///         Box::new(self.clone())
+///     }
+/// }
+///
+/// let mut dialogue = Dialogue::new(Box::new(MemoryVariableStorage::new()), Box::new(StringTableTextProvider::new()));
+/// dialogue.register_markup_processor("keybind", Box::new(KeybindProcessor));
+/// ```
+///
+/// [`Dialogue::register_markup_processor`]: crate::prelude::Dialogue::register_markup_processor
+/// [`Line::attributes`]: crate::prelude::Line::attributes
+pub trait AttributeMarkerProcessor: Debug + Send + Sync {
     /// Produces the replacement text that should be inserted into a parse
     /// result for a given attribute.
     ///
     /// If the marker is an `open` marker, the text from the marker's
     /// position to its corresponding closing marker is provided as a string
-    /// property called `contents`.
+    /// property called [`REPLACEMENT_MARKER_CONTENTS`](crate::markup::REPLACEMENT_MARKER_CONTENTS).
     fn replacement_text_for_marker(&self, marker: &MarkupAttributeMarker) -> String;
-    fn set_language_code(&mut self, language_code: Option<Language>);
+
+    /// Called with the new language whenever the language of the [`Dialogue`](crate::prelude::Dialogue) changes,
+    /// and with the current one when the processor is registered. `None` means the base language.
+    ///
+    /// Does nothing by default. Processors that produce localized text, like the one behind `plural`, should remember it.
+    fn set_language_code(&mut self, _language_code: Option<Language>) {}
+
+    /// Clones the processor into a new box. Usually implemented as `Box::new(self.clone())`.
     fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor>;
 }
 
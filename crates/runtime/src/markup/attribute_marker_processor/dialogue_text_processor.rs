@@ -2,7 +2,7 @@
 
 use crate::markup::AttributeMarkerProcessor;
 use crate::prelude::*;
-use icu_plurals::PluralCategory;
+use log::error;
 use std::collections::HashSet;
 
 #[derive(Default, Debug, Clone)]
@@ -58,14 +58,17 @@ impl AttributeMarkerProcessor for DialogueTextProcessor {
         // Implementation note: no need to fiddle with locales here because ICU already does fallbacks for us.
 
         // I would love to cache this, but `icu_plural::PluralRules` is not `Send` because it contains an `Rc`, so even a mutex can't help here :(
-        let plural_case =
-            match marker.name.as_ref().unwrap().as_str() {
-                "plural" => Pluralization::new(language_code.clone())
-                    .get_cardinal_plural_case(value_as_float),
-                "ordinal" => Pluralization::new(language_code.clone())
-                    .get_ordinal_plural_case(value_as_float),
+        let plural_case = match Pluralization::new(language_code.clone()) {
+            Ok(pluralization) => match marker.name.as_ref().unwrap().as_str() {
+                "plural" => pluralization.get_cardinal_plural_case(value_as_float),
+                "ordinal" => pluralization.get_ordinal_plural_case(value_as_float),
                 _ => panic!("Invalid marker name {:?}", marker.name),
-            };
+            },
+            Err(error) => {
+                error!("{error}, using the plural case \"other\" for {value}");
+                PluralCategory::Other
+            }
+        };
         let plural_case_name = plural_case_name(plural_case);
 
         // Now that we know the plural case, we can select the
@@ -3,7 +3,6 @@
 use crate::markup::{
     AttributeMarkerProcessor, MarkupAttributeMarker, MarkupValue, REPLACEMENT_MARKER_CONTENTS,
 };
-use crate::prelude::Language;
 
 /// A markup text processor that implements the `[nomarkup]` attribute's behaviour.
 #[derive(Default, Debug, Clone)]
@@ -27,10 +26,6 @@ impl AttributeMarkerProcessor for NoMarkupTextProcessor {
         }
     }
 
-    fn set_language_code(&mut self, _language_code: Option<Language>) {
-        // no-op
-    }
-
     fn clone_box(&self) -> Box<dyn AttributeMarkerProcessor> {
         Box::new(self.clone())
     }
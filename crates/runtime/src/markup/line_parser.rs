@@ -14,6 +14,12 @@ use unicode_segmentation::UnicodeSegmentation;
 
 pub type Result<T> = std::result::Result<T, MarkupParseError>;
 
+/// Parses text and produces markup information.
+///
+/// A [`Dialogue`] uses this internally to turn the text of lines into [`Line`]s. Use it directly if you need to inspect
+/// the markup of text that does not run through a [`Dialogue`], e.g. when validating translations.
+/// Note that a fresh [`LineParser`] only knows about the `nomarkup` marker. The `select`, `plural` and `ordinal` markers
+/// are registered by the [`Dialogue`], so a [`LineParser`] treats them as regular self-closing attributes.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "bevy", derive(Reflect))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -22,7 +28,7 @@ pub type Result<T> = std::result::Result<T, MarkupParseError>;
     all(feature = "bevy", feature = "serde"),
     reflect(Serialize, Deserialize)
 )]
-pub(crate) struct LineParser {
+pub struct LineParser {
     // ## Implementation notes
     // We don't port `stringReader` because [`BufReader`] is not [`Clone`]
     /// A map for the names of attributes to an object that can generate replacement text for those attributes.
@@ -52,7 +58,8 @@ impl Default for LineParser {
 }
 
 impl LineParser {
-    pub(crate) fn new() -> Self {
+    /// Creates a new [`LineParser`] that only knows about the `nomarkup` marker.
+    pub fn new() -> Self {
         Self::default()
     }
 
@@ -66,27 +73,33 @@ impl LineParser {
     /// implemented in this way by the [`LineParser`]
     /// directly; the [`Dialogue`] uses this mechanism
     /// to implement the `select`, `plural` and `ordinal` markers.
-    pub(crate) fn register_marker_processor(
+    ///
+    /// Replaces the processor previously registered for that name, if any.
+    pub fn register_marker_processor(
         mut self,
         attribute_name: impl Into<String>,
         processor: Box<dyn AttributeMarkerProcessor>,
     ) -> Self {
-        let attribute_name = attribute_name.into();
-        let previous_value = self
-            .marker_processors
-            .insert(attribute_name.clone(), processor);
-        assert!(previous_value.is_none(),
-                "A marker processor for the attribute '{attribute_name}' has already been added. \
-                This is a bug. Please report it at https://github.com/YarnSpinnerTool/YarnSpinner-Rust/issues/new");
+        self.insert_marker_processor(attribute_name, processor);
         self
     }
 
+    /// Same as [`LineParser::register_marker_processor`], but through a mutable reference.
+    pub(crate) fn insert_marker_processor(
+        &mut self,
+        attribute_name: impl Into<String>,
+        processor: Box<dyn AttributeMarkerProcessor>,
+    ) {
+        self.marker_processors
+            .insert(attribute_name.into(), processor);
+    }
+
     /// Parses a line of text, and produces a [`ParsedMarkup`] containing the processed text
     ///
     /// ## Implementation notes
     ///
     /// The original does not reset the internal `source_position`. This was likely a bug.
-    pub(crate) fn parse_markup(&mut self, input: &str) -> Result<ParsedMarkup> {
+    pub fn parse_markup(&mut self, input: &str) -> Result<ParsedMarkup> {
         if input.is_empty() {
             // We got a null input; return an empty markup parse result
             return Ok(ParsedMarkup::new());
@@ -210,7 +223,8 @@ impl LineParser {
         Ok(ParsedMarkup { text, attributes })
     }
 
-    pub(crate) fn set_language_code(&mut self, language_code: impl Into<Option<Language>>) {
+    /// Passes the language to all registered marker processors. See [`AttributeMarkerProcessor::set_language_code`].
+    pub fn set_language_code(&mut self, language_code: impl Into<Option<Language>>) {
         let language_code = language_code.into();
         for processor in self.marker_processors.values_mut() {
             processor.set_language_code(language_code.clone());
@@ -726,7 +740,8 @@ pub(crate) fn normalize(string: &str) -> String {
 }
 
 /// The name of the property in replacement attributes that contains the text of the attribute.
-pub(crate) const REPLACEMENT_MARKER_CONTENTS: &str = "contents";
+/// See [`AttributeMarkerProcessor::replacement_text_for_marker`].
+pub const REPLACEMENT_MARKER_CONTENTS: &str = "contents";
 
 /// The name of the implicitly-generated `character` attribute.
 pub const CHARACTER_ATTRIBUTE: &str = "character";
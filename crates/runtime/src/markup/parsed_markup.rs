@@ -1,7 +1,6 @@
 //! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner/YarnSpinner.Markup/MarkupParseResult.cs>
 
-pub use self::{markup_attribute::*, markup_value::*};
-pub(crate) use self::{markup_attribute_marker::*, tag_type::*};
+pub use self::{markup_attribute::*, markup_attribute_marker::*, markup_value::*, tag_type::*};
 use std::fmt::Debug;
 
 mod markup_attribute;
@@ -12,15 +11,15 @@ mod tag_type;
 /// The result of parsing a line of marked-up text.
 ///
 /// You do not create instances of this struct yourself. It is created
-/// by objects that can parse markup, such as [`Dialogue`].
+/// by objects that can parse markup, such as [`LineParser`].
 ///
 /// ## Implementation Notes
 /// - This is called `MarkupParseResult` in the original C# code, but was renamed because [`Result`] already carries meaning in Rust.
-/// - The API has been merged with [`Line`], so this is now only an internal type.
+/// - The API has been merged with [`Line`], which is what a [`Dialogue`] hands out.
 
 #[derive(Debug, Default, Clone)]
 #[non_exhaustive]
-pub(crate) struct ParsedMarkup {
+pub struct ParsedMarkup {
     /// The original text, with all parsed markers removed.
     pub text: String,
     /// The list of [`MarkupAttribute`] in this parse result.
@@ -0,0 +1,212 @@
+//! Turns the flat list of [`MarkupAttribute`]s of a line into structures that can be mapped onto styled text.
+
+use crate::markup::MarkupAttribute;
+use std::cmp::Reverse;
+use std::ops::Range;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A piece of text in which the same [`MarkupAttribute`]s are active. Created by [`text_runs`].
+///
+/// Runs covering text never overlap and together cover the whole text.
+/// Each attribute that covers no text, like the self-closing `[pause/]`, gets its own run with an empty [`TextRun::text`] at its position,
+/// so that a view can e.g. insert an icon or a delay there. Such runs at the same position are ordered like the attributes in the markup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun<'a> {
+    /// The text of this run.
+    pub text: &'a str,
+    /// The position of the run in the plain text, measured in text elements like [`MarkupAttribute::position`].
+    pub position: usize,
+    /// The number of text elements in this run, like [`MarkupAttribute::length`].
+    pub length: usize,
+    /// The attributes that are active in this run, ordered from the outermost to the innermost.
+    pub attributes: Vec<&'a MarkupAttribute>,
+}
+
+impl<'a> TextRun<'a> {
+    /// Gets the innermost active attribute with the specified name, if present.
+    pub fn attribute(&self, name: &str) -> Option<&'a MarkupAttribute> {
+        self.attributes
+            .iter()
+            .rev()
+            .find(|attribute| attribute.name == name)
+            .copied()
+    }
+
+    /// Whether an attribute with the specified name is active in this run.
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attribute(name).is_some()
+    }
+}
+
+/// A node of the tree created by [`markup_tree`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkupNode<'a> {
+    /// Plain text.
+    Text(&'a str),
+    /// An attribute containing text and other attributes.
+    /// Attributes that partially overlap cannot be nested, so one of them is split into several nodes referring to the same [`MarkupAttribute`].
+    Attribute {
+        /// The attribute.
+        attribute: &'a MarkupAttribute,
+        /// The text and attributes inside the attribute. Empty for attributes that cover no text, like the self-closing `[pause/]`.
+        children: Vec<MarkupNode<'a>>,
+    },
+}
+
+/// Splits the text into [`TextRun`]s at every point where an attribute starts or ends.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::markup::*;
+/// # use yarnspinner_runtime::prelude::*;
+/// # let mut line_parser = LineParser::new();
+/// let markup = line_parser.parse_markup("A [b]bold [i]move[/b]![/i]").unwrap();
+/// let runs = text_runs(&markup.text, &markup.attributes);
+///
+/// let texts: Vec<_> = runs.iter().map(|run| run.text).collect();
+/// assert_eq!(vec!["A ", "bold ", "move", "!"], texts);
+/// assert!(runs[2].has_attribute("b") && runs[2].has_attribute("i"));
+/// assert!(!runs[3].has_attribute("b") && runs[3].has_attribute("i"));
+/// ```
+pub fn text_runs<'a>(text: &'a str, attributes: &'a [MarkupAttribute]) -> Vec<TextRun<'a>> {
+    let byte_offsets: Vec<_> = text
+        .grapheme_indices(true)
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(text.len()))
+        .collect();
+    let text_length = byte_offsets.len() - 1;
+    let range_of = |attribute: &MarkupAttribute| {
+        let start = attribute.position.min(text_length);
+        start..(attribute.position + attribute.length).min(text_length)
+    };
+
+    let mut sorted_attributes: Vec<_> = attributes.iter().enumerate().collect();
+    // Outer attributes start earlier or are longer. Ties keep the order of the markup.
+    sorted_attributes.sort_by_key(|(index, attribute)| {
+        let range = range_of(attribute);
+        (range.start, Reverse(range.end), *index)
+    });
+    let sorted_attributes: Vec<_> = sorted_attributes
+        .into_iter()
+        .map(|(_, attribute)| attribute)
+        .collect();
+
+    let mut boundaries: Vec<_> = attributes
+        .iter()
+        .flat_map(|attribute| {
+            let range = range_of(attribute);
+            [range.start, range.end]
+        })
+        .chain([0, text_length])
+        .collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let text_run = |range: Range<usize>, attributes| TextRun {
+        text: &text[byte_offsets[range.start]..byte_offsets[range.end]],
+        position: range.start,
+        length: range.len(),
+        attributes,
+    };
+    let mut runs = Vec::new();
+    for (index, &position) in boundaries.iter().enumerate() {
+        let covering_attributes: Vec<_> = sorted_attributes
+            .iter()
+            .copied()
+            .filter(|attribute| range_of(attribute).contains(&position))
+            .collect();
+        let empty_attributes = sorted_attributes
+            .iter()
+            .copied()
+            .filter(|attribute| range_of(attribute) == (position..position));
+        for empty_attribute in empty_attributes {
+            let active_attributes = covering_attributes
+                .iter()
+                .copied()
+                .chain([empty_attribute])
+                .collect();
+            runs.push(text_run(position..position, active_attributes));
+        }
+        let Some(&end) = boundaries.get(index + 1) else {
+            break;
+        };
+        let active_attributes = sorted_attributes
+            .iter()
+            .copied()
+            .filter(|attribute| {
+                let range = range_of(attribute);
+                range.start <= position && range.end >= end
+            })
+            .collect();
+        runs.push(text_run(position..end, active_attributes));
+    }
+    runs
+}
+
+/// Nests the text and attributes into a tree of [`MarkupNode`]s, like the elements of an HTML document.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner_runtime::markup::*;
+/// # use yarnspinner_runtime::prelude::*;
+/// # let mut line_parser = LineParser::new();
+/// let markup = line_parser.parse_markup("A [b]bold[/b] move").unwrap();
+/// let tree = markup_tree(&markup.text, &markup.attributes);
+///
+/// assert_eq!(
+///     vec![
+///         MarkupNode::Text("A "),
+///         MarkupNode::Attribute {
+///             attribute: &markup.attributes[0],
+///             children: vec![MarkupNode::Text("bold")],
+///         },
+///         MarkupNode::Text(" move"),
+///     ],
+///     tree
+/// );
+/// ```
+pub fn markup_tree<'a>(text: &'a str, attributes: &'a [MarkupAttribute]) -> Vec<MarkupNode<'a>> {
+    let mut root = Vec::new();
+    let mut open_attributes: Vec<(&MarkupAttribute, Vec<MarkupNode>)> = Vec::new();
+    for run in text_runs(text, attributes) {
+        let still_open = open_attributes
+            .iter()
+            .zip(&run.attributes)
+            .take_while(|((open, _), active)| std::ptr::eq(*open, **active))
+            .count();
+        while open_attributes.len() > still_open {
+            close_attribute(&mut open_attributes, &mut root);
+        }
+        for attribute in &run.attributes[still_open..] {
+            open_attributes.push((attribute, Vec::new()));
+        }
+        if !run.text.is_empty() {
+            let node = MarkupNode::Text(run.text);
+            match open_attributes.last_mut() {
+                Some((_, children)) => children.push(node),
+                None => root.push(node),
+            }
+        }
+    }
+    while !open_attributes.is_empty() {
+        close_attribute(&mut open_attributes, &mut root);
+    }
+    root
+}
+
+fn close_attribute<'a>(
+    open_attributes: &mut Vec<(&'a MarkupAttribute, Vec<MarkupNode<'a>>)>,
+    root: &mut Vec<MarkupNode<'a>>,
+) {
+    let (attribute, children) = open_attributes.pop().unwrap();
+    let node = MarkupNode::Attribute {
+        attribute,
+        children,
+    };
+    match open_attributes.last_mut() {
+        Some((_, children)) => children.push(node),
+        None => root.push(node),
+    }
+}
@@ -1,45 +1,27 @@
-use crate::prelude::Language;
-use fixed_decimal::{DoublePrecision, FixedDecimal};
-use icu_plurals::{PluralCategory, PluralRuleType};
-use icu_plurals::{PluralOperands, PluralRules};
+//! Determines the plural case of numbers for the `plural` and `ordinal` markers.
+//!
+//! By default, the rules come from ICU. With the `compact_plurals` feature, a small table of rules is used instead,
+//! see [`compact`] for details.
 
-#[derive(Debug)]
-pub(crate) struct Pluralization {
-    cardinal_rules: PluralRules,
-    ordinal_rules: PluralRules,
-}
-
-impl Pluralization {
-    pub(crate) fn new(language: impl Into<Language>) -> Self {
-        let language = language.into();
-        let locale = language.0.into();
-        let cardinal_rules = PluralRules::try_new(&locale, PluralRuleType::Cardinal).unwrap();
-        let ordinal_rules = PluralRules::try_new(&locale, PluralRuleType::Ordinal).unwrap();
-        Self {
-            cardinal_rules,
-            ordinal_rules,
-        }
-    }
+#[cfg(any(feature = "compact_plurals", not(feature = "icu_plurals")))]
+mod compact;
+#[cfg(all(feature = "icu_plurals", not(feature = "compact_plurals")))]
+mod icu;
 
-    pub(crate) fn get_cardinal_plural_case(&self, value: f32) -> PluralCategory {
-        let value = get_into_plural_operand(value);
-        self.cardinal_rules.category_for(value)
-    }
+#[cfg(any(feature = "compact_plurals", not(feature = "icu_plurals")))]
+pub(crate) use self::compact::*;
+#[cfg(all(feature = "icu_plurals", not(feature = "compact_plurals")))]
+pub(crate) use self::icu::*;
 
-    pub(crate) fn get_ordinal_plural_case(&self, value: f32) -> PluralCategory {
-        let value = get_into_plural_operand(value);
-        self.ordinal_rules.category_for(value)
-    }
-}
-
-fn get_into_plural_operand(value: f32) -> PluralOperands {
-    let rounded = value.round();
-    let floating_point = (rounded - value).abs();
-    if floating_point < 1e-5 {
-        (value as isize).into()
-    } else {
-        (&FixedDecimal::try_from_f64(value as f64, DoublePrecision::Floating).unwrap()).into()
-    }
+/// The plural case of a number, as defined by the [CLDR](https://cldr.unicode.org/index/cldr-spec/plural-rules).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
 }
 
 #[cfg(test)]
@@ -47,6 +29,7 @@ mod tests {
     //! Adapted from `TestNumberPlurals` in <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Tests/LanguageTests.cs>
 
     use super::*;
+    use crate::prelude::Language;
 
     #[test]
     fn test_number_plurals() {
@@ -111,7 +94,9 @@ mod tests {
         ];
 
         for (locale, value, expected_category) in cardinal_tests.into_iter() {
-            let result = Pluralization::new(locale).get_cardinal_plural_case(value);
+            let result = Pluralization::new(locale)
+                .unwrap()
+                .get_cardinal_plural_case(value);
             assert_eq!(
                 expected_category, result,
                 "locale: {locale}, value: {value}, type: Cardinal"
@@ -119,11 +104,32 @@ mod tests {
         }
 
         for (locale, value, expected_category) in ordinal_tests.into_iter() {
-            let result = Pluralization::new(locale).get_ordinal_plural_case(value);
+            let result = Pluralization::new(locale)
+                .unwrap()
+                .get_ordinal_plural_case(value);
             assert_eq!(
                 expected_category, result,
                 "locale: {locale}, value: {value}, type: Ordinal"
             );
         }
     }
+
+    #[test]
+    fn knows_which_languages_have_plural_data() {
+        for (language, expected) in [
+            ("en", true),
+            ("en-US", true),
+            ("zh-Hant-TW", true),
+            ("ja", true),
+            ("und", false),
+            ("xx", false),
+            ("tlh", false),
+        ] {
+            assert_eq!(
+                expected,
+                has_plural_data(&Language::new(language)),
+                "{language}"
+            );
+        }
+    }
 }
@@ -0,0 +1,591 @@
+//! A compact table of the [CLDR plural rules](https://www.unicode.org/cldr/charts/latest/supplemental/language_plural_rules.html),
+//! used instead of ICU's when the `compact_plurals` feature is enabled.
+//!
+//! ICU ships the rules of every locale as baked data, which makes up a considerable part of a WASM build.
+//! The rules below are plain functions instead, so they cost next to nothing in binary size.
+//! They cover the languages most games are translated into.
+//!
+//! The list of languages can be narrowed down further at compile time by setting the `YARNSPINNER_PLURAL_LOCALES`
+//! environment variable to a comma-separated list of language subtags, e.g. `YARNSPINNER_PLURAL_LOCALES=en,de,fr`.
+//! All other languages are then treated as having no rules, while every regional variant of a selected language,
+//! like `pt-PT` for `pt`, keeps its own. The rules themselves are still compiled in, as they are only a few small functions.
+//!
+//! A language without rules falls back to the rules of its parent, e.g. `de-CH` uses the ones of `de`.
+//! If no parent has rules either, every number uses the `other` case, just like ICU does for unknown languages.
+
+use super::PluralCategory;
+use super::PluralCategory::*;
+use crate::prelude::{Language, LanguageError};
+
+#[derive(Debug)]
+pub(crate) struct Pluralization {
+    rules: &'static PluralRules,
+}
+
+impl Pluralization {
+    pub(crate) fn new(language: impl Into<Language>) -> Result<Self, LanguageError> {
+        let language = language.into();
+        let rules = find_rules(&language).unwrap_or(&ROOT_RULES);
+        Ok(Self { rules })
+    }
+
+    pub(crate) fn get_cardinal_plural_case(&self, value: f32) -> PluralCategory {
+        (self.rules.cardinal)(&PluralOperands::from(value))
+    }
+
+    pub(crate) fn get_ordinal_plural_case(&self, value: f32) -> PluralCategory {
+        (self.rules.ordinal)(&PluralOperands::from(value))
+    }
+}
+
+/// Whether the table has plural rules for the language or one of its parents.
+pub(crate) fn has_plural_data(language: &Language) -> bool {
+    find_rules(language).is_some()
+}
+
+fn find_rules(language: &Language) -> Option<&'static PluralRules> {
+    let mut language = Some(language.clone());
+    while let Some(current) = language {
+        let tag = current.to_string();
+        let rules = RULES
+            .iter()
+            .find(|rules| rules.languages.contains(&tag.as_str()) && is_included(&tag));
+        if rules.is_some() {
+            return rules;
+        }
+        language = current.parent();
+    }
+    None
+}
+
+/// Whether the language was selected through `YARNSPINNER_PLURAL_LOCALES`. All languages are included if it is not set.
+fn is_included(language: &str) -> bool {
+    match option_env!("YARNSPINNER_PLURAL_LOCALES") {
+        Some(selection) => is_selected(language, selection),
+        None => true,
+    }
+}
+
+/// Whether the language subtag of the language is one of the comma-separated ones in the selection.
+fn is_selected(language: &str, selection: &str) -> bool {
+    fn language_subtag(tag: &str) -> &str {
+        tag.trim().split(['-', '_']).next().unwrap_or_default()
+    }
+    let language = language_subtag(language);
+    selection
+        .split(',')
+        .any(|selected| language_subtag(selected).eq_ignore_ascii_case(language))
+}
+
+/// The operands of a number as defined by the [CLDR](https://unicode.org/reports/tr35/tr35-numbers.html#Operands).
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PluralOperands {
+    /// The absolute value.
+    n: f64,
+    /// The integer digits.
+    i: u64,
+    /// The number of visible fraction digits.
+    v: usize,
+    /// The visible fraction digits.
+    f: u64,
+    /// The visible fraction digits without trailing zeros.
+    t: u64,
+}
+
+impl From<f32> for PluralOperands {
+    fn from(value: f32) -> Self {
+        // Going through the shortest representation makes `1.1` have the fraction digits `1` instead of `100000002384185791015625`.
+        let representation = value.abs().to_string();
+        let (integer_digits, fraction_digits) = representation
+            .split_once('.')
+            .unwrap_or((&representation, ""));
+        let fraction = fraction_digits.parse().unwrap_or_default();
+        Self {
+            n: representation.parse().unwrap_or_default(),
+            i: integer_digits.parse().unwrap_or(u64::MAX),
+            v: fraction_digits.len(),
+            f: fraction,
+            t: fraction_digits
+                .trim_end_matches('0')
+                .parse()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl PluralOperands {
+    /// `n % divisor`, which is only an integer if `n` is.
+    fn n_mod(&self, divisor: f64) -> f64 {
+        self.n % divisor
+    }
+
+    /// Whether `n` is one of the given integers.
+    fn n_is(&self, values: &[u64]) -> bool {
+        values.iter().any(|&value| self.n == value as f64)
+    }
+
+    /// Whether `n` is an integer in the given range.
+    fn n_in(&self, start: u64, end: u64) -> bool {
+        in_range(self.n, start, end)
+    }
+}
+
+/// Whether a value is an integer in the given range, as in the CLDR's `n % 100 = 3..10`.
+fn in_range(value: f64, start: u64, end: u64) -> bool {
+    value.fract() == 0.0 && value >= start as f64 && value <= end as f64
+}
+
+type Rule = fn(&PluralOperands) -> PluralCategory;
+
+#[derive(Debug)]
+struct PluralRules {
+    /// The language tags the rules apply to.
+    languages: &'static [&'static str],
+    cardinal: Rule,
+    ordinal: Rule,
+}
+
+const ROOT_RULES: PluralRules = PluralRules {
+    languages: &[],
+    cardinal: other,
+    ordinal: other,
+};
+
+fn other(_: &PluralOperands) -> PluralCategory {
+    Other
+}
+
+/// `one: i = 1 and v = 0`
+fn one_if_integer_one(o: &PluralOperands) -> PluralCategory {
+    if o.i == 1 && o.v == 0 {
+        One
+    } else {
+        Other
+    }
+}
+
+/// `one: n = 1`
+fn one_if_one(o: &PluralOperands) -> PluralCategory {
+    if o.n_is(&[1]) {
+        One
+    } else {
+        Other
+    }
+}
+
+/// `one: i = 0 or n = 1`
+fn one_if_zero_or_one(o: &PluralOperands) -> PluralCategory {
+    if o.i == 0 || o.n_is(&[1]) {
+        One
+    } else {
+        Other
+    }
+}
+
+/// `many: e = 0 and i != 0 and i % 1000000 = 0 and v = 0`, i.e. for millions
+fn is_million(o: &PluralOperands) -> bool {
+    o.i != 0 && o.i.is_multiple_of(1_000_000) && o.v == 0
+}
+
+/// `one: n % 10 = 1 and n % 100 != 11`, `few: n % 10 = 2..4 and n % 100 != 12..14`,
+/// `many: n % 10 = 0 or n % 10 = 5..9 or n % 100 = 11..14` with `i` for `n` and `v = 0`, like in Russian
+fn east_slavic(i: u64) -> PluralCategory {
+    match (i % 10, i % 100) {
+        (1, hundreds) if hundreds != 11 => One,
+        (2..=4, hundreds) if !(12..=14).contains(&hundreds) => Few,
+        _ => Many,
+    }
+}
+
+/// Ordinals of English: 1st, 2nd, 3rd, 4th, 11th, 21st
+fn english_ordinal(o: &PluralOperands) -> PluralCategory {
+    match (o.n_mod(10.0), o.n_mod(100.0)) {
+        (tens, hundreds) if tens == 1.0 && hundreds != 11.0 => One,
+        (tens, hundreds) if tens == 2.0 && hundreds != 12.0 => Two,
+        (tens, hundreds) if tens == 3.0 && hundreds != 13.0 => Few,
+        _ => Other,
+    }
+}
+
+/// The rules of the supported languages, as of CLDR 44.
+/// Languages whose rules only use `other`, like Japanese, are listed so that they count as having plural data.
+static RULES: &[PluralRules] = &[
+    PluralRules {
+        languages: &["en"],
+        cardinal: one_if_integer_one,
+        ordinal: english_ordinal,
+    },
+    PluralRules {
+        languages: &["de", "nl", "fi", "et", "ur"],
+        cardinal: one_if_integer_one,
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["sv"],
+        cardinal: one_if_integer_one,
+        ordinal: |o| match (o.n_mod(10.0), o.n_mod(100.0)) {
+            (tens, hundreds)
+                if (tens == 1.0 || tens == 2.0) && hundreds != 11.0 && hundreds != 12.0 =>
+            {
+                One
+            }
+            _ => Other,
+        },
+    },
+    PluralRules {
+        languages: &["it"],
+        cardinal: |o| match one_if_integer_one(o) {
+            Other if is_million(o) => Many,
+            category => category,
+        },
+        ordinal: |o| {
+            if o.n_is(&[11, 8, 80, 800]) {
+                Many
+            } else {
+                Other
+            }
+        },
+    },
+    PluralRules {
+        languages: &["ca"],
+        cardinal: |o| match one_if_integer_one(o) {
+            Other if is_million(o) => Many,
+            category => category,
+        },
+        ordinal: |o| match () {
+            _ if o.n_is(&[1, 3]) => One,
+            _ if o.n_is(&[2]) => Two,
+            _ if o.n_is(&[4]) => Few,
+            _ => Other,
+        },
+    },
+    PluralRules {
+        languages: &["es"],
+        cardinal: |o| match one_if_one(o) {
+            Other if is_million(o) => Many,
+            category => category,
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["pt-PT"],
+        cardinal: |o| match one_if_integer_one(o) {
+            Other if is_million(o) => Many,
+            category => category,
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["pt"],
+        cardinal: |o| match () {
+            _ if o.i <= 1 => One,
+            _ if is_million(o) => Many,
+            _ => Other,
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["fr"],
+        cardinal: |o| match () {
+            _ if o.i <= 1 => One,
+            _ if is_million(o) => Many,
+            _ => Other,
+        },
+        ordinal: one_if_one,
+    },
+    PluralRules {
+        languages: &["da"],
+        cardinal: |o| {
+            if o.n_is(&[1]) || (o.t != 0 && o.i <= 1) {
+                One
+            } else {
+                Other
+            }
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["nb", "nn", "no", "el", "bg", "tr"],
+        cardinal: one_if_one,
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["hu"],
+        cardinal: one_if_one,
+        ordinal: |o| if o.n_is(&[1, 5]) { One } else { Other },
+    },
+    PluralRules {
+        languages: &["is"],
+        cardinal: |o| {
+            if (o.t == 0 && o.i % 10 == 1 && o.i % 100 != 11) || (o.t % 10 == 1 && o.t % 100 != 11)
+            {
+                One
+            } else {
+                Other
+            }
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["ru"],
+        cardinal: |o| if o.v == 0 { east_slavic(o.i) } else { Other },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["uk"],
+        cardinal: |o| if o.v == 0 { east_slavic(o.i) } else { Other },
+        ordinal: |o| {
+            if o.n_mod(10.0) == 3.0 && o.n_mod(100.0) != 13.0 {
+                Few
+            } else {
+                Other
+            }
+        },
+    },
+    PluralRules {
+        languages: &["be"],
+        cardinal: |o| {
+            if o.n.fract() == 0.0 {
+                east_slavic(o.i)
+            } else {
+                Other
+            }
+        },
+        ordinal: |o| match (o.n_mod(10.0), o.n_mod(100.0)) {
+            (tens, hundreds)
+                if (tens == 2.0 || tens == 3.0) && hundreds != 12.0 && hundreds != 13.0 =>
+            {
+                Few
+            }
+            _ => Other,
+        },
+    },
+    PluralRules {
+        languages: &["pl"],
+        cardinal: |o| match east_slavic(o.i) {
+            _ if o.v != 0 => Other,
+            _ if o.i == 1 => One,
+            One => Many,
+            category => category,
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["cs", "sk"],
+        cardinal: |o| match () {
+            _ if o.v != 0 => Many,
+            _ if o.i == 1 => One,
+            _ if (2..=4).contains(&o.i) => Few,
+            _ => Other,
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["hr", "sr", "bs"],
+        cardinal: |o| {
+            let integer = (o.v == 0).then_some(o.i);
+            match (integer, o.f) {
+                (Some(i), _) | (None, i) if i % 10 == 1 && i % 100 != 11 => One,
+                (Some(i), _) | (None, i)
+                    if (2..=4).contains(&(i % 10)) && !(12..=14).contains(&(i % 100)) =>
+                {
+                    Few
+                }
+                _ => Other,
+            }
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["sl"],
+        cardinal: |o| match o.i % 100 {
+            _ if o.v != 0 => Few,
+            1 => One,
+            2 => Two,
+            3 | 4 => Few,
+            _ => Other,
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["lt"],
+        cardinal: |o| match (o.n_mod(10.0), o.n_mod(100.0)) {
+            _ if o.f != 0 => Many,
+            (_, hundreds) if in_range(hundreds, 11, 19) => Other,
+            (tens, _) if in_range(tens, 1, 1) => One,
+            (tens, _) if in_range(tens, 2, 9) => Few,
+            _ => Other,
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["lv"],
+        cardinal: |o| {
+            let (tens, hundreds) = (o.n_mod(10.0), o.n_mod(100.0));
+            if tens == 0.0
+                || in_range(hundreds, 11, 19)
+                || (o.v == 2 && (11..=19).contains(&(o.f % 100)))
+            {
+                Zero
+            } else if (tens == 1.0 && hundreds != 11.0)
+                || (o.f % 10 == 1 && (o.v != 2 || o.f % 100 != 11))
+            {
+                One
+            } else {
+                Other
+            }
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["ro"],
+        cardinal: |o| match () {
+            _ if o.i == 1 && o.v == 0 => One,
+            _ if o.v != 0 || o.n_is(&[0]) || in_range(o.n_mod(100.0), 1, 19) => Few,
+            _ => Other,
+        },
+        ordinal: one_if_one,
+    },
+    PluralRules {
+        languages: &["ga"],
+        cardinal: |o| match () {
+            _ if o.n_is(&[1]) => One,
+            _ if o.n_is(&[2]) => Two,
+            _ if o.n_in(3, 6) => Few,
+            _ if o.n_in(7, 10) => Many,
+            _ => Other,
+        },
+        ordinal: one_if_one,
+    },
+    PluralRules {
+        languages: &["cy"],
+        cardinal: |o| match () {
+            _ if o.n_is(&[0]) => Zero,
+            _ if o.n_is(&[1]) => One,
+            _ if o.n_is(&[2]) => Two,
+            _ if o.n_is(&[3]) => Few,
+            _ if o.n_is(&[6]) => Many,
+            _ => Other,
+        },
+        ordinal: |o| match () {
+            _ if o.n_is(&[0, 7, 8, 9]) => Zero,
+            _ if o.n_is(&[1]) => One,
+            _ if o.n_is(&[2]) => Two,
+            _ if o.n_is(&[3, 4]) => Few,
+            _ if o.n_is(&[5, 6]) => Many,
+            _ => Other,
+        },
+    },
+    PluralRules {
+        languages: &["ar"],
+        cardinal: |o| match () {
+            _ if o.n_is(&[0]) => Zero,
+            _ if o.n_is(&[1]) => One,
+            _ if o.n_is(&[2]) => Two,
+            _ if in_range(o.n_mod(100.0), 3, 10) => Few,
+            _ if in_range(o.n_mod(100.0), 11, 99) => Many,
+            _ => Other,
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["he"],
+        cardinal: |o| match () {
+            _ if (o.i == 1 && o.v == 0) || (o.i == 0 && o.v != 0) => One,
+            _ if o.i == 2 && o.v == 0 => Two,
+            _ => Other,
+        },
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["hi"],
+        cardinal: one_if_zero_or_one,
+        ordinal: |o| match () {
+            _ if o.n_is(&[1]) => One,
+            _ if o.n_is(&[2, 3]) => Two,
+            _ if o.n_is(&[4]) => Few,
+            _ if o.n_is(&[6]) => Many,
+            _ => Other,
+        },
+    },
+    PluralRules {
+        languages: &["bn"],
+        cardinal: one_if_zero_or_one,
+        ordinal: |o| match () {
+            _ if o.n_is(&[1, 5, 7, 8, 9, 10]) => One,
+            _ if o.n_is(&[2, 3]) => Two,
+            _ if o.n_is(&[4]) => Few,
+            _ if o.n_is(&[6]) => Many,
+            _ => Other,
+        },
+    },
+    PluralRules {
+        languages: &["fa"],
+        cardinal: one_if_zero_or_one,
+        ordinal: other,
+    },
+    PluralRules {
+        languages: &["vi", "ms"],
+        cardinal: other,
+        ordinal: one_if_one,
+    },
+    PluralRules {
+        languages: &["ja", "zh", "ko", "th", "id", "yue", "my", "lo"],
+        cardinal: other,
+        ordinal: other,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_operands() {
+        assert_eq!(
+            PluralOperands {
+                n: 1.1,
+                i: 1,
+                v: 1,
+                f: 1,
+                t: 1
+            },
+            PluralOperands::from(-1.1)
+        );
+        assert_eq!(
+            PluralOperands {
+                n: 21.0,
+                i: 21,
+                v: 0,
+                f: 0,
+                t: 0
+            },
+            PluralOperands::from(21.0)
+        );
+    }
+
+    #[test]
+    fn selects_languages_by_language_subtag() {
+        assert!(is_selected("pt-PT", "en, pt"));
+        assert!(is_selected("pt", "en,pt-BR"));
+        assert!(is_selected("de", "DE"));
+        assert!(!is_selected("pt-PT", "en,de"));
+        assert!(!is_selected("en", ""));
+    }
+
+    #[test]
+    fn falls_back_to_parents_and_then_to_other() {
+        let swiss_german = Pluralization::new("de-CH").unwrap();
+        assert_eq!(One, swiss_german.get_cardinal_plural_case(1.0));
+        assert_eq!(Other, swiss_german.get_cardinal_plural_case(2.0));
+
+        let portuguese = Pluralization::new("pt-BR").unwrap();
+        assert_eq!(One, portuguese.get_cardinal_plural_case(0.0));
+        let european_portuguese = Pluralization::new("pt-PT").unwrap();
+        assert_eq!(Other, european_portuguese.get_cardinal_plural_case(0.0));
+
+        let klingon = Pluralization::new("tlh").unwrap();
+        assert_eq!(Other, klingon.get_cardinal_plural_case(1.0));
+        assert_eq!(Other, klingon.get_ordinal_plural_case(1.0));
+    }
+}
@@ -0,0 +1,97 @@
+//! The plural rules of ICU, which cover every locale in CLDR.
+
+use super::PluralCategory;
+use crate::prelude::{Language, LanguageError};
+use fixed_decimal::{DoublePrecision, FixedDecimal};
+use icu_locid::LanguageIdentifier;
+use icu_locid_transform::{LocaleExpander, TransformResult};
+use icu_plurals::provider::{Baked, CardinalV1Marker};
+use icu_plurals::PluralRuleType;
+use icu_plurals::{PluralOperands, PluralRules};
+use icu_provider::{DataLocale, DataProvider, DataRequest};
+
+#[derive(Debug)]
+pub(crate) struct Pluralization {
+    cardinal_rules: PluralRules,
+    ordinal_rules: PluralRules,
+}
+
+impl Pluralization {
+    pub(crate) fn new(language: impl Into<Language>) -> Result<Self, LanguageError> {
+        let language = language.into();
+        let locale = (&language.0).into();
+        let no_plural_data = |_| LanguageError::NoPluralData {
+            language: language.clone(),
+        };
+        let cardinal_rules =
+            PluralRules::try_new(&locale, PluralRuleType::Cardinal).map_err(no_plural_data)?;
+        let ordinal_rules =
+            PluralRules::try_new(&locale, PluralRuleType::Ordinal).map_err(no_plural_data)?;
+        Ok(Self {
+            cardinal_rules,
+            ordinal_rules,
+        })
+    }
+
+    pub(crate) fn get_cardinal_plural_case(&self, value: f32) -> PluralCategory {
+        let value = get_into_plural_operand(value);
+        self.cardinal_rules.category_for(value).into()
+    }
+
+    pub(crate) fn get_ordinal_plural_case(&self, value: f32) -> PluralCategory {
+        let value = get_into_plural_operand(value);
+        self.ordinal_rules.category_for(value).into()
+    }
+}
+
+/// Whether the compiled ICU data has plural rules for the language.
+///
+/// ICU falls back to the rules of the root locale, which treat every number as `other`, for any language it has no rules for.
+/// Since the data omits languages whose rules are identical to the root ones, like Chinese, those count as long as ICU knows the language.
+pub(crate) fn has_plural_data(language: &Language) -> bool {
+    if language.0.language.is_empty() {
+        return false;
+    }
+    let locale = DataLocale::from(&language.0);
+    let request = DataRequest {
+        locale: &locale,
+        metadata: Default::default(),
+    };
+    let has_own_rules = match DataProvider::<CardinalV1Marker>::load(&Baked, request) {
+        // The locale is only set if the data is for a fallback
+        Ok(response) => response
+            .metadata
+            .locale
+            .is_none_or(|fallback| !fallback.is_und()),
+        Err(_) => false,
+    };
+    has_own_rules || is_known_language(&language.0)
+}
+
+fn is_known_language(language: &LanguageIdentifier) -> bool {
+    let mut language = LanguageIdentifier::from(language.language);
+    LocaleExpander::new_extended().maximize(&mut language) == TransformResult::Modified
+}
+
+fn get_into_plural_operand(value: f32) -> PluralOperands {
+    let rounded = value.round();
+    let floating_point = (rounded - value).abs();
+    if floating_point < 1e-5 {
+        (value as isize).into()
+    } else {
+        (&FixedDecimal::try_from_f64(value as f64, DoublePrecision::Floating).unwrap()).into()
+    }
+}
+
+impl From<icu_plurals::PluralCategory> for PluralCategory {
+    fn from(category: icu_plurals::PluralCategory) -> Self {
+        match category {
+            icu_plurals::PluralCategory::Zero => Self::Zero,
+            icu_plurals::PluralCategory::One => Self::One,
+            icu_plurals::PluralCategory::Two => Self::Two,
+            icu_plurals::PluralCategory::Few => Self::Few,
+            icu_plurals::PluralCategory::Many => Self::Many,
+            icu_plurals::PluralCategory::Other => Self::Other,
+        }
+    }
+}
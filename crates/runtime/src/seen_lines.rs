@@ -0,0 +1,176 @@
+//! Keeps track of which lines the player has already read, e.g. to only fast-forward through those.
+
+use crate::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserializer, Serializer};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+/// The IDs of all lines a [`Dialogue`] has delivered through [`DialogueEvent::Line`], which sets [`Line::is_seen`] accordingly.
+///
+/// This is kept separately from the [`VariableStorage`] so that it can be persisted independently of a save game,
+/// e.g. per player profile. With the `serde` feature, it serializes as a sorted list of line IDs.
+///
+/// Cloning is shallow, like [`VariableStorage::clone_shallow`]: all clones share the same underlying set,
+/// which is how the `seen_line` function added by [`Dialogue::add_seen_line_function`] stays in sync.
+#[derive(Debug, Clone, Default)]
+pub struct SeenLines(Arc<RwLock<HashSet<LineId>>>);
+
+impl SeenLines {
+    /// Creates a new empty `SeenLines`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the line has been seen.
+    pub fn contains(&self, line_id: &LineId) -> bool {
+        self.0.read().unwrap().contains(line_id)
+    }
+
+    /// Marks the line as seen. Returns `true` if it had not been seen before.
+    pub fn insert(&mut self, line_id: LineId) -> bool {
+        self.0.write().unwrap().insert(line_id)
+    }
+
+    /// Marks the line as not seen. Returns `true` if it had been seen before.
+    pub fn remove(&mut self, line_id: &LineId) -> bool {
+        self.0.write().unwrap().remove(line_id)
+    }
+
+    /// Forgets all seen lines.
+    pub fn clear(&mut self) {
+        self.0.write().unwrap().clear();
+    }
+
+    /// The number of seen lines.
+    pub fn len(&self) -> usize {
+        self.0.read().unwrap().len()
+    }
+
+    /// Returns `true` if no lines have been seen.
+    pub fn is_empty(&self) -> bool {
+        self.0.read().unwrap().is_empty()
+    }
+
+    /// Returns the IDs of all seen lines, sorted.
+    pub fn line_ids(&self) -> Vec<LineId> {
+        let mut line_ids: Vec<_> = self.0.read().unwrap().iter().cloned().collect();
+        line_ids.sort();
+        line_ids
+    }
+
+    /// Creates a Yarn function that returns whether the line with the given ID has been seen, e.g. `seen_line("line:intro")`.
+    /// See [`Dialogue::add_seen_line_function`] for the usual way to register it.
+    pub fn seen_line_function(&self) -> yarn_fn_type! { impl Fn(String) -> bool } {
+        let seen_lines = self.clone();
+        move |line_id: String| seen_lines.contains(&LineId(line_id))
+    }
+}
+
+impl Extend<LineId> for SeenLines {
+    fn extend<T: IntoIterator<Item = LineId>>(&mut self, iter: T) {
+        self.0.write().unwrap().extend(iter);
+    }
+}
+
+impl FromIterator<LineId> for SeenLines {
+    fn from_iter<T: IntoIterator<Item = LineId>>(iter: T) -> Self {
+        Self(Arc::new(RwLock::new(iter.into_iter().collect())))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SeenLines {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.line_ids().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for SeenLines {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        Vec::<LineId>::deserialize(deserializer).map(|line_ids| line_ids.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    /// A dialogue that delivers the lines with the given IDs in order.
+    fn dialogue(line_ids: &[&'static str]) -> Dialogue {
+        let mut instructions: Vec<_> = line_ids.iter().map(|line_id| line(line_id)).collect();
+        instructions.push(stop());
+        crate::test_utils::dialogue(
+            program([("Start", instructions)]),
+            line_ids.iter().map(|line_id| (*line_id, *line_id)),
+        )
+    }
+
+    fn delivered_lines(dialogue: &mut Dialogue) -> Vec<(String, bool)> {
+        let mut lines = Vec::new();
+        for events in dialogue.by_ref() {
+            lines.extend(events.into_iter().filter_map(|event| match event {
+                DialogueEvent::Line(line) => Some((line.text, line.is_seen)),
+                _ => None,
+            }));
+        }
+        lines
+    }
+
+    #[test]
+    fn marks_delivered_lines_as_seen() {
+        let mut dialogue = dialogue(&["line:a", "line:b", "line:a"]);
+
+        let lines = delivered_lines(&mut dialogue);
+
+        assert_eq!(
+            vec![
+                ("line:a".to_owned(), false),
+                ("line:b".to_owned(), false),
+                ("line:a".to_owned(), true),
+            ],
+            lines
+        );
+        assert!(dialogue.seen(&LineId::from("line:b")));
+        assert!(!dialogue.seen(&LineId::from("line:c")));
+    }
+
+    #[test]
+    fn keeps_seen_lines_across_nodes_and_restores_them() {
+        let mut dialogue = dialogue(&["line:a", "line:b"]);
+        dialogue.seen_lines_mut().insert(LineId::from("line:b"));
+
+        let lines = delivered_lines(&mut dialogue);
+        dialogue.set_node("Start").unwrap();
+        let lines_again = delivered_lines(&mut dialogue);
+
+        assert_eq!(
+            vec![("line:a".to_owned(), false), ("line:b".to_owned(), true)],
+            lines
+        );
+        assert!(lines_again.iter().all(|(_, is_seen)| *is_seen));
+        assert_eq!(
+            vec![LineId::from("line:a"), LineId::from("line:b")],
+            dialogue.seen_lines().line_ids()
+        );
+    }
+
+    #[test]
+    fn seen_line_function_reflects_seen_lines() {
+        let mut dialogue = dialogue(&["line:a"]);
+        dialogue.add_seen_line_function();
+        let seen_line = |dialogue: &Dialogue| {
+            dialogue
+                .library()
+                .get("seen_line")
+                .unwrap()
+                .call(vec![YarnValue::from("line:a")])
+        };
+
+        assert_eq!(YarnValue::from(false), seen_line(&dialogue));
+        delivered_lines(&mut dialogue);
+        assert_eq!(YarnValue::from(true), seen_line(&dialogue));
+    }
+}
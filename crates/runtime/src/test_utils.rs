@@ -0,0 +1,87 @@
+//! Helpers for unit tests that run hand-built [`Program`]s instead of compiled Yarn scripts.
+
+use crate::prelude::*;
+
+pub(crate) fn instruction(
+    opcode: OpCode,
+    operands: impl IntoIterator<Item = Operand>,
+) -> Instruction {
+    Instruction {
+        opcode: opcode.into(),
+        operands: operands.into_iter().collect(),
+    }
+}
+
+pub(crate) fn line(line_id: &str) -> Instruction {
+    instruction(OpCode::RunLine, [line_id.to_owned().into(), 0_usize.into()])
+}
+
+/// An option without a condition that continues at the given label when selected.
+pub(crate) fn option(line_id: &str, label: &str) -> Instruction {
+    instruction(
+        OpCode::AddOption,
+        [
+            line_id.to_owned().into(),
+            label.to_owned().into(),
+            0_usize.into(),
+            false.into(),
+        ],
+    )
+}
+
+pub(crate) fn jump(target: &str) -> [Instruction; 2] {
+    [
+        instruction(OpCode::PushString, [target.to_owned().into()]),
+        instruction(OpCode::RunNode, []),
+    ]
+}
+
+pub(crate) fn stop() -> Instruction {
+    instruction(OpCode::Stop, [])
+}
+
+/// A program with the given nodes. Labels can be added to the nodes afterwards.
+pub(crate) fn program(
+    nodes: impl IntoIterator<Item = (&'static str, Vec<Instruction>)>,
+) -> Program {
+    Program {
+        nodes: nodes
+            .into_iter()
+            .map(|(name, instructions)| {
+                let node = Node {
+                    name: name.to_owned(),
+                    instructions,
+                    ..Default::default()
+                };
+                (name.to_owned(), node)
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// A dialogue that is about to run the `Start` node of the program, with the given texts for its line IDs.
+pub(crate) fn dialogue<'a>(
+    program: Program,
+    lines: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Dialogue {
+    dialogue_with_storage(program, lines, MemoryVariableStorage::new())
+}
+
+/// Like [`dialogue`], but keeps its variables in the given storage.
+pub(crate) fn dialogue_with_storage<'a>(
+    program: Program,
+    lines: impl IntoIterator<Item = (&'a str, &'a str)>,
+    variable_storage: impl VariableStorage + 'static,
+) -> Dialogue {
+    let mut text_provider = StringTableTextProvider::new();
+    text_provider.extend_base_language(
+        lines
+            .into_iter()
+            .map(|(line_id, text)| (LineId::from(line_id), text.to_owned()))
+            .collect(),
+    );
+    let mut dialogue = Dialogue::new(Box::new(variable_storage), Box::new(text_provider));
+    dialogue.add_program(program).set_node("Start").unwrap();
+    dialogue
+}
@@ -4,6 +4,7 @@ use log::error;
 use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::iter;
 use yarnspinner_core::prelude::*;
 
 /// A trait for providing text to a [`Dialogue`](crate::prelude::Dialogue). The default implementation is [`StringTableTextProvider`], which keeps the
@@ -27,6 +28,10 @@ pub trait TextProvider: Debug + Send + Sync {
     fn get_language(&self) -> Option<Language>;
     /// Returns whether the text for all lines announced by [`TextProvider::accept_line_hints`] are available, i.e. have been loaded and are ready to be used.
     fn are_lines_available(&self) -> bool;
+    /// Returns the languages that this [`TextProvider`] can provide text for, including the base language if its [`Language`] is known. Defaults to none.
+    fn available_languages(&self) -> Vec<Language> {
+        Vec::new()
+    }
     /// Gets the [`TextProvider`] as a trait object.
     /// This allows retrieving the concrete type by downcasting, using the `downcast_ref` method available through the `Any` trait.
     fn as_any(&self) -> &dyn Any;
@@ -45,11 +50,15 @@ impl Clone for Box<dyn TextProvider> {
 pub type StringTable = HashMap<LineId, String>;
 
 /// A basic implementation of [`TextProvider`] which keeps the text for the base language,
-/// i.e. the language the Yarn files are written in, and the text for the currently selected translation in memory.
+/// i.e. the language the Yarn files are written in, and the text for any number of translations in memory.
+///
+/// If a line is not translated into the selected language, the languages of its fallback chain are tried in order before falling back to the base language.
+/// See [`StringTableTextProvider::fallback_chain`] for how this chain is built.
 #[derive(Debug, Clone, Default)]
 pub struct StringTableTextProvider {
     base_language_table: StringTable,
-    translation_table: Option<(Language, StringTable)>,
+    translation_tables: HashMap<Language, StringTable>,
+    fallback_languages: HashMap<Language, Vec<Language>>,
     /// Set to `None` to select base language.
     translation_language: Option<Language>,
 }
@@ -65,23 +74,142 @@ impl StringTableTextProvider {
         self.base_language_table.extend(string_table);
     }
 
-    /// Adds strings for the a specific language. If this is not the language used selected by [`TextProvider::set_language`], the strings will be ignored.
+    /// Adds strings for a specific language. Strings for any number of languages can be added, and switching between them with
+    /// [`TextProvider::set_language`] does not discard any of them.
     pub fn extend_translation(
         &mut self,
         language: impl Into<Language>,
         string_table: HashMap<LineId, String>,
     ) {
-        let language = language.into();
-        if let Some((current_language, translation_table)) = self.translation_table.as_mut() {
-            if language == *current_language {
-                translation_table.extend(string_table);
-                return;
+        self.translation_tables
+            .entry(language.into())
+            .or_default()
+            .extend(string_table);
+    }
+
+    /// Sets the languages that `language` falls back to when a line is not translated into it, in order of preference.
+    /// For example, a Brazilian Portuguese translation that is still in progress could fall back to European Portuguese and then to Spanish:
+    ///
+    /// ```rust
+    /// # use yarnspinner_runtime::prelude::*;
+    /// let text_provider = StringTableTextProvider::new().with_fallback_chain("pt-BR", ["pt-PT", "es"]);
+    /// assert_eq!(
+    ///     vec![Language::new("pt-BR"), Language::new("pt"), Language::new("pt-PT"), Language::new("es")],
+    ///     text_provider.fallback_chain(&Language::new("pt-BR"))
+    /// );
+    /// ```
+    pub fn with_fallback_chain<L: Into<Language>>(
+        mut self,
+        language: impl Into<Language>,
+        fallback_languages: impl IntoIterator<Item = L>,
+    ) -> Self {
+        self.set_fallback_chain(language, fallback_languages);
+        self
+    }
+
+    /// Sets the languages that `language` falls back to when a line is not translated into it. See [`StringTableTextProvider::with_fallback_chain`].
+    pub fn set_fallback_chain<L: Into<Language>>(
+        &mut self,
+        language: impl Into<Language>,
+        fallback_languages: impl IntoIterator<Item = L>,
+    ) -> &mut Self {
+        let fallback_languages = fallback_languages.into_iter().map(Into::into).collect();
+        self.fallback_languages
+            .insert(language.into(), fallback_languages);
+        self
+    }
+
+    /// Iterates over all languages that strings were added for with [`StringTableTextProvider::extend_translation`].
+    pub fn translation_languages(&self) -> impl Iterator<Item = &Language> {
+        self.translation_tables.keys()
+    }
+
+    /// Returns the languages that are searched for a line when `language` is selected, in order. The base language is always tried last and is not part of the chain.
+    ///
+    /// The chain starts with `language` itself, followed by its more general versions according to [`Language::parent`], e.g. `pt` for `pt-BR`.
+    /// After that come the languages set with [`StringTableTextProvider::with_fallback_chain`], each also followed by their more general versions.
+    pub fn fallback_chain(&self, language: &Language) -> Vec<Language> {
+        let with_parents =
+            |language: &Language| iter::successors(Some(language.clone()), Language::parent);
+        let configured_fallbacks = self
+            .fallback_languages
+            .get(language)
+            .into_iter()
+            .flatten()
+            .flat_map(with_parents);
+        let mut chain = Vec::new();
+        for language in with_parents(language).chain(configured_fallbacks) {
+            if !chain.contains(&language) {
+                chain.push(language);
             }
         }
-        self.translation_table.replace((language, string_table));
+        chain
+    }
+
+    /// Reports which lines of the base language are not translated into `language` and which language they fall back to instead.
+    /// Useful for finding incomplete translations.
+    pub fn fallback_report(&self, language: &Language) -> FallbackReport {
+        let chain = self.fallback_chain(language);
+        let mut fallback_lines: Vec<_> = self
+            .base_language_table
+            .keys()
+            .filter_map(|line_id| {
+                let (resolved_language, _) = self.resolve(line_id, &chain)?;
+                let language_used = resolved_language.cloned();
+                (language_used.as_ref() != Some(language)).then(|| FallbackLine {
+                    line_id: line_id.clone(),
+                    language_used,
+                })
+            })
+            .collect();
+        fallback_lines.sort_by(|lhs, rhs| lhs.line_id.0.cmp(&rhs.line_id.0));
+        FallbackReport {
+            language: language.clone(),
+            fallback_lines,
+        }
+    }
+
+    /// Looks up a line in the languages of the given chain, then in the base language. A language of [`None`] means the base language.
+    fn resolve<'a>(
+        &'a self,
+        id: &LineId,
+        chain: &'a [Language],
+    ) -> Option<(Option<&'a Language>, &'a String)> {
+        chain
+            .iter()
+            .find_map(|language| {
+                let line = self.translation_tables.get(language)?.get(id)?;
+                Some((Some(language), line))
+            })
+            .or_else(|| self.base_language_table.get(id).map(|line| (None, line)))
+    }
+}
+
+/// A report created by [`StringTableTextProvider::fallback_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackReport {
+    /// The language the report was created for.
+    pub language: Language,
+    /// The lines that are not translated into [`FallbackReport::language`], sorted by their [`LineId`].
+    pub fallback_lines: Vec<FallbackLine>,
+}
+
+impl FallbackReport {
+    /// Returns `true` if every line is translated into [`FallbackReport::language`].
+    pub fn is_complete(&self) -> bool {
+        self.fallback_lines.is_empty()
     }
 }
 
+/// A line that is shown in a different language than the selected one. Part of a [`FallbackReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackLine {
+    /// The ID of the line.
+    pub line_id: LineId,
+    /// The language the line is shown in instead. [`None`] means the base language.
+    pub language_used: Option<Language>,
+}
+
 impl TextProvider for StringTableTextProvider {
     fn clone_shallow(&self) -> Box<dyn TextProvider> {
         Box::new(self.clone())
@@ -92,19 +220,18 @@ impl TextProvider for StringTableTextProvider {
     }
 
     fn get_text(&self, id: &LineId) -> Option<String> {
-        if let Some(language) = self.translation_language.as_ref() {
-            if let Some((registered_language, translation_table)) = self.translation_table.as_ref()
-            {
-                if registered_language != language {
-                    error!("Didn't find language {language} in translations, falling back to base language.");
-                } else if let Some(line) = translation_table.get(id) {
-                    return Some(line.clone());
-                } else {
-                    error!("No translation found for line {id} in language {language}, falling back to base language.");
-                }
-            }
+        let Some(language) = self.translation_language.as_ref() else {
+            return self.base_language_table.get(id).cloned();
+        };
+        let chain = self.fallback_chain(language);
+        let (resolved_language, line) = self.resolve(id, &chain)?;
+        if resolved_language != Some(language) {
+            let fallback = resolved_language
+                .map(|language| format!("language {language}"))
+                .unwrap_or_else(|| "base language".to_owned());
+            error!("No translation found for line {id} in language {language}, falling back to {fallback}.");
         }
-        self.base_language_table.get(id).cloned()
+        Some(line.clone())
     }
 
     fn set_language(&mut self, language_code: Option<Language>) {
@@ -119,11 +246,13 @@ impl TextProvider for StringTableTextProvider {
         let Some(language) = self.translation_language.as_ref() else {
             return !self.base_language_table.is_empty();
         };
-        let translation_language = self
-            .translation_table
-            .as_ref()
-            .map(|(language, _)| language);
-        translation_language == Some(language)
+        self.fallback_chain(language)
+            .iter()
+            .any(|language| self.translation_tables.contains_key(language))
+    }
+
+    fn available_languages(&self) -> Vec<Language> {
+        self.translation_languages().cloned().collect()
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -134,3 +263,97 @@ impl TextProvider for StringTableTextProvider {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_provider() -> StringTableTextProvider {
+        let mut text_provider = StringTableTextProvider::new();
+        text_provider.extend_base_language(HashMap::from([
+            ("line:1".into(), "Hello".to_owned()),
+            ("line:2".into(), "Goodbye".to_owned()),
+            ("line:3".into(), "Thanks".to_owned()),
+        ]));
+        text_provider
+    }
+
+    #[test]
+    fn keeps_multiple_translations() {
+        let mut text_provider = text_provider();
+        text_provider
+            .extend_translation("de", HashMap::from([("line:1".into(), "Hallo".to_owned())]));
+        text_provider.extend_translation(
+            "fr",
+            HashMap::from([("line:1".into(), "Bonjour".to_owned())]),
+        );
+
+        text_provider.set_language(Some("de".into()));
+        assert_eq!(
+            Some("Hallo".to_owned()),
+            text_provider.get_text(&"line:1".into())
+        );
+        text_provider.set_language(Some("fr".into()));
+        assert_eq!(
+            Some("Bonjour".to_owned()),
+            text_provider.get_text(&"line:1".into())
+        );
+    }
+
+    #[test]
+    fn falls_back_along_chain() {
+        let mut text_provider = text_provider().with_fallback_chain("pt-BR", ["es"]);
+        text_provider
+            .extend_translation("pt-BR", HashMap::from([("line:1".into(), "Oi".to_owned())]));
+        text_provider
+            .extend_translation("pt", HashMap::from([("line:2".into(), "Adeus".to_owned())]));
+        text_provider.extend_translation(
+            "es",
+            HashMap::from([
+                ("line:2".into(), "Adiós".to_owned()),
+                ("line:3".into(), "Gracias".to_owned()),
+            ]),
+        );
+        text_provider.set_language(Some("pt-BR".into()));
+
+        assert!(text_provider.are_lines_available());
+        assert_eq!(
+            Some("Oi".to_owned()),
+            text_provider.get_text(&"line:1".into())
+        );
+        assert_eq!(
+            Some("Adeus".to_owned()),
+            text_provider.get_text(&"line:2".into())
+        );
+        assert_eq!(
+            Some("Gracias".to_owned()),
+            text_provider.get_text(&"line:3".into())
+        );
+    }
+
+    #[test]
+    fn reports_fallback_lines() {
+        let mut text_provider = text_provider().with_fallback_chain("pt-BR", ["es"]);
+        text_provider
+            .extend_translation("pt-BR", HashMap::from([("line:1".into(), "Oi".to_owned())]));
+        text_provider
+            .extend_translation("es", HashMap::from([("line:2".into(), "Adiós".to_owned())]));
+
+        let report = text_provider.fallback_report(&Language::new("pt-BR"));
+
+        assert!(!report.is_complete());
+        assert_eq!(
+            vec![
+                FallbackLine {
+                    line_id: "line:2".into(),
+                    language_used: Some(Language::new("es")),
+                },
+                FallbackLine {
+                    line_id: "line:3".into(),
+                    language_used: None,
+                },
+            ],
+            report.fallback_lines
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Formats the values of inline expressions, e.g. `{$gold}` or `{$gold:N0}`, according to the language of the [`Dialogue`].
+
+use crate::prelude::*;
+
+#[cfg(feature = "icu_plurals")]
+mod icu;
+#[cfg(not(feature = "icu_plurals"))]
+mod plain;
+
+#[cfg(feature = "icu_plurals")]
+use self::icu::format_number;
+#[cfg(not(feature = "icu_plurals"))]
+use self::plain::format_number;
+
+/// Formats a value that is substituted into a line.
+///
+/// Numbers use the digits, decimal separator and minus sign of the language, which defaults to [`Language::default`].
+/// Without a [`FormatSpecifier`], they are written with as many decimals as needed and without grouping separators,
+/// so `1000000.5` stays `1000000.5` in English and becomes `1000000,5` in German.
+/// Without the `icu_plurals` feature, there is no data about languages, so numbers are formatted like in English in every language.
+/// Strings and booleans are inserted as they are.
+pub(crate) fn format_value(
+    value: &YarnValue,
+    format_specifier: Option<FormatSpecifier>,
+    language: Option<&Language>,
+) -> String {
+    match value {
+        YarnValue::Number(number) => {
+            format_number(*number, format_specifier, language).unwrap_or_else(|| value.to_string())
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Parses the inside of a substitution marker like `0` or `0:N0`.
+pub(crate) fn parse_substitution_marker(marker: &str) -> Option<(usize, Option<FormatSpecifier>)> {
+    let (index, format_specifier) = match marker.split_once(':') {
+        Some((index, format_specifier)) => (index, Some(format_specifier.parse().ok()?)),
+        None => (marker, None),
+    };
+    if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((index.parse().ok()?, format_specifier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "icu_plurals")]
+    fn formats_numbers_for_language() {
+        for (value, format_specifier, language, expected) in [
+            (1_000_000.5, None, None, "1000000.5"),
+            (0.3, None, None, "0.3"),
+            (-2.0, None, None, "-2"),
+            (1_000_000.5, None, Some("de"), "1000000,5"),
+            (1_234.5, Some("N"), None, "1,234.50"),
+            (1_234.5, Some("N0"), None, "1,235"),
+            (1_234.5, Some("N0"), Some("de"), "1.235"),
+            (1_234.5, Some("N1"), Some("fr"), "1\u{202f}234,5"),
+            (1_234.5, Some("F1"), Some("de"), "1234,5"),
+            (0.125, Some("P1"), None, "12.5%"),
+            (0.5, Some("P0"), None, "50%"),
+            (7.0, Some("D3"), None, "007"),
+            (1_234.5, Some("N0"), Some("ar-EG"), "١٬٢٣٥"),
+        ] {
+            let format_specifier = format_specifier.map(|s| s.parse().unwrap());
+            let language = language.map(Language::new);
+
+            let formatted = format_value(
+                &YarnValue::Number(value),
+                format_specifier,
+                language.as_ref(),
+            );
+
+            assert_eq!(
+                expected, formatted,
+                "{value} with {format_specifier:?} in {language:?}"
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "icu_plurals"))]
+    fn formats_numbers_without_locale_data() {
+        for (value, format_specifier, language, expected) in [
+            (1_000_000.5, None, None, "1000000.5"),
+            (0.3, None, None, "0.3"),
+            (-2.0, None, None, "-2"),
+            (1_000_000.5, None, Some("de"), "1000000.5"),
+            (1_234.5, Some("N"), None, "1,234.50"),
+            (-1_234_567.0, Some("N0"), None, "-1,234,567"),
+            (1_234.5, Some("N0"), Some("de"), "1,235"),
+            (1_234.5, Some("F1"), Some("de"), "1234.5"),
+            (0.125, Some("P1"), None, "12.5%"),
+            (0.5, Some("P0"), None, "50%"),
+            (7.0, Some("D3"), None, "007"),
+            (-7.0, Some("D3"), None, "-007"),
+            (f32::NAN, Some("N0"), None, "NaN"),
+        ] {
+            let format_specifier = format_specifier.map(|s| s.parse().unwrap());
+            let language = language.map(Language::new);
+
+            let formatted = format_value(
+                &YarnValue::Number(value),
+                format_specifier,
+                language.as_ref(),
+            );
+
+            assert_eq!(
+                expected, formatted,
+                "{value} with {format_specifier:?} in {language:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn inserts_other_values_as_they_are() {
+        let format_specifier = Some(FormatSpecifier::Number { decimals: 2 });
+
+        assert_eq!("Mae", format_value(&"Mae".into(), format_specifier, None));
+        assert_eq!("true", format_value(&true.into(), format_specifier, None));
+    }
+
+    #[test]
+    fn parses_substitution_markers() {
+        assert_eq!(Some((0, None)), parse_substitution_marker("0"));
+        assert_eq!(
+            Some((12, Some(FormatSpecifier::Number { decimals: 0 }))),
+            parse_substitution_marker("12:N0")
+        );
+        assert_eq!(None, parse_substitution_marker("0:X"));
+        assert_eq!(None, parse_substitution_marker("$gold"));
+        assert_eq!(None, parse_substitution_marker(""));
+    }
+}
@@ -0,0 +1,76 @@
+//! Formats numbers with the data of ICU, which covers every locale in CLDR.
+
+use crate::prelude::*;
+use fixed_decimal::FixedDecimal;
+use icu_decimal::options::{FixedDecimalFormatterOptions, GroupingStrategy};
+use icu_decimal::FixedDecimalFormatter;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+thread_local! {
+    /// Creating a formatter loads its data, so each one is only created once per thread.
+    /// `None` if ICU has no formatter for the language.
+    static FORMATTERS: RefCell<HashMap<(Language, GroupingStrategy), Option<FixedDecimalFormatter>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Formats a number with the digits, decimal separator and minus sign of the language.
+/// Returns `None` for NaN and infinity.
+pub(crate) fn format_number(
+    number: f32,
+    format_specifier: Option<FormatSpecifier>,
+    language: Option<&Language>,
+) -> Option<String> {
+    // Going through the shortest representation avoids printing `0.3` as `0.30000001192092896`.
+    let mut decimal = FixedDecimal::from_str(&number.to_string()).ok()?;
+    let grouping_strategy = match format_specifier {
+        None | Some(FormatSpecifier::FixedPoint { .. } | FormatSpecifier::Decimal { .. }) => {
+            GroupingStrategy::Never
+        }
+        Some(FormatSpecifier::Number { .. } | FormatSpecifier::Percent { .. }) => {
+            GroupingStrategy::Auto
+        }
+    };
+    let suffix = match format_specifier {
+        None => "",
+        Some(FormatSpecifier::Number { decimals } | FormatSpecifier::FixedPoint { decimals }) => {
+            round_to_decimals(&mut decimal, decimals);
+            ""
+        }
+        Some(FormatSpecifier::Percent { decimals }) => {
+            decimal.multiply_pow10(2);
+            decimal.trim_start();
+            round_to_decimals(&mut decimal, decimals);
+            "%"
+        }
+        Some(FormatSpecifier::Decimal { digits }) => {
+            decimal.half_expand(0);
+            decimal.pad_start(digits.into());
+            ""
+        }
+    };
+
+    let language = language.cloned().unwrap_or_default();
+    let formatted = FORMATTERS.with_borrow_mut(|formatters| {
+        let formatter = formatters
+            .entry((language, grouping_strategy))
+            .or_insert_with_key(|(language, grouping_strategy)| {
+                let mut options = FixedDecimalFormatterOptions::default();
+                options.grouping_strategy = *grouping_strategy;
+                FixedDecimalFormatter::try_new(&(&language.0).into(), options).ok()
+            });
+        match formatter {
+            Some(formatter) => formatter.format_to_string(&decimal),
+            None => decimal.to_string(),
+        }
+    });
+    Some(format!("{formatted}{suffix}"))
+}
+
+/// Rounds half away from zero like .NET does, and pads with zeros.
+fn round_to_decimals(decimal: &mut FixedDecimal, decimals: u8) {
+    let position = -i16::from(decimals);
+    decimal.half_expand(position);
+    decimal.pad_end(position);
+}
@@ -0,0 +1,61 @@
+//! Formats numbers without any locale data, like .NET's invariant culture.
+
+use crate::prelude::*;
+
+/// Formats a number with `.` as the decimal separator and, if the [`FormatSpecifier`] asks for it, `,` as the grouping separator.
+/// The language is ignored. Returns `None` for NaN and infinity.
+pub(crate) fn format_number(
+    number: f32,
+    format_specifier: Option<FormatSpecifier>,
+    _language: Option<&Language>,
+) -> Option<String> {
+    if !number.is_finite() {
+        return None;
+    }
+    // Going through the shortest representation avoids printing `0.3` as `0.30000001192092896`.
+    let number: f64 = number.to_string().parse().ok()?;
+    let formatted = match format_specifier {
+        None => number.to_string(),
+        Some(FormatSpecifier::Number { decimals }) => group_thousands(&round(number, decimals)),
+        Some(FormatSpecifier::FixedPoint { decimals }) => round(number, decimals),
+        Some(FormatSpecifier::Percent { decimals }) => {
+            format!("{}%", group_thousands(&round(number * 100.0, decimals)))
+        }
+        Some(FormatSpecifier::Decimal { digits }) => {
+            let rounded = number.round();
+            let sign = if rounded < 0.0 { "-" } else { "" };
+            let digits = usize::from(digits);
+            format!("{sign}{:0>digits$}", rounded.abs())
+        }
+    };
+    Some(formatted)
+}
+
+/// Rounds half away from zero like .NET does, and pads with zeros.
+fn round(number: f64, decimals: u8) -> String {
+    let factor = 10_f64.powi(decimals.into());
+    let rounded = (number * factor).round() / factor;
+    format!("{rounded:.0$}", usize::from(decimals))
+}
+
+/// Inserts a `,` between every group of three digits before the decimal separator.
+fn group_thousands(number: &str) -> String {
+    let (sign, number) = match number.strip_prefix('-') {
+        Some(number) => ("-", number),
+        None => ("", number),
+    };
+    let (integer, fraction) = match number.find('.') {
+        Some(index) => number.split_at(index),
+        None => (number, ""),
+    };
+    let mut grouped = String::with_capacity(number.len() + integer.len() / 3 + 1);
+    grouped.push_str(sign);
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped.push_str(fraction);
+    grouped
+}
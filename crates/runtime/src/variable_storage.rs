@@ -57,9 +57,25 @@ impl Extend<(String, YarnValue)> for Box<dyn VariableStorage> {
 #[allow(missing_docs)]
 #[derive(Debug)]
 pub enum VariableStorageError {
-    InvalidVariableName { name: String },
-    VariableNotFound { name: String },
-    InternalError { error: Box<dyn Error + Send + Sync> },
+    InvalidVariableName {
+        name: String,
+    },
+    VariableNotFound {
+        name: String,
+    },
+    InternalError {
+        error: Box<dyn Error + Send + Sync>,
+    },
+    /// The value does not have the type the variable was declared with.
+    TypeMismatch {
+        name: String,
+        expected: Type,
+        actual: Type,
+    },
+    /// The storage only accepts declared variables, but this one was never declared.
+    UndeclaredVariable {
+        name: String,
+    },
 }
 
 impl Error for VariableStorageError {}
@@ -71,6 +87,8 @@ impl Display for VariableStorageError {
             InvalidVariableName { name } => write!(f, "{name} is not a valid variable name: Variable names must start with a \'$\'. (Did you mean to use \'${name}\'?)"),
             VariableNotFound { name } => write!(f, "Variable name {name} is not defined"),
             InternalError { error } => write!(f, "Internal variable storage error: {error}"),
+            TypeMismatch { name, expected, actual } => write!(f, "Cannot assign a value of type {actual} to {name}, which was declared as {expected}"),
+            UndeclaredVariable { name } => write!(f, "Variable {name} was never declared"),
         }
     }
 }
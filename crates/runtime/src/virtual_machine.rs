@@ -3,32 +3,42 @@
 //! ## Implementation Notes
 //! The `Operand` extensions and the `Operator` enum were moved into upstream crates to make them not depend on the runtime.
 
-pub(crate) use self::{execution_state::*, state::*};
+pub(crate) use self::{execution_state::*, snapshot::*, state::*};
 use crate::markup::{LineParser, ParsedMarkup};
 use crate::prelude::*;
+use crate::value_formatting::{format_value, parse_substitution_marker};
 use crate::Result;
 use log::*;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt::Debug;
 use yarnspinner_core::prelude::OpCode;
 use yarnspinner_core::prelude::*;
 
 mod execution_state;
+mod snapshot;
 mod state;
 
+/// The prefix of the variables the dialogue uses to track its own state, such as how often a node was visited.
+/// Changes to them are not reported as [`DialogueEvent::VariableChanged`].
+const INTERNAL_VARIABLE_PREFIX: &str = "$Yarn.Internal.";
+
 #[derive(Debug, Clone)]
 pub(crate) struct VirtualMachine {
     pub(crate) library: Library,
     pub(crate) program: Option<Program>,
     pub(crate) variable_storage: Box<dyn VariableStorage>,
     pub(crate) line_hints_enabled: bool,
+    pub(crate) instruction_tracking_enabled: bool,
     current_node_name: Option<String>,
     state: State,
     execution_state: ExecutionState,
     current_node: Option<Node>,
     batched_events: Vec<DialogueEvent>,
+    executed_instructions: HashMap<String, BTreeSet<usize>>,
     line_parser: LineParser,
     text_provider: Box<dyn TextProvider>,
     language_code: Option<Language>,
+    pub(crate) seen_lines: SeenLines,
 }
 
 impl Iterator for VirtualMachine {
@@ -60,7 +70,10 @@ impl VirtualMachine {
             execution_state: Default::default(),
             current_node: Default::default(),
             batched_events: Default::default(),
+            executed_instructions: Default::default(),
             line_hints_enabled: Default::default(),
+            instruction_tracking_enabled: Default::default(),
+            seen_lines: Default::default(),
         }
     }
 
@@ -201,6 +214,9 @@ impl VirtualMachine {
         while self.execution_state == ExecutionState::Running {
             let current_node = self.current_node.clone().unwrap();
             let current_instruction = &current_node.instructions[self.state.program_counter];
+            if self.instruction_tracking_enabled {
+                self.track_instruction(&current_node.name);
+            }
             self.run_instruction(current_instruction)?;
             // ## Implementation note
             // The original increments the program counter here, but that leads to intentional underflow on [`OpCode::RunNode`],
@@ -219,6 +235,24 @@ impl VirtualMachine {
         Ok(std::mem::take(&mut self.batched_events))
     }
 
+    fn track_instruction(&mut self, node_name: &str) {
+        let program_counter = self.state.program_counter;
+        if let Some(instructions) = self.executed_instructions.get_mut(node_name) {
+            instructions.insert(program_counter);
+        } else {
+            self.executed_instructions
+                .insert(node_name.to_owned(), BTreeSet::from([program_counter]));
+        }
+    }
+
+    pub(crate) fn pop_executed_instructions(&mut self) -> HashMap<String, BTreeSet<usize>> {
+        std::mem::take(&mut self.executed_instructions)
+    }
+
+    pub(crate) fn line_parser_mut(&mut self) -> &mut LineParser {
+        &mut self.line_parser
+    }
+
     pub(crate) fn parse_markup(&mut self, line: &str) -> crate::markup::Result<ParsedMarkup> {
         self.line_parser.parse_markup(line)
     }
@@ -279,6 +313,32 @@ impl VirtualMachine {
         self.current_node_name.clone()
     }
 
+    pub(crate) fn current_options(&self) -> &[DialogueOption] {
+        &self.state.current_options
+    }
+
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            node_name: self.current_node_name.clone(),
+            state: self.state.clone(),
+            execution_state: self.execution_state,
+        }
+    }
+
+    /// Moves back to the position stored in the snapshot. Events that were not returned yet are discarded.
+    pub(crate) fn restore(&mut self, snapshot: Snapshot) -> Result<()> {
+        self.current_node = snapshot
+            .node_name
+            .as_deref()
+            .map(|node_name| self.get_node_from_name(node_name).cloned())
+            .transpose()?;
+        self.current_node_name = snapshot.node_name;
+        self.state = snapshot.state;
+        self.execution_state = snapshot.execution_state;
+        self.batched_events.clear();
+        Ok(())
+    }
+
     /// ## Implementation note
     ///
     /// Increments the program counter here instead of in `continue_` for cleaner code
@@ -311,6 +371,7 @@ impl VirtualMachine {
 
                 let substitutions = self.pop_substitutions_with_count_at_operand(instruction, 1);
                 let line = self.prepare_line(string_id, &substitutions)?;
+                self.seen_lines.insert(line.id.clone());
 
                 self.batched_events.push(DialogueEvent::Line(line));
 
@@ -331,7 +392,7 @@ impl VirtualMachine {
                     .into_iter()
                     .enumerate()
                     .fold(command_text, |command_text, (i, substitution)| {
-                        command_text.replace(&format!("{{{i}}}"), &substitution)
+                        command_text.replace(&format!("{{{i}}}"), &substitution.to_string())
                     });
                 let command = Command::parse(command_text);
 
@@ -523,9 +584,23 @@ impl VirtualMachine {
             }
             OpCode::StoreVariable => {
                 // Store the top value on the stack in a variable.
-                let top_value = self.state.peek_value().clone();
+                let top_value: YarnValue = self.state.peek_value().clone().into();
                 let variable_name: String = instruction.read_operand(0);
-                self.variable_storage.set(variable_name, top_value.into())?;
+                let old_value = self.variable_storage.get(&variable_name).ok().or_else(|| {
+                    let initial_value =
+                        self.program.as_ref()?.initial_values.get(&variable_name)?;
+                    Some(initial_value.clone().into())
+                });
+                self.variable_storage
+                    .set(variable_name.clone(), top_value.clone())?;
+                let is_internal = variable_name.starts_with(INTERNAL_VARIABLE_PREFIX);
+                if !is_internal && old_value.as_ref() != Some(&top_value) {
+                    self.batched_events.push(DialogueEvent::VariableChanged {
+                        name: variable_name,
+                        old: old_value,
+                        new: top_value,
+                    });
+                }
                 self.state.program_counter += 1;
             }
             OpCode::Stop => {
@@ -554,18 +629,20 @@ impl VirtualMachine {
         Ok(())
     }
 
-    fn prepare_line(&mut self, string_id: LineId, substitutions: &[String]) -> Result<Line> {
+    fn prepare_line(&mut self, string_id: LineId, substitutions: &[YarnValue]) -> Result<Line> {
         let line_text = self.text_provider.get_text(&string_id).ok_or_else(|| {
             DialogueError::LineProviderError {
                 id: string_id.clone(),
                 language_code: self.language_code.clone(),
             }
         })?;
-        let substituted_text = expand_substitutions(&line_text, substitutions);
+        let substituted_text =
+            expand_substitutions(&line_text, substitutions, self.language_code.as_ref());
         let markup = self
             .parse_markup(&substituted_text)
             .map_err(DialogueError::MarkupParseError)?;
         let line = Line {
+            is_seen: self.seen_lines.contains(&string_id),
             id: string_id,
             text: markup.text,
             attributes: markup.attributes,
@@ -602,7 +679,7 @@ impl VirtualMachine {
         &mut self,
         instruction: &Instruction,
         index: usize,
-    ) -> Vec<String> {
+    ) -> Vec<YarnValue> {
         let expression_count: usize = instruction.operands[index].clone().try_into().unwrap();
         let mut values: Vec<_> = (0..expression_count)
             .rev()
@@ -624,16 +701,65 @@ fn assert_up_to_date_compiler(predicate: bool) {
 /// Replaces all substitution markers in a text with the given substitution list.
 ///
 /// This method replaces substitution markers
-/// -  for example, `{0}` - with the corresponding entry in `substitutions`.
+/// -  for example, `{0}` or `{0:N0}` - with the corresponding entry in `substitutions`,
+/// formatted for the given language.
 /// If `test` contains a substitution marker whose
 /// index is not present in `substitutions`, it is
 /// ignored.
 #[must_use]
-fn expand_substitutions(text: &str, substitutions: &[String]) -> String {
-    substitutions
-        .iter()
-        .enumerate()
-        .fold(text.to_owned(), |text, (i, substitution)| {
-            text.replace(&format!("{{{i}}}",), substitution)
-        })
+fn expand_substitutions(
+    text: &str,
+    substitutions: &[YarnValue],
+    language: Option<&Language>,
+) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        let (before, marker) = rest.split_at(start);
+        expanded.push_str(before);
+        let substitution = marker.find('}').and_then(|end| {
+            let (index, format_specifier) = parse_substitution_marker(&marker[1..end])?;
+            let value = substitutions.get(index)?;
+            Some((format_value(value, format_specifier, language), end))
+        });
+        match substitution {
+            Some((substitution, end)) => {
+                expanded.push_str(&substitution);
+                rest = &marker[end + 1..];
+            }
+            None => {
+                expanded.push('{');
+                rest = &marker[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_substitutions_with_format_specifiers() {
+        let substitutions = [YarnValue::from(1234.5), YarnValue::from("Mae")];
+
+        let expanded = expand_substitutions(
+            "{1} has {0:N0} gold ({0}) and {2} {friends} {0:X}",
+            &substitutions,
+            Some(&Language::new("de-CH")),
+        );
+
+        // Without ICU, numbers are formatted the same way in every language
+        let gold = if cfg!(feature = "icu_plurals") {
+            "1’235"
+        } else {
+            "1,235"
+        };
+        assert_eq!(
+            format!("Mae has {gold} gold (1234.5) and {{2}} {{friends}} {{0:X}}"),
+            expanded
+        );
+    }
 }
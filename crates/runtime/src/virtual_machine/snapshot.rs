@@ -0,0 +1,10 @@
+use crate::prelude::*;
+
+/// The position of the [`VirtualMachine`] in the program, as stored in the [`DialogueHistory`].
+/// The node itself is looked up by name again when restoring, so the snapshot stays cheap to clone.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Snapshot {
+    pub(crate) node_name: Option<String>,
+    pub(crate) state: State,
+    pub(crate) execution_state: ExecutionState,
+}
@@ -0,0 +1,98 @@
+use std::fmt::Write;
+
+/// How many unchanged lines are shown around every change.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+/// Renders a line-by-line diff between two texts, marking lines only in `expected` with `-` and lines only in `actual` with `+`.
+/// Long runs of unchanged lines are collapsed into `...`.
+pub(crate) fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+
+    // common[i][j] is the length of the longest common subsequence of expected[i..] and actual[j..]
+    let mut common = vec![vec![0_usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            changes.push((Change::Unchanged, expected[i]));
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || common[i + 1][j] >= common[i][j + 1])
+        {
+            changes.push((Change::Removed, expected[i]));
+            i += 1;
+        } else {
+            changes.push((Change::Added, actual[j]));
+            j += 1;
+        }
+    }
+
+    let is_near_change = |index: usize| {
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + CONTEXT_LINES + 1).min(changes.len());
+        changes[start..end]
+            .iter()
+            .any(|(change, _)| *change != Change::Unchanged)
+    };
+    let mut diff = String::new();
+    let mut skipping = false;
+    for (index, (change, line)) in changes.iter().enumerate() {
+        let marker = match change {
+            Change::Unchanged if !is_near_change(index) => {
+                if !skipping {
+                    diff.push_str("  ...\n");
+                    skipping = true;
+                }
+                continue;
+            }
+            Change::Unchanged => ' ',
+            Change::Removed => '-',
+            Change::Added => '+',
+        };
+        skipping = false;
+        writeln!(diff, "{marker} {line}").unwrap();
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marks_removed_and_added_lines() {
+        let expected = "a\nb\nc\nd\n";
+        let actual = "a\nc\nx\nd\n";
+
+        assert_eq!("  a\n- b\n  c\n+ x\n  d\n", diff_lines(expected, actual));
+    }
+
+    #[test]
+    fn collapses_unchanged_lines() {
+        let expected = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let actual = "1\n2\n3\n4\n5\n6\n7\n8\nnine\n";
+
+        assert_eq!(
+            "  ...\n  6\n  7\n  8\n- 9\n+ nine\n",
+            diff_lines(expected, actual)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// How many steps of the [`TestFailure::transcript`] are shown before the point of failure when displaying a [`TestFailure`].
+const CONTEXT_STEPS: usize = 5;
+
+/// The reason a [`TestPlan`](crate::prelude::TestPlan) failed, returned by [`TestRunner::run`](crate::prelude::TestRunner::run)
+/// and [`TestRunner::record_transcript`](crate::prelude::TestRunner::record_transcript).
+///
+/// Its [`Display`] implementation renders a diff in the `.testplan` format, e.g.
+///
+/// ```text
+/// The dialogue did not follow the test plan at step 3:
+///   line: Mae: Hello!
+///   command: wave
+/// - line: Mae: How are you?
+/// + line: Mae: Goodbye!
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestFailure {
+    /// What happened.
+    pub kind: TestFailureKind,
+    /// Everything the dialogue did as expected before the failure, in the `.testplan` format,
+    /// or as [`Transcript::entries`](crate::prelude::Transcript::entries) when recording a transcript.
+    pub transcript: Vec<String>,
+}
+
+/// The kind of a [`TestFailure`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestFailureKind {
+    /// The dialogue did something other than what the test plan expected.
+    Mismatch {
+        /// The steps the test plan expected, in the `.testplan` format.
+        expected: Vec<String>,
+        /// The steps the dialogue took instead, in the `.testplan` format.
+        actual: Vec<String>,
+    },
+    /// The test plan selected an option that doesn't exist.
+    InvalidSelection {
+        /// The 1-based number of the selected option.
+        selection: usize,
+        /// The number of options that were presented.
+        option_count: usize,
+    },
+    /// The dialogue presented options, but [`TestRunner::record_transcript`](crate::prelude::TestRunner::record_transcript) ran out of choices.
+    OutOfChoices {
+        /// The number of options that were presented.
+        option_count: usize,
+    },
+    /// A command handler registered with [`TestRunner::with_command`](crate::prelude::TestRunner::with_command) returned an error.
+    CommandFailed {
+        /// The text of the command, without the surrounding `<<` and `>>`.
+        command: String,
+        /// The error returned by the handler.
+        message: String,
+    },
+    /// The dialogue itself returned an error, e.g. because a node or function doesn't exist.
+    DialogueError {
+        /// The description of the error.
+        message: String,
+    },
+}
+
+impl Error for TestFailure {}
+
+impl Display for TestFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let steps = self.transcript.len();
+        let step = steps + 1;
+        match &self.kind {
+            TestFailureKind::Mismatch { .. } => writeln!(
+                f,
+                "The dialogue did not follow the test plan at step {step}:"
+            )?,
+            TestFailureKind::InvalidSelection {
+                selection,
+                option_count,
+            } => writeln!(
+                f,
+                "The test plan selected option {selection} at step {step}, but only {option_count} options were presented:"
+            )?,
+            TestFailureKind::OutOfChoices { option_count } => writeln!(
+                f,
+                "The dialogue presented {option_count} options at step {step}, but there are no choices left:"
+            )?,
+            TestFailureKind::CommandFailed { command, message } => writeln!(
+                f,
+                "The command \"{command}\" failed at step {step}: {message}"
+            )?,
+            TestFailureKind::DialogueError { message } => {
+                writeln!(f, "The dialogue failed at step {step}: {message}")?
+            }
+        }
+
+        if steps > CONTEXT_STEPS {
+            writeln!(f, "  ...")?;
+        }
+        for step in &self.transcript[steps.saturating_sub(CONTEXT_STEPS)..] {
+            writeln!(f, "  {step}")?;
+        }
+        if let TestFailureKind::Mismatch { expected, actual } = &self.kind {
+            for step in expected {
+                writeln!(f, "- {step}")?;
+            }
+            for step in actual {
+                writeln!(f, "+ {step}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+//! Regression tests for Yarn Spinner dialogue.
+//!
+//! A [`TestPlan`](prelude::TestPlan) describes what a dialogue is expected to do: which lines, options and commands it delivers,
+//! which options to select and what values variables should have along the way. Plans can be built in code or read from `.testplan` files,
+//! the format used by the test suite of Yarn Spinner itself.
+//! A [`TestRunner`](prelude::TestRunner) plays the dialogue against a plan, standing in for the game's functions and commands,
+//! and reports the first difference as a readable diff.
+//!
+//! For golden-file tests, [`TestRunner::record_transcript`](prelude::TestRunner::record_transcript) plays the dialogue with a scripted list of choices
+//! and renders everything that happens as a [`Transcript`](prelude::Transcript), which can be compared against a checked-in [`Snapshot`](prelude::Snapshot).
+//!
+//! ## Example
+//!
+//! ```rust
+//! # use yarnspinner::prelude::*;
+//! # use yarnspinner_testing::prelude::*;
+//! let file = YarnFile {
+//!     file_name: "example.yarn".to_string(),
+//!     source: "title: Start\n---\nMae: Hello!\n-> Hi!\n-> Go away.\n    Mae: Fine.\n===".to_string(),
+//! };
+//! let compilation = YarnCompiler::new().add_file(file).compile().unwrap();
+//!
+//! let test_plan = TestPlan::parse(
+//!     "line: Mae: Hello!
+//!      option: Hi!
+//!      option: Go away.
+//!      select: 2
+//!      line: Mae: Fine.",
+//! )
+//! .unwrap();
+//!
+//! TestRunner::new(&compilation).assert_plan(&test_plan);
+//! ```
+#![warn(missing_docs, missing_debug_implementations)]
+
+mod diff;
+mod failure;
+mod runner;
+mod snapshot;
+mod step;
+mod test_plan;
+mod transcript;
+
+pub mod prelude {
+    //! Everything you need to write tests for your dialogue.
+    pub(crate) use crate::step::Step;
+    pub use crate::{
+        failure::*,
+        runner::{CommandHandler, TestRunner},
+        snapshot::*,
+        step::{ExpectedStepType, StepValue},
+        test_plan::*,
+        transcript::*,
+    };
+    pub(crate) use yarnspinner::core::YarnValue;
+}
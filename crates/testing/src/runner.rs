@@ -0,0 +1,456 @@
+use crate::prelude::*;
+use crate::step::format_step;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::path::PathBuf;
+use yarnspinner::compiler::Compilation;
+use yarnspinner::core::{IntoYarnValueFromNonYarnValue, Library, YarnFn};
+use yarnspinner::runtime::{
+    Command, Dialogue, DialogueEvent, DialogueOption, MemoryVariableStorage, OptionId,
+    StringTableTextProvider, VariableStorage,
+};
+
+/// A function that stands in for the game when the dialogue runs a command. See [`TestRunner::with_command`].
+pub type CommandHandler = Box<dyn FnMut(&Command, &mut dyn VariableStorage) -> Result<(), String>>;
+
+/// Runs a [`Dialogue`] against a [`TestPlan`] and reports the first place where they disagree.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner::prelude::*;
+/// # use yarnspinner_testing::prelude::*;
+/// let file = YarnFile {
+///     file_name: "example.yarn".to_string(),
+///     source: "title: Start\n---\n<<declare $gold = 0>>\nMae: Hello!\n<<give_gold 5>>\n===".to_string(),
+/// };
+/// let compilation = YarnCompiler::new().add_file(file).compile().unwrap();
+///
+/// let mut runner = TestRunner::new(&compilation).with_command("give_gold", |command, variable_storage| {
+///     let amount = f32::try_from(&command.parameters[0]).map_err(|e| e.to_string())?;
+///     variable_storage.set("$gold".to_owned(), amount.into()).map_err(|e| e.to_string())
+/// });
+///
+/// runner.assert_plan(
+///     &TestPlan::new()
+///         .expect_line("Mae: Hello!")
+///         .expect_command("give_gold 5")
+///         .expect_variable("$gold", 5)
+///         .expect_stop(),
+/// );
+/// ```
+pub struct TestRunner {
+    dialogue: Dialogue,
+    start_node: String,
+    command_handlers: HashMap<String, CommandHandler>,
+}
+
+impl Debug for TestRunner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestRunner")
+            .field("dialogue", &self.dialogue)
+            .field("start_node", &self.start_node)
+            .field(
+                "command_handlers",
+                &self.command_handlers.keys().collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl TestRunner {
+    /// Creates a runner for the program of the given [`Compilation`], with its lines in the base language
+    /// and all variables stored in memory.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the compilation has no program, i.e. if it was compiled with [`CompilationType::StringsOnly`](yarnspinner::compiler::CompilationType::StringsOnly).
+    pub fn new(compilation: &Compilation) -> Self {
+        let program = compilation
+            .program
+            .clone()
+            .expect("Cannot run a test plan against a compilation without a program");
+        let mut text_provider = StringTableTextProvider::new();
+        text_provider.extend_base_language(
+            compilation
+                .string_table
+                .iter()
+                .map(|(line_id, string_info)| (line_id.clone(), string_info.text.clone()))
+                .collect(),
+        );
+        let mut dialogue = Dialogue::new(
+            Box::new(MemoryVariableStorage::new()),
+            Box::new(text_provider),
+        );
+        dialogue.add_program(program);
+        Self::from_dialogue(dialogue)
+    }
+
+    /// Creates a runner for a [`Dialogue`] that was already set up, e.g. with the game's own [`VariableStorage`] or a translation.
+    pub fn from_dialogue(dialogue: Dialogue) -> Self {
+        Self {
+            dialogue,
+            start_node: "Start".to_owned(),
+            command_handlers: HashMap::new(),
+        }
+    }
+
+    /// Sets the node the dialogue starts at. Defaults to `"Start"`.
+    pub fn with_start_node(mut self, node_name: impl Into<String>) -> Self {
+        self.start_node = node_name.into();
+        self
+    }
+
+    /// Registers a function that the script can call, like [`Library::add_function`].
+    pub fn with_function<Marker, F>(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        function: F,
+    ) -> Self
+    where
+        Marker: 'static,
+        F: YarnFn<Marker> + 'static + Clone,
+        F::Out: IntoYarnValueFromNonYarnValue + 'static + Clone,
+    {
+        self.dialogue.library_mut().add_function(name, function);
+        self
+    }
+
+    /// Registers all functions of the given [`Library`], e.g. the one the game uses.
+    pub fn with_library(mut self, library: Library) -> Self {
+        self.dialogue.library_mut().import(library);
+        self
+    }
+
+    /// Registers a function that is called instead of the game whenever the dialogue runs the command with the given name.
+    /// It may change variables to simulate what the command would do in the game.
+    /// Returning an error fails the test with [`TestFailureKind::CommandFailed`].
+    ///
+    /// Commands without a handler are only checked against the test plan.
+    pub fn with_command(
+        mut self,
+        name: impl Into<String>,
+        handler: impl FnMut(&Command, &mut dyn VariableStorage) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.command_handlers.insert(name.into(), Box::new(handler));
+        self
+    }
+
+    /// Sets a variable before the dialogue starts, overriding its declared initial value.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the variable name doesn't start with `$`.
+    pub fn with_variable(mut self, name: impl Into<String>, value: impl Into<YarnValue>) -> Self {
+        let name = name.into();
+        self.dialogue
+            .variable_storage_mut()
+            .set(name.clone(), value.into())
+            .unwrap_or_else(|e| panic!("Failed to set variable {name}: {e}"));
+        self
+    }
+
+    /// Returns the [`Dialogue`] the test plans are run against.
+    pub fn dialogue(&self) -> &Dialogue {
+        &self.dialogue
+    }
+
+    /// Returns the [`Dialogue`] the test plans are run against.
+    pub fn dialogue_mut(&mut self) -> &mut Dialogue {
+        &mut self.dialogue
+    }
+
+    /// Runs the dialogue from the start node until it ends, checking every step against the test plan.
+    ///
+    /// Variables are not reset between runs, so running multiple plans on the same runner continues where the last one left off.
+    pub fn run(&mut self, test_plan: &TestPlan) -> Result<(), TestFailure> {
+        let mut run = Run {
+            test_plan: test_plan.clone(),
+            transcript: Vec::new(),
+        };
+        self.dialogue
+            .set_node(self.start_node.clone())
+            .map_err(|e| run.dialogue_error(e))?;
+        loop {
+            let events = self
+                .dialogue
+                .continue_()
+                .map_err(|e| run.dialogue_error(e))?;
+            for event in events {
+                match event {
+                    DialogueEvent::Line(line) => {
+                        run.advance(&self.dialogue)?;
+                        let step = run.expect(ExpectedStepType::Line, line.text)?;
+                        run.transcript.push(step);
+                    }
+                    DialogueEvent::Options(options) => {
+                        run.advance(&self.dialogue)?;
+                        let selection = run.expect_options(&options)?;
+                        self.dialogue
+                            .set_selected_option(selection)
+                            .map_err(|e| run.dialogue_error(e))?;
+                    }
+                    DialogueEvent::Command(command) => {
+                        run.advance(&self.dialogue)?;
+                        let step = run.expect(ExpectedStepType::Command, command.raw.clone())?;
+                        self.run_command_handler(&command)
+                            .map_err(|kind| run.failure(kind))?;
+                        run.transcript.push(step);
+                    }
+                    DialogueEvent::DialogueComplete => {
+                        run.advance(&self.dialogue)?;
+                        if run.test_plan.next_expected_step != ExpectedStepType::Stop {
+                            return Err(run.mismatch(vec![format_step(
+                                ExpectedStepType::Stop,
+                                None,
+                                true,
+                            )]));
+                        }
+                        return Ok(());
+                    }
+                    DialogueEvent::NodeStart(_)
+                    | DialogueEvent::NodeComplete(_)
+                    | DialogueEvent::LineHints(_)
+                    | DialogueEvent::VariableChanged { .. } => {}
+                }
+            }
+        }
+    }
+
+    /// Like [`TestRunner::run`], but panics with a readable diff if the test plan fails.
+    pub fn assert_plan(&mut self, test_plan: &TestPlan) {
+        if let Err(failure) = self.run(test_plan) {
+            panic!("{failure}");
+        }
+    }
+
+    /// Runs the dialogue from the start node until it ends and records everything that happens in a [`Transcript`].
+    /// Whenever options are presented, the next of the given `choices` is selected. Like in [`TestPlan::then_select`], they are 1-based.
+    ///
+    /// Variables changed by the script are recorded through [`DialogueEvent::VariableChanged`].
+    /// Changes made by a command handler are recorded right after the command.
+    pub fn record_transcript(&mut self, choices: &[usize]) -> Result<Transcript, TestFailure> {
+        let mut transcript = Transcript::new();
+        let failure = |transcript: &Transcript, kind| TestFailure {
+            kind,
+            transcript: transcript.entries().to_vec(),
+        };
+        let mut choices = choices.iter().copied();
+        self.dialogue
+            .set_node(self.start_node.clone())
+            .map_err(|e| failure(&transcript, dialogue_error(e)))?;
+        loop {
+            let events = self
+                .dialogue
+                .continue_()
+                .map_err(|e| failure(&transcript, dialogue_error(e)))?;
+
+            for event in events {
+                transcript.record_event(&event);
+                match event {
+                    DialogueEvent::Options(options) => {
+                        let Some(selection) = choices.next() else {
+                            return Err(failure(
+                                &transcript,
+                                TestFailureKind::OutOfChoices {
+                                    option_count: options.len(),
+                                },
+                            ));
+                        };
+                        if selection == 0 || selection > options.len() {
+                            return Err(failure(
+                                &transcript,
+                                TestFailureKind::InvalidSelection {
+                                    selection,
+                                    option_count: options.len(),
+                                },
+                            ));
+                        }
+                        let option = &options[selection - 1];
+                        transcript.record_selection(option);
+                        self.dialogue
+                            .set_selected_option(option.id)
+                            .map_err(|e| failure(&transcript, dialogue_error(e)))?;
+                    }
+                    DialogueEvent::Command(command) => {
+                        let variables = self.dialogue.variable_storage().variables();
+                        self.run_command_handler(&command)
+                            .map_err(|kind| failure(&transcript, kind))?;
+                        let current_variables = self.dialogue.variable_storage().variables();
+                        transcript.record_variable_changes(&variables, &current_variables);
+                    }
+                    DialogueEvent::DialogueComplete => return Ok(transcript),
+                    DialogueEvent::Line(_)
+                    | DialogueEvent::NodeStart(_)
+                    | DialogueEvent::NodeComplete(_)
+                    | DialogueEvent::LineHints(_)
+                    | DialogueEvent::VariableChanged { .. } => {}
+                }
+            }
+        }
+    }
+
+    /// Records a transcript with [`TestRunner::record_transcript`] and compares it against the snapshot file at the given path.
+    /// Run the test with the [`BLESS_ENV_VAR`] environment variable set to create or update the snapshot.
+    ///
+    /// ## Panics
+    ///
+    /// Panics with a readable diff if the transcript doesn't match the snapshot or the dialogue fails.
+    pub fn assert_snapshot(&mut self, choices: &[usize], path: impl Into<PathBuf>) {
+        let transcript = self
+            .record_transcript(choices)
+            .unwrap_or_else(|failure| panic!("{failure}"));
+        Snapshot::new(path).assert_matches(&transcript.to_string());
+    }
+
+    fn run_command_handler(&mut self, command: &Command) -> Result<(), TestFailureKind> {
+        let Some(handler) = self.command_handlers.get_mut(&command.name) else {
+            return Ok(());
+        };
+        handler(command, self.dialogue.variable_storage_mut()).map_err(|message| {
+            TestFailureKind::CommandFailed {
+                command: command.raw.clone(),
+                message,
+            }
+        })
+    }
+}
+
+fn dialogue_error(error: impl ToString) -> TestFailureKind {
+    TestFailureKind::DialogueError {
+        message: error.to_string(),
+    }
+}
+
+/// The state of a single [`TestRunner::run`].
+struct Run {
+    test_plan: TestPlan,
+    transcript: Vec<String>,
+}
+
+impl Run {
+    /// Moves the test plan to the next expected step and checks the variable values expected before it.
+    fn advance(&mut self, dialogue: &Dialogue) -> Result<(), TestFailure> {
+        self.test_plan.next();
+        for (name, expected) in self.test_plan.next_expected_variables.clone() {
+            let actual = dialogue.variable_storage().get(&name).ok();
+            let expected_step = variable_step(&name, Some(&expected));
+            if actual.as_ref() != Some(&expected) {
+                return Err(self.mismatch_with_expected(
+                    vec![expected_step],
+                    vec![variable_step(&name, actual.as_ref())],
+                ));
+            }
+            self.transcript.push(expected_step);
+        }
+        Ok(())
+    }
+
+    /// Checks a line or command against the test plan and returns it as a step in the `.testplan` format.
+    fn expect(&self, step_type: ExpectedStepType, text: String) -> Result<String, TestFailure> {
+        let actual = format_step(step_type, Some(&StepValue::String(text.clone())), true);
+        let matches = self.test_plan.next_expected_step == step_type
+            && match &self.test_plan.next_step_value {
+                Some(StepValue::String(expected)) => expected == &text,
+                Some(_) => false,
+                // `*` matches anything
+                None => true,
+            };
+        if !matches {
+            return Err(self.mismatch(vec![actual]));
+        }
+        Ok(actual)
+    }
+
+    fn expect_options(&mut self, options: &[DialogueOption]) -> Result<OptionId, TestFailure> {
+        let actual_options: Vec<_> = options
+            .iter()
+            .map(|option| ProcessedOption {
+                line: option.line.text.clone(),
+                enabled: option.is_available,
+            })
+            .collect();
+        let mut actual: Vec<_> = actual_options.iter().map(option_step).collect();
+        if self.test_plan.next_expected_step != ExpectedStepType::Select
+            || self.test_plan.next_expected_options != actual_options
+        {
+            return Err(self.mismatch(actual));
+        }
+        let selection = match self.test_plan.next_step_value {
+            Some(StepValue::Number(selection)) => selection,
+            _ => 1,
+        };
+        if selection == 0 || selection > options.len() {
+            return Err(self.failure(TestFailureKind::InvalidSelection {
+                selection,
+                option_count: options.len(),
+            }));
+        }
+        actual.push(format_step(
+            ExpectedStepType::Select,
+            Some(&StepValue::Number(selection)),
+            true,
+        ));
+        self.transcript.extend(actual);
+        // 1-indexed for test plan, 0-indexed in the code
+        Ok(options[selection - 1].id)
+    }
+
+    fn mismatch(&self, actual: Vec<String>) -> TestFailure {
+        self.mismatch_with_expected(self.expected_steps(), actual)
+    }
+
+    fn mismatch_with_expected(&self, expected: Vec<String>, actual: Vec<String>) -> TestFailure {
+        self.failure(TestFailureKind::Mismatch { expected, actual })
+    }
+
+    fn dialogue_error(&self, error: impl ToString) -> TestFailure {
+        self.failure(dialogue_error(error))
+    }
+
+    fn failure(&self, kind: TestFailureKind) -> TestFailure {
+        TestFailure {
+            kind,
+            transcript: self.transcript.clone(),
+        }
+    }
+
+    /// The steps the test plan expects right now, in the `.testplan` format.
+    fn expected_steps(&self) -> Vec<String> {
+        let test_plan = &self.test_plan;
+        let mut steps: Vec<_> = test_plan
+            .next_expected_options
+            .iter()
+            .map(option_step)
+            .collect();
+        steps.push(format_step(
+            test_plan.next_expected_step,
+            test_plan.next_step_value.as_ref(),
+            true,
+        ));
+        steps
+    }
+}
+
+fn option_step(option: &ProcessedOption) -> String {
+    format_step(
+        ExpectedStepType::Option,
+        Some(&StepValue::String(option.line.clone())),
+        option.enabled,
+    )
+}
+
+fn variable_step(name: &str, value: Option<&YarnValue>) -> String {
+    match value {
+        Some(value) => format_step(
+            ExpectedStepType::Variable,
+            Some(&StepValue::Variable {
+                name: name.to_owned(),
+                value: value.clone(),
+            }),
+            true,
+        ),
+        None => format!("{}: {name} is not set", ExpectedStepType::Variable),
+    }
+}
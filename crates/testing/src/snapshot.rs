@@ -0,0 +1,186 @@
+use crate::diff::diff_lines;
+use std::env;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The environment variable that switches [`SnapshotMode::from_env`] to [`SnapshotMode::Update`] when set to anything but `0` or an empty string,
+/// e.g. `YARNSPINNER_BLESS=1 cargo test`.
+pub const BLESS_ENV_VAR: &str = "YARNSPINNER_BLESS";
+
+/// Whether a [`Snapshot`] is compared against or overwritten.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotMode {
+    /// Fail if the snapshot file is missing or differs.
+    #[default]
+    Compare,
+    /// Write the snapshot file if it is missing or differs. Use this to accept intended changes, also known as "blessing" them.
+    Update,
+}
+
+impl SnapshotMode {
+    /// Reads the mode from the [`BLESS_ENV_VAR`] environment variable.
+    pub fn from_env() -> Self {
+        match env::var(BLESS_ENV_VAR) {
+            Ok(value) if !value.is_empty() && value != "0" => Self::Update,
+            _ => Self::Compare,
+        }
+    }
+}
+
+/// A checked-in text file that a rendered [`Transcript`](crate::prelude::Transcript) or any other text is compared against, also known as a golden file.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use yarnspinner::prelude::*;
+/// # use yarnspinner_testing::prelude::*;
+/// # let compilation: Compilation = unimplemented!();
+/// let transcript = TestRunner::new(&compilation).record_transcript(&[2, 1]).unwrap();
+///
+/// // Run with `YARNSPINNER_BLESS=1` to create or update the file
+/// Snapshot::new("tests/snapshots/shop.transcript").assert_matches(&transcript.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    path: PathBuf,
+    mode: SnapshotMode,
+}
+
+impl Snapshot {
+    /// Creates a snapshot stored at the given path, with its mode read from the [`BLESS_ENV_VAR`] environment variable.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: SnapshotMode::from_env(),
+        }
+    }
+
+    /// Overrides the mode read from the environment.
+    pub fn with_mode(mut self, mode: SnapshotMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// The path of the snapshot file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The mode of the snapshot.
+    pub fn mode(&self) -> SnapshotMode {
+        self.mode
+    }
+
+    /// Compares the text against the snapshot file, or writes it to the file in [`SnapshotMode::Update`].
+    /// Line endings are normalized, so snapshots checked out with `\r\n` line endings still match.
+    pub fn check(&self, actual: &str) -> Result<SnapshotOutcome, SnapshotError> {
+        let actual = normalize_line_endings(actual);
+        let expected = match fs::read_to_string(&self.path) {
+            Ok(expected) => Some(normalize_line_endings(&expected)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => return Err(self.io_error(error)),
+        };
+        if expected.as_deref() == Some(actual.as_str()) {
+            return Ok(SnapshotOutcome::Matched);
+        }
+        match (self.mode, expected) {
+            (SnapshotMode::Update, _) => {
+                if let Some(parent) = self.path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| self.io_error(e))?;
+                }
+                fs::write(&self.path, actual).map_err(|e| self.io_error(e))?;
+                Ok(SnapshotOutcome::Updated)
+            }
+            (SnapshotMode::Compare, None) => Err(SnapshotError::Missing {
+                path: self.path.clone(),
+            }),
+            (SnapshotMode::Compare, Some(expected)) => Err(SnapshotError::Mismatch {
+                path: self.path.clone(),
+                diff: diff_lines(&expected, &actual),
+            }),
+        }
+    }
+
+    /// Like [`Snapshot::check`], but panics with a readable diff if the text doesn't match.
+    pub fn assert_matches(&self, actual: &str) {
+        if let Err(error) = self.check(actual) {
+            panic!("{error}");
+        }
+    }
+
+    fn io_error(&self, error: io::Error) -> SnapshotError {
+        SnapshotError::Io {
+            path: self.path.clone(),
+            error,
+        }
+    }
+}
+
+fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n")
+}
+
+/// The result of a successful [`Snapshot::check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    /// The text was identical to the snapshot.
+    Matched,
+    /// The snapshot file was created or overwritten because of [`SnapshotMode::Update`].
+    Updated,
+}
+
+/// An error returned by [`Snapshot::check`].
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The snapshot file could not be read or written.
+    Io {
+        /// The path of the snapshot file.
+        path: PathBuf,
+        /// The underlying error.
+        error: io::Error,
+    },
+    /// The snapshot file doesn't exist yet.
+    Missing {
+        /// The path of the snapshot file.
+        path: PathBuf,
+    },
+    /// The text differs from the snapshot.
+    Mismatch {
+        /// The path of the snapshot file.
+        path: PathBuf,
+        /// The differing lines, with lines only in the snapshot marked with `-` and new lines with `+`.
+        diff: String,
+    },
+}
+
+impl Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SnapshotError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io { path, error } => {
+                write!(f, "Failed to access snapshot {}: {error}", path.display())
+            }
+            SnapshotError::Missing { path } => write!(
+                f,
+                "Snapshot {} does not exist. Run the test with {BLESS_ENV_VAR}=1 to create it.",
+                path.display()
+            ),
+            SnapshotError::Mismatch { path, diff } => write!(
+                f,
+                "Snapshot {} does not match. Run the test with {BLESS_ENV_VAR}=1 to update it if the change is intended.\n{diff}",
+                path.display()
+            ),
+        }
+    }
+}
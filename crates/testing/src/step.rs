@@ -0,0 +1,314 @@
+//! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Tests/TestPlan.cs>
+
+use crate::prelude::*;
+use reader::*;
+use std::fmt::{self, Debug, Display};
+use std::str::FromStr;
+
+mod reader;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Step {
+    pub expected_step_type: ExpectedStepType,
+    pub value: Option<StepValue>,
+    pub expect_option_enabled: bool,
+}
+
+/// The value a step of a [`TestPlan`] expects to see.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepValue {
+    /// The text of a line, option or command.
+    String(String),
+    /// The 1-based index of an option to select.
+    Number(usize),
+    /// The value a variable should have.
+    Variable {
+        /// The name of the variable, including the leading `$`.
+        name: String,
+        /// The expected value.
+        value: YarnValue,
+    },
+}
+
+impl Step {
+    /// Parses a single line of a `.testplan` file. `line_number` is 1-based and only used for error reporting.
+    pub(crate) fn read(string: &str, line_number: usize) -> Result<Self, TestPlanError> {
+        let invalid_step = || TestPlanError::InvalidStep {
+            line: line_number,
+            content: string.to_owned(),
+        };
+        let mut reader = Reader::new(string);
+        let step_type = reader.read_next_raw();
+        let expected_step_type =
+            step_type
+                .parse::<ExpectedStepType>()
+                .map_err(|_| TestPlanError::UnknownStepType {
+                    line: line_number,
+                    step_type,
+                })?;
+        let delimiter = reader.read_next_raw();
+        if delimiter != ":" {
+            return Err(invalid_step());
+        }
+
+        match expected_step_type {
+            ExpectedStepType::Line | ExpectedStepType::Option | ExpectedStepType::Command => {
+                let buf = reader.read_to_end();
+                let value = buf.trim().to_owned();
+
+                // Options whose text ends with " [disabled]"
+                // are expected to be present, but have their
+                // 'allowed' flag set to false
+                if value == "*" {
+                    if expected_step_type == ExpectedStepType::Option {
+                        // Options are compared as a whole list, so they cannot be skipped
+                        return Err(invalid_step());
+                    }
+                    Ok(Self::with_expected_step_type(expected_step_type))
+                } else if expected_step_type == ExpectedStepType::Option
+                    && value.ends_with(" [disabled]")
+                {
+                    Ok(Self::from_disabled_option(value.replace(" [disabled]", "")))
+                } else {
+                    Ok(Self::with_value_and_type(value, expected_step_type))
+                }
+            }
+            ExpectedStepType::Select => {
+                let value = reader
+                    .read_next_raw()
+                    .parse::<usize>()
+                    .map_err(|_| invalid_step())?;
+                Ok(Self::with_value_and_type(value, expected_step_type))
+            }
+            ExpectedStepType::Variable => {
+                let buf = reader.read_to_end();
+                let (name, value) = buf.split_once('=').ok_or_else(invalid_step)?;
+                let name = name.trim();
+                if !name.starts_with('$') {
+                    return Err(invalid_step());
+                }
+                Ok(Self::from_variable(name, parse_yarn_value(value.trim())))
+            }
+            ExpectedStepType::Stop => Ok(Self::with_expected_step_type(expected_step_type)),
+        }
+    }
+
+    pub(crate) fn from_line(line: impl Into<String>) -> Self {
+        Self::with_value_and_type(line.into(), ExpectedStepType::Line)
+    }
+
+    pub(crate) fn from_option(line: impl Into<String>) -> Self {
+        Self::with_value_and_type(line.into(), ExpectedStepType::Option)
+    }
+
+    pub(crate) fn from_disabled_option(line: impl Into<String>) -> Self {
+        Self {
+            expect_option_enabled: false,
+            ..Self::from_option(line)
+        }
+    }
+
+    pub(crate) fn from_command(line: impl Into<String>) -> Self {
+        Self::with_value_and_type(line.into(), ExpectedStepType::Command)
+    }
+
+    pub(crate) fn from_select(selection: impl Into<usize>) -> Self {
+        Self::with_value_and_type(selection.into(), ExpectedStepType::Select)
+    }
+
+    pub(crate) fn from_variable(name: impl Into<String>, value: impl Into<YarnValue>) -> Self {
+        Self::with_value_and_type(
+            StepValue::Variable {
+                name: name.into(),
+                value: value.into(),
+            },
+            ExpectedStepType::Variable,
+        )
+    }
+
+    pub(crate) fn from_stop() -> Self {
+        Self::with_expected_step_type(ExpectedStepType::Stop)
+    }
+
+    fn with_value_and_type(
+        value: impl Into<StepValue>,
+        expected_step_type: ExpectedStepType,
+    ) -> Self {
+        Self {
+            expected_step_type,
+            value: Some(value.into()),
+            expect_option_enabled: true,
+        }
+    }
+    fn with_expected_step_type(expected_step_type: ExpectedStepType) -> Self {
+        Self {
+            expected_step_type,
+            value: None,
+            expect_option_enabled: true,
+        }
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let step = format_step(
+            self.expected_step_type,
+            self.value.as_ref(),
+            self.expect_option_enabled,
+        );
+        f.write_str(&step)
+    }
+}
+
+/// Formats a step the way it is written in a `.testplan` file.
+pub(crate) fn format_step(
+    expected_step_type: ExpectedStepType,
+    value: Option<&StepValue>,
+    enabled: bool,
+) -> String {
+    let value = match value {
+        Some(value) => format!(" {value}"),
+        None if expected_step_type == ExpectedStepType::Stop => String::new(),
+        None => " *".to_owned(),
+    };
+    let disabled = if enabled { "" } else { " [disabled]" };
+    format!("{expected_step_type}:{value}{disabled}")
+}
+
+impl From<String> for StepValue {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+impl From<&str> for StepValue {
+    fn from(value: &str) -> Self {
+        Self::String(to_rust_serialization(value))
+    }
+}
+
+impl From<usize> for StepValue {
+    fn from(value: usize) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl TryInto<String> for StepValue {
+    type Error = ();
+
+    fn try_into(self) -> Result<String, Self::Error> {
+        match self {
+            Self::String(value) => Ok(value),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<usize> for StepValue {
+    type Error = ();
+
+    fn try_into(self) -> Result<usize, Self::Error> {
+        match self {
+            Self::Number(value) => Ok(value),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for StepValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(value) => write!(f, "{value}"),
+            Self::Number(value) => write!(f, "{value}"),
+            Self::Variable { name, value } => write!(f, "{name} = {}", format_yarn_value(value)),
+        }
+    }
+}
+
+/// The kind of step of a [`TestPlan`]. The names are the ones used in `.testplan` files.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ExpectedStepType {
+    /// expecting to see this specific line (if '*' is given,
+    /// means 'see a line, don't care about text')
+    #[default]
+    Line,
+
+    /// expecting to see this specific option
+    Option,
+
+    /// expecting options to have been presented; value = the
+    /// index to select
+    Select,
+
+    /// expecting to see this specific command (if '*' is given,
+    /// means 'see a command, don't care about text')
+    Command,
+
+    /// expecting a variable to have a specific value at this point.
+    /// Checked right before the next line, option, command or stop.
+    Variable,
+
+    /// expecting to stop the test here (this is optional - a
+    /// 'stop' at the end of a test plan is assumed)
+    Stop,
+}
+
+impl FromStr for ExpectedStepType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "line" => Ok(Self::Line),
+            "option" => Ok(Self::Option),
+            "select" => Ok(Self::Select),
+            "command" => Ok(Self::Command),
+            "variable" => Ok(Self::Variable),
+            "stop" => Ok(Self::Stop),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for ExpectedStepType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Line => "line",
+            Self::Option => "option",
+            Self::Select => "select",
+            Self::Command => "command",
+            Self::Variable => "variable",
+            Self::Stop => "stop",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Formats a value the way it is written in a `.testplan` file, i.e. with strings in quotes.
+pub(crate) fn format_yarn_value(value: &YarnValue) -> String {
+    match value {
+        YarnValue::String(value) => format!("{value:?}"),
+        _ => value.to_string(),
+    }
+}
+
+fn parse_yarn_value(value: &str) -> YarnValue {
+    if let Some(string) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        return string.replace("\\\"", "\"").into();
+    }
+    if let Ok(boolean) = value.to_lowercase().parse::<bool>() {
+        return boolean.into();
+    }
+    if let Ok(number) = value.parse::<f32>() {
+        return number.into();
+    }
+    value.into()
+}
+
+fn to_rust_serialization(line: &str) -> String {
+    // Need to do this because in Rust, booleans are not capitalized when converted to strings.
+    // But in C# and hence our test plans, they are: https://stackoverflow.com/questions/491334/why-does-boolean-tostring-output-true-and-not-true
+    line.replace("True", "true").replace("False", "false")
+}
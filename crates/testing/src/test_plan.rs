@@ -0,0 +1,235 @@
+//! Adapted from <https://github.com/YarnSpinnerTool/YarnSpinner/blob/da39c7195107d8211f21c263e4084f773b84eaff/YarnSpinner.Tests/TestPlan.cs>
+
+use crate::prelude::*;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A script of what a dialogue is expected to do, step by step.
+///
+/// Build one in code with the `expect_*` and [`TestPlan::then_select`] methods, or load a `.testplan` file with [`TestPlan::read`].
+/// Run it with a [`TestRunner`].
+///
+/// ## The `.testplan` format
+///
+/// Every non-empty line that doesn't start with `#` is one step:
+///
+/// ```text
+/// # The text of a line. `*` matches any line.
+/// line: Mae: Hello!
+/// # The options that will be presented, in order. Disabled ones end in " [disabled]".
+/// option: Go left
+/// option: Go right [disabled]
+/// # The 1-based index of the option to select
+/// select: 1
+/// # The text of a command. `*` matches any command.
+/// command: fade_out 2
+/// # The value of a variable right before the next line, options, command or end of the dialogue
+/// variable: $gold = 5
+/// # The end of the dialogue. Optional at the end of the plan.
+/// stop:
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TestPlan {
+    /// The kind of step expected next. Updated by [`TestPlan::next`].
+    pub next_expected_step: ExpectedStepType,
+    /// The options expected when [`TestPlan::next_expected_step`] is [`ExpectedStepType::Select`].
+    pub next_expected_options: Vec<ProcessedOption>,
+    /// The value belonging to [`TestPlan::next_expected_step`], if any.
+    pub next_step_value: Option<StepValue>,
+    /// The variable values that must hold before [`TestPlan::next_expected_step`] is checked.
+    pub next_expected_variables: Vec<(String, YarnValue)>,
+    steps: Vec<Step>,
+    current_test_plan_step: usize,
+}
+
+/// An option as it is expected to be presented.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessedOption {
+    /// The text of the option.
+    pub line: String,
+    /// Whether the option can be selected.
+    pub enabled: bool,
+}
+
+impl TestPlan {
+    /// Creates an empty test plan. An empty plan expects the dialogue to stop immediately.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Reads a test plan from a `.testplan` file. See [`TestPlan`] for the format.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, TestPlanError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|error| TestPlanError::Io {
+            path: path.to_owned(),
+            error,
+        })?;
+        Self::parse(&source)
+    }
+
+    /// Parses a test plan in the `.testplan` format. See [`TestPlan`] for the format.
+    pub fn parse(source: &str) -> Result<Self, TestPlanError> {
+        let steps = source
+            .lines()
+            .enumerate()
+            // Skip commented lines
+            .filter(|(_, line)| !line.trim_start().starts_with('#'))
+            // Skip empty or blank lines
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| Step::read(line, index + 1))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            steps,
+            ..Default::default()
+        })
+    }
+
+    /// Advances to the next line, command, selection or stop in the plan,
+    /// collecting the options and variable values expected on the way.
+    pub fn next(&mut self) {
+        // step through the test plan until we hit an expectation to
+        // see a line, option, or command. specifically, we're waiting
+        // to see if we got a Line, Select, Command or Assert step
+        // type.
+        if self.next_expected_step == ExpectedStepType::Select {
+            // our previously-notified task was to select an option.
+            // we've now moved past that, so clear the list of expected
+            // options.
+            self.next_expected_options.clear();
+            self.next_step_value = Some(StepValue::Number(0));
+        }
+        self.next_expected_variables.clear();
+
+        for current_step in self.steps.iter().skip(self.current_test_plan_step) {
+            self.current_test_plan_step += 1;
+            match (current_step.expected_step_type, current_step.value.clone()) {
+                (ExpectedStepType::Option, Some(StepValue::String(line))) => {
+                    self.next_expected_options.push(ProcessedOption {
+                        line,
+                        enabled: current_step.expect_option_enabled,
+                    });
+                }
+                (ExpectedStepType::Option, _) => panic!("Expected option line to be a string"),
+                (ExpectedStepType::Variable, Some(StepValue::Variable { name, value })) => {
+                    self.next_expected_variables.push((name, value));
+                }
+                (ExpectedStepType::Variable, _) => {
+                    panic!("Expected variable step to have a name and value")
+                }
+                (expected_step_type, value) => {
+                    self.next_expected_step = expected_step_type;
+                    self.next_step_value = value;
+                    return;
+                }
+            }
+        }
+
+        // We've fallen off the end of the test plan step list. We
+        // expect a stop here.
+        self.next_expected_step = ExpectedStepType::Stop;
+    }
+
+    /// Expects the next content to be a line with the given text.
+    pub fn expect_line(mut self, line: impl Into<String>) -> Self {
+        self.steps.push(Step::from_line(line));
+        self
+    }
+
+    /// Expects an option with the given text. Consecutive options are expected to be presented together.
+    pub fn expect_option(mut self, line: impl Into<String>) -> Self {
+        self.steps.push(Step::from_option(line));
+        self
+    }
+
+    /// Expects an option with the given text that cannot be selected, e.g. because its condition is false.
+    pub fn expect_disabled_option(mut self, line: impl Into<String>) -> Self {
+        self.steps.push(Step::from_disabled_option(line));
+        self
+    }
+
+    /// Expects the next content to be a command with the given text, including its parameters.
+    pub fn expect_command(mut self, line: impl Into<String>) -> Self {
+        self.steps.push(Step::from_command(line));
+        self
+    }
+
+    /// Expects the variable to have the given value right before the next line, options, command or end of the dialogue.
+    pub fn expect_variable(mut self, name: impl Into<String>, value: impl Into<YarnValue>) -> Self {
+        self.steps.push(Step::from_variable(name, value));
+        self
+    }
+
+    /// Selects the option with the given 1-based index once the expected options have been presented.
+    pub fn then_select(mut self, selection: usize) -> Self {
+        self.steps.push(Step::from_select(selection));
+        self
+    }
+
+    /// Expects the dialogue to end.
+    pub fn expect_stop(mut self) -> Self {
+        self.steps.push(Step::from_stop());
+        self
+    }
+
+    /// Renders the steps of the plan in the `.testplan` format.
+    pub fn to_testplan_string(&self) -> String {
+        self.steps.iter().map(|step| format!("{step}\n")).collect()
+    }
+}
+
+/// An error that occurred while reading a test plan with [`TestPlan::read`] or [`TestPlan::parse`]. Line numbers are 1-based.
+#[derive(Debug)]
+pub enum TestPlanError {
+    /// The test plan file could not be read.
+    Io {
+        /// The path of the test plan file.
+        path: PathBuf,
+        /// The underlying error.
+        error: io::Error,
+    },
+    /// A line that doesn't start with one of `line`, `option`, `select`, `command`, `variable` or `stop`.
+    UnknownStepType {
+        /// The number of the line.
+        line: usize,
+        /// The first word of the line.
+        step_type: String,
+    },
+    /// A step whose value is missing or malformed, e.g. `select: first` or `variable: gold`.
+    InvalidStep {
+        /// The number of the line.
+        line: usize,
+        /// The text of the line.
+        content: String,
+    },
+}
+
+impl Error for TestPlanError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TestPlanError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for TestPlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestPlanError::Io { path, error } => {
+                write!(f, "Failed to read test plan {}: {error}", path.display())
+            }
+            TestPlanError::UnknownStepType { line, step_type } => {
+                write!(
+                    f,
+                    "Unknown step type on line {line} of test plan: {step_type}"
+                )
+            }
+            TestPlanError::InvalidStep { line, content } => {
+                write!(f, "Invalid step on line {line} of test plan: {content}")
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::prelude::*;
+use crate::step::format_yarn_value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{self, Display};
+use yarnspinner::runtime::{DialogueEvent, DialogueOption};
+
+/// A text rendering of everything that happened while running a dialogue, meant to be compared against a checked-in [`Snapshot`].
+/// Record one with [`TestRunner::record_transcript`] or build it yourself from the [`DialogueEvent`]s of a [`Dialogue`](yarnspinner::runtime::Dialogue).
+///
+/// Every event is rendered as one line of text:
+///
+/// ```text
+/// [node start] Start
+/// Mae: Hello!
+/// <<give_gold 5>>
+/// [variable] $gold = 5 (was 0)
+/// -> Buy a sword [disabled]
+/// -> Leave
+/// [selected] Leave
+/// Mae: Goodbye!
+/// [node complete] Start
+/// [dialogue complete]
+/// ```
+///
+/// Lines, options and commands use the [`Display`] implementations of [`Line`](yarnspinner::runtime::Line),
+/// [`DialogueOption`] and [`Command`](yarnspinner::runtime::Command). [`DialogueEvent::LineHints`] are not recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Transcript {
+    entries: Vec<String>,
+}
+
+impl Transcript {
+    /// Creates an empty transcript.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a transcript that was rendered with its [`Display`] implementation, e.g. the contents of a snapshot file.
+    pub fn parse(source: &str) -> Self {
+        Self {
+            entries: source.lines().map(ToOwned::to_owned).collect(),
+        }
+    }
+
+    /// The rendered events, one per line.
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    /// Records the given events in order.
+    pub fn record_events(&mut self, events: &[DialogueEvent]) {
+        for event in events {
+            self.record_event(event);
+        }
+    }
+
+    /// Records a single event.
+    pub fn record_event(&mut self, event: &DialogueEvent) {
+        match event {
+            DialogueEvent::Line(line) => self.entries.push(line.to_string()),
+            DialogueEvent::Options(options) => self
+                .entries
+                .extend(options.iter().map(|option| format!("-> {option}"))),
+            DialogueEvent::Command(command) => self.entries.push(command.to_string()),
+            DialogueEvent::NodeStart(node_name) => {
+                self.entries.push(format!("[node start] {node_name}"))
+            }
+            DialogueEvent::NodeComplete(node_name) => {
+                self.entries.push(format!("[node complete] {node_name}"))
+            }
+            DialogueEvent::VariableChanged { name, old, new } => self
+                .entries
+                .push(format_variable_change(name, old.as_ref(), Some(new))),
+            DialogueEvent::DialogueComplete => self.entries.push("[dialogue complete]".to_owned()),
+            DialogueEvent::LineHints(_) => {}
+        }
+    }
+
+    /// Records that the given option was selected.
+    pub fn record_selection(&mut self, option: &DialogueOption) {
+        self.entries.push(format!("[selected] {}", option.line));
+    }
+
+    /// Records every variable that differs between the two sets of values, as returned by [`VariableStorage::variables`](yarnspinner::runtime::VariableStorage::variables).
+    /// The variables are recorded in alphabetical order.
+    pub fn record_variable_changes(
+        &mut self,
+        before: &HashMap<String, YarnValue>,
+        after: &HashMap<String, YarnValue>,
+    ) {
+        let names: BTreeSet<_> = before.keys().chain(after.keys()).collect();
+        for name in names {
+            let (old, new) = (before.get(name), after.get(name));
+            if old != new {
+                self.entries.push(format_variable_change(name, old, new));
+            }
+        }
+    }
+}
+
+fn format_variable_change(name: &str, old: Option<&YarnValue>, new: Option<&YarnValue>) -> String {
+    match (old, new) {
+        (Some(old), Some(new)) => format!(
+            "[variable] {name} = {} (was {})",
+            format_yarn_value(new),
+            format_yarn_value(old)
+        ),
+        (None, Some(new)) => format!("[variable] {name} = {}", format_yarn_value(new)),
+        (Some(old), None) => {
+            format!("[variable] {name} removed (was {})", format_yarn_value(old))
+        }
+        (None, None) => format!("[variable] {name} removed"),
+    }
+}
+
+impl Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
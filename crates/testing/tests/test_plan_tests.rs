@@ -0,0 +1,254 @@
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+use yarnspinner_testing::prelude::*;
+
+const SHOP: &str = "title: Start
+---
+<<declare $gold = 0>>
+Mae: Hello!
+<<give_gold 5>>
+-> Buy a sword <<if is_rich($gold)>>
+    Mae: Here you go.
+-> Leave
+    Mae: Goodbye!
+===";
+
+fn is_rich(gold: f32) -> bool {
+    gold >= 10.0
+}
+
+fn compile(source: &str) -> Compilation {
+    let mut library = Library::new();
+    library.add_function("is_rich", is_rich);
+    let file = File {
+        file_name: "test.yarn".to_string(),
+        source: source.to_string(),
+    };
+    Compiler::new()
+        .extend_library(library)
+        .add_file(file)
+        .compile()
+        .unwrap()
+}
+
+fn shop_runner() -> TestRunner {
+    TestRunner::new(&compile(SHOP))
+        .with_function("is_rich", is_rich)
+        .with_command("give_gold", |command, variable_storage| {
+            let amount = f32::try_from(&command.parameters[0]).map_err(|e| e.to_string())?;
+            let gold = f32::try_from(variable_storage.get("$gold").unwrap()).unwrap();
+            variable_storage
+                .set("$gold".to_owned(), (gold + amount).into())
+                .map_err(|e| e.to_string())
+        })
+}
+
+#[test]
+fn passes_matching_plan() {
+    let test_plan = TestPlan::new()
+        .expect_line("Mae: Hello!")
+        .expect_command("give_gold 5")
+        .expect_variable("$gold", 5)
+        .expect_disabled_option("Buy a sword")
+        .expect_option("Leave")
+        .then_select(2)
+        .expect_line("Mae: Goodbye!")
+        .expect_stop();
+
+    shop_runner().assert_plan(&test_plan);
+}
+
+#[test]
+fn reports_variable_mismatch() {
+    let test_plan = TestPlan::new()
+        .expect_line("Mae: Hello!")
+        .expect_command("give_gold 5")
+        .expect_variable("$gold", 5);
+
+    let failure = shop_runner()
+        .with_variable("$gold", 5)
+        .run(&test_plan)
+        .unwrap_err();
+
+    assert_eq!(
+        "The dialogue did not follow the test plan at step 3:
+  line: Mae: Hello!
+  command: give_gold 5
+- variable: $gold = 5
++ variable: $gold = 10
+",
+        failure.to_string()
+    );
+}
+
+#[test]
+fn reports_mismatch_as_diff() {
+    let test_plan = TestPlan::parse(
+        "line: Mae: Hello!
+         command: *
+         option: Buy a sword
+         option: Leave
+         select: 1",
+    )
+    .unwrap();
+
+    let failure = shop_runner().run(&test_plan).unwrap_err();
+
+    assert_eq!(
+        TestFailureKind::Mismatch {
+            expected: vec![
+                "option: Buy a sword".to_owned(),
+                "option: Leave".to_owned(),
+                "select: 1".to_owned(),
+            ],
+            actual: vec![
+                "option: Buy a sword [disabled]".to_owned(),
+                "option: Leave".to_owned(),
+            ],
+        },
+        failure.kind
+    );
+    assert_eq!(
+        "The dialogue did not follow the test plan at step 3:
+  line: Mae: Hello!
+  command: give_gold 5
+- option: Buy a sword
+- option: Leave
+- select: 1
++ option: Buy a sword [disabled]
++ option: Leave
+",
+        failure.to_string()
+    );
+}
+
+#[test]
+fn reports_unexpected_stop() {
+    let test_plan = TestPlan::new()
+        .expect_line("Mae: Hello!")
+        .expect_command("give_gold 5")
+        .expect_disabled_option("Buy a sword")
+        .expect_option("Leave")
+        .then_select(2)
+        .expect_line("Mae: Goodbye!")
+        .expect_line("Mae: Come again!");
+
+    let failure = shop_runner().run(&test_plan).unwrap_err();
+
+    assert_eq!(
+        TestFailureKind::Mismatch {
+            expected: vec!["line: Mae: Come again!".to_owned()],
+            actual: vec!["stop:".to_owned()],
+        },
+        failure.kind
+    );
+    assert_eq!(
+        vec![
+            "line: Mae: Hello!",
+            "command: give_gold 5",
+            "option: Buy a sword [disabled]",
+            "option: Leave",
+            "select: 2",
+            "line: Mae: Goodbye!",
+        ],
+        failure.transcript
+    );
+}
+
+#[test]
+fn reports_failing_commands() {
+    let test_plan = TestPlan::new()
+        .expect_line("Mae: Hello!")
+        .expect_command("give_gold 5");
+
+    let failure = TestRunner::new(&compile(SHOP))
+        .with_function("is_rich", is_rich)
+        .with_command("give_gold", |_, _| Err("The shop is closed".to_owned()))
+        .run(&test_plan)
+        .unwrap_err();
+
+    assert_eq!(
+        TestFailureKind::CommandFailed {
+            command: "give_gold 5".to_owned(),
+            message: "The shop is closed".to_owned(),
+        },
+        failure.kind
+    );
+    assert_eq!(vec!["line: Mae: Hello!"], failure.transcript);
+}
+
+#[test]
+fn reports_invalid_selection() {
+    let test_plan = TestPlan::new()
+        .expect_line("Mae: Hello!")
+        .expect_command("give_gold 5")
+        .expect_disabled_option("Buy a sword")
+        .expect_option("Leave")
+        .then_select(3);
+
+    let failure = shop_runner().run(&test_plan).unwrap_err();
+
+    assert_eq!(
+        TestFailureKind::InvalidSelection {
+            selection: 3,
+            option_count: 2,
+        },
+        failure.kind
+    );
+}
+
+#[test]
+fn reads_testplan_format() {
+    let source = "# Comments and blank lines are ignored
+
+line: Mae: Hello!
+command: *
+variable: $name = \"Mae\"
+variable: $met = True
+option: Buy a sword [disabled]
+option: Leave
+select: 2
+stop:
+";
+
+    let test_plan = TestPlan::parse(source).unwrap();
+
+    assert_eq!(
+        "line: Mae: Hello!
+command: *
+variable: $name = \"Mae\"
+variable: $met = true
+option: Buy a sword [disabled]
+option: Leave
+select: 2
+stop:
+",
+        test_plan.to_testplan_string()
+    );
+    assert_eq!(
+        test_plan,
+        TestPlan::parse(&test_plan.to_testplan_string()).unwrap()
+    );
+}
+
+#[test]
+fn rejects_malformed_testplan() {
+    let error = |source: &str| TestPlan::parse(source).unwrap_err().to_string();
+
+    assert_eq!(
+        "Unknown step type on line 2 of test plan: wait",
+        error("line: Hello\nwait: 5")
+    );
+    assert_eq!(
+        "Invalid step on line 1 of test plan: select: first",
+        error("select: first")
+    );
+    assert_eq!(
+        "Invalid step on line 1 of test plan: variable: gold = 5",
+        error("variable: gold = 5")
+    );
+    assert_eq!(
+        "Invalid step on line 1 of test plan: option: *",
+        error("option: *")
+    );
+}
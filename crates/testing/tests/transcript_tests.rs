@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::PathBuf;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+use yarnspinner_testing::prelude::*;
+
+const SHOP: &str = "title: Start
+---
+<<declare $gold = 0>>
+Mae: Hello!
+<<give_gold 5>>
+-> Buy a sword <<if is_rich($gold)>>
+    Mae: Here you go.
+-> Leave
+    Mae: Goodbye!
+===";
+
+fn is_rich(gold: f32) -> bool {
+    gold >= 10.0
+}
+
+fn shop_runner() -> TestRunner {
+    let mut library = Library::new();
+    library.add_function("is_rich", is_rich);
+    let file = File {
+        file_name: "shop.yarn".to_string(),
+        source: SHOP.to_string(),
+    };
+    let compilation = Compiler::new()
+        .extend_library(library.clone())
+        .add_file(file)
+        .compile()
+        .unwrap();
+    TestRunner::new(&compilation)
+        .with_library(library)
+        .with_command("give_gold", |command, variable_storage| {
+            let amount = f32::try_from(&command.parameters[0]).map_err(|e| e.to_string())?;
+            let gold = f32::try_from(variable_storage.get("$gold").unwrap()).unwrap();
+            variable_storage
+                .set("$gold".to_owned(), (gold + amount).into())
+                .map_err(|e| e.to_string())
+        })
+}
+
+fn snapshot_path(file_name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(file_name)
+}
+
+#[test]
+fn records_transcript() {
+    let transcript = shop_runner().record_transcript(&[2]).unwrap();
+
+    assert_eq!(
+        "[node start] Start
+Mae: Hello!
+<<give_gold 5>>
+[variable] $gold = 5 (was 0)
+-> Buy a sword [disabled]
+-> Leave
+[selected] Leave
+Mae: Goodbye!
+[node complete] Start
+[dialogue complete]
+",
+        transcript.to_string()
+    );
+    assert_eq!(transcript, Transcript::parse(&transcript.to_string()));
+}
+
+#[test]
+fn records_variables_set_by_script() {
+    let file = File {
+        file_name: "set.yarn".to_string(),
+        source: "title: Start
+---
+<<declare $gold = 0>>
+<<set $gold to 10>>
+<<set $gold to 10>>
+Mae: You're rich!
+==="
+        .to_string(),
+    };
+    let compilation = Compiler::new().add_file(file).compile().unwrap();
+
+    let transcript = TestRunner::new(&compilation)
+        .record_transcript(&[])
+        .unwrap();
+
+    assert_eq!(
+        "[node start] Start
+[variable] $gold = 10 (was 0)
+Mae: You're rich!
+[node complete] Start
+[dialogue complete]
+",
+        transcript.to_string()
+    );
+}
+
+#[test]
+fn matches_checked_in_snapshot() {
+    shop_runner().assert_snapshot(&[2], snapshot_path("shop.transcript"));
+}
+
+#[test]
+fn reports_missing_choices() {
+    let failure = shop_runner().record_transcript(&[]).unwrap_err();
+
+    assert_eq!(
+        TestFailureKind::OutOfChoices { option_count: 2 },
+        failure.kind
+    );
+    assert_eq!(
+        vec![
+            "[node start] Start",
+            "Mae: Hello!",
+            "<<give_gold 5>>",
+            "[variable] $gold = 5 (was 0)",
+            "-> Buy a sword [disabled]",
+            "-> Leave",
+        ],
+        failure.transcript
+    );
+}
+
+#[test]
+fn updates_and_compares_snapshots() {
+    let directory = std::env::temp_dir().join("yarnspinner_testing_snapshots");
+    let _ = fs::remove_dir_all(&directory);
+    let snapshot = Snapshot::new(directory.join("shop.transcript"));
+    let transcript = shop_runner().record_transcript(&[2]).unwrap().to_string();
+
+    let missing = snapshot
+        .clone()
+        .with_mode(SnapshotMode::Compare)
+        .check(&transcript);
+    let created = snapshot
+        .clone()
+        .with_mode(SnapshotMode::Update)
+        .check(&transcript);
+    let windows_line_endings = snapshot
+        .clone()
+        .with_mode(SnapshotMode::Compare)
+        .check(&transcript.replace('\n', "\r\n"));
+    let mismatch = snapshot
+        .clone()
+        .with_mode(SnapshotMode::Compare)
+        .check(&transcript.replace("Leave", "Stay"));
+
+    assert!(matches!(missing, Err(SnapshotError::Missing { .. })));
+    assert_eq!(SnapshotOutcome::Updated, created.unwrap());
+    assert_eq!(SnapshotOutcome::Matched, windows_line_endings.unwrap());
+    let Err(SnapshotError::Mismatch { diff, .. }) = mismatch else {
+        panic!("Expected a mismatch, got {mismatch:?}");
+    };
+    assert_eq!(
+        "  ...
+  <<give_gold 5>>
+  [variable] $gold = 5 (was 0)
+  -> Buy a sword [disabled]
+- -> Leave
+- [selected] Leave
++ -> Stay
++ [selected] Stay
+  Mae: Goodbye!
+  [node complete] Start
+  [dialogue complete]
+",
+        diff
+    );
+    fs::remove_dir_all(&directory).unwrap();
+}
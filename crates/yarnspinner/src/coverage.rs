@@ -0,0 +1,621 @@
+//! Tracking which parts of a Yarn project were actually seen while playing through it.
+//!
+//! A [`CoverageRecorder`] watches a [`Dialogue`] as it runs and collects the lines, options and instructions it encounters into [`CoverageData`].
+//! The data of multiple sessions, e.g. different testers or different runs of an automated playthrough, can be merged and,
+//! with the `serde` feature, stored as JSON in between.
+//! A [`CoverageReport`] then compares the data against a [`Compilation`] to show what was missed, per node and per file.
+//! It can be exported in the [LCOV](https://github.com/linux-test-project/lcov) tracefile format, which tools such as `genhtml`
+//! or the coverage gutters of common editors turn into an annotated view of the `.yarn` files.
+
+use crate::compiler::{Compilation, StringInfo};
+use crate::core::{LineId, OpCode};
+use crate::localization::sorted_string_infos;
+use crate::runtime::{Dialogue, DialogueEvent, DialogueOption, OptionId};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::{self, Display, Write};
+use std::ops::Range;
+
+/// Records what a [`Dialogue`] runs through.
+///
+/// Either let the recorder drive the dialogue via [`CoverageRecorder::continue_`] and [`CoverageRecorder::set_selected_option`],
+/// or pass it the events and selections yourself via [`CoverageRecorder::record_events`], [`CoverageRecorder::record_selected_option`]
+/// and [`CoverageRecorder::record_executed_instructions`].
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner::prelude::*;
+/// # use yarnspinner::coverage::*;
+/// # use yarnspinner::runtime::*;
+/// # let file = YarnFile {
+/// #     file_name: "example.yarn".to_string(),
+/// #     source: "title: Start\n---\nHello!\n-> Hi\n-> Bye\n    See you!\n===".to_string(),
+/// # };
+/// # let compilation = YarnCompiler::new().add_file(file).compile().unwrap();
+/// # let mut text_provider = StringTableTextProvider::new();
+/// # text_provider.extend_base_language(
+/// #     compilation.string_table.iter().map(|(id, info)| (id.clone(), info.text.clone())).collect(),
+/// # );
+/// # let mut dialogue = Dialogue::new(Box::new(MemoryVariableStorage::new()), Box::new(text_provider));
+/// # dialogue.add_program(compilation.program.clone().unwrap());
+/// # dialogue.set_node("Start").unwrap();
+/// let mut recorder = CoverageRecorder::new();
+/// loop {
+///     let events = recorder.continue_(&mut dialogue).unwrap();
+///     if events.contains(&DialogueEvent::DialogueComplete) {
+///         break;
+///     }
+///     if events.iter().any(|event| matches!(event, DialogueEvent::Options(_))) {
+///         recorder.set_selected_option(&mut dialogue, OptionId(0)).unwrap();
+///     }
+/// }
+///
+/// let report = CoverageReport::new(&compilation, recorder.data());
+/// // "See you!" was never seen
+/// assert_eq!(3, report.lines().hit);
+/// assert_eq!(4, report.lines().total);
+/// // "Bye" was offered, but never selected
+/// assert_eq!(1, report.options().hit);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CoverageRecorder {
+    data: CoverageData,
+    current_options: Vec<DialogueOption>,
+}
+
+impl CoverageRecorder {
+    /// Creates a new [`CoverageRecorder`] that has not recorded anything yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new [`CoverageRecorder`] that continues recording on top of the data of earlier sessions.
+    pub fn from_data(data: CoverageData) -> Self {
+        Self {
+            data,
+            ..Default::default()
+        }
+    }
+
+    /// The data recorded so far.
+    pub fn data(&self) -> &CoverageData {
+        &self.data
+    }
+
+    /// Consumes the recorder and returns the data recorded so far.
+    pub fn into_data(self) -> CoverageData {
+        self.data
+    }
+
+    /// Calls [`Dialogue::continue_`] and records the executed instructions and returned events.
+    ///
+    /// Enables [`Dialogue::instruction_tracking_enabled`] if it isn't already.
+    pub fn continue_(
+        &mut self,
+        dialogue: &mut Dialogue,
+    ) -> crate::runtime::Result<Vec<DialogueEvent>> {
+        dialogue.set_instruction_tracking_enabled(true);
+        let result = dialogue.continue_();
+        self.record_executed_instructions(dialogue);
+        let events = result?;
+        self.record_events(&events);
+        Ok(events)
+    }
+
+    /// Calls [`Dialogue::set_selected_option`] and records the selection.
+    pub fn set_selected_option(
+        &mut self,
+        dialogue: &mut Dialogue,
+        option_id: OptionId,
+    ) -> crate::runtime::Result<()> {
+        dialogue.set_selected_option(option_id)?;
+        self.record_selected_option(option_id);
+        Ok(())
+    }
+
+    /// Records the lines, options and node starts found in a batch of events returned by [`Dialogue::continue_`].
+    pub fn record_events(&mut self, events: &[DialogueEvent]) {
+        for event in events {
+            match event {
+                DialogueEvent::Line(line) => increment(&mut self.data.lines, &line.id),
+                DialogueEvent::Options(options) => {
+                    for option in options {
+                        increment(&mut self.data.offered_options, &option.line.id);
+                    }
+                    self.current_options.clone_from(options);
+                }
+                DialogueEvent::NodeStart(node_name) => {
+                    increment(&mut self.data.node_visits, node_name)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Records that the option with the given ID was selected from the options of the last recorded [`DialogueEvent::Options`].
+    /// Does nothing if there is no such option.
+    pub fn record_selected_option(&mut self, option_id: OptionId) {
+        let selected_option = self
+            .current_options
+            .iter()
+            .find(|option| option.id == option_id);
+        if let Some(option) = selected_option {
+            increment(&mut self.data.selected_options, &option.line.id);
+        }
+        self.current_options.clear();
+    }
+
+    /// Records the instructions returned by [`Dialogue::pop_executed_instructions`].
+    /// Requires [`Dialogue::instruction_tracking_enabled`] to be set before the dialogue runs.
+    pub fn record_executed_instructions(&mut self, dialogue: &mut Dialogue) {
+        for (node_name, instructions) in dialogue.pop_executed_instructions() {
+            self.data.add_instructions(&node_name, instructions);
+        }
+    }
+}
+
+fn increment<K: Ord + Clone>(counts: &mut BTreeMap<K, usize>, key: &K) {
+    *counts.entry(key.clone()).or_default() += 1;
+}
+
+/// What was seen during one or more sessions, as recorded by a [`CoverageRecorder`].
+///
+/// Options are identified by the ID of their line, since [`OptionId`]s are only unique within a single set of options.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct CoverageData {
+    /// How often each line was delivered via [`DialogueEvent::Line`].
+    pub lines: BTreeMap<LineId, usize>,
+    /// How often each option was offered via [`DialogueEvent::Options`], regardless of whether it was available.
+    pub offered_options: BTreeMap<LineId, usize>,
+    /// How often each option was selected.
+    pub selected_options: BTreeMap<LineId, usize>,
+    /// How often each node was started.
+    pub node_visits: BTreeMap<String, usize>,
+    /// The indices of the instructions that were executed, per node.
+    /// The ranges are sorted, non-overlapping and non-adjacent.
+    pub instructions: BTreeMap<String, Vec<Range<usize>>>,
+}
+
+impl CoverageData {
+    /// Creates a new [`CoverageData`] that does not cover anything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the data of another session to this one.
+    pub fn merge(&mut self, other: &CoverageData) {
+        for (counts, other_counts) in [
+            (&mut self.lines, &other.lines),
+            (&mut self.offered_options, &other.offered_options),
+            (&mut self.selected_options, &other.selected_options),
+        ] {
+            for (line_id, count) in other_counts {
+                *counts.entry(line_id.clone()).or_default() += count;
+            }
+        }
+        for (node_name, count) in &other.node_visits {
+            *self.node_visits.entry(node_name.clone()).or_default() += count;
+        }
+        for (node_name, ranges) in &other.instructions {
+            self.add_instructions(node_name, ranges.iter().cloned().flatten());
+        }
+    }
+
+    /// Returns whether the instruction at the given index of the given node was executed.
+    pub fn is_instruction_executed(&self, node_name: &str, instruction_index: usize) -> bool {
+        self.instructions.get(node_name).is_some_and(|ranges| {
+            ranges
+                .iter()
+                .any(|range| range.contains(&instruction_index))
+        })
+    }
+
+    /// Serializes the data as JSON, e.g. to merge it with the data of other sessions later.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserializes data written by [`CoverageData::to_json`].
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    fn add_instructions(&mut self, node_name: &str, instructions: impl IntoIterator<Item = usize>) {
+        let mut indices: BTreeSet<usize> = instructions.into_iter().collect();
+        if let Some(ranges) = self.instructions.get(node_name) {
+            indices.extend(ranges.iter().cloned().flatten());
+        }
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for index in indices {
+            match ranges.last_mut() {
+                Some(range) if range.end == index => range.end += 1,
+                _ => ranges.push(index..index + 1),
+            }
+        }
+        self.instructions.insert(node_name.to_owned(), ranges);
+    }
+}
+
+/// How much of a Yarn project is covered by some [`CoverageData`], grouped by file and node.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CoverageReport {
+    /// The coverage of every file of the project, sorted by file name.
+    pub files: Vec<FileCoverage>,
+}
+
+/// The coverage of a single `.yarn` file. Part of a [`CoverageReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FileCoverage {
+    /// The name of the file.
+    pub file_name: String,
+    /// The coverage of every node in the file, sorted by where they start in the file.
+    pub nodes: Vec<NodeCoverage>,
+    /// How often each one-based line number of the file was hit. Only contains lines that produced instructions or Yarn lines.
+    ///
+    /// A line that produced a Yarn line or option counts as hit as often as that line was delivered or offered.
+    /// Other lines count as hit once if any of their instructions was executed.
+    pub source_lines: BTreeMap<usize, usize>,
+}
+
+/// The coverage of a single node. Part of a [`FileCoverage`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeCoverage {
+    /// The name of the node.
+    pub node_name: String,
+    /// The one-based line number the node's body starts at, if known.
+    pub line_number: Option<usize>,
+    /// How often the node was started.
+    pub visit_count: usize,
+    /// The coverage of every line and option in the node, sorted by line number.
+    pub lines: Vec<LineCoverage>,
+    /// How many of the node's instructions were executed.
+    pub instructions: CoverageCount,
+}
+
+/// The coverage of a single line or option. Part of a [`NodeCoverage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LineCoverage {
+    /// The ID of the line.
+    pub line_id: LineId,
+    /// The one-based line number at which the line is defined.
+    pub line_number: usize,
+    /// How often the line was delivered or, if it is an option, offered.
+    pub hit_count: usize,
+    /// How often the option was selected. `None` if the line is not an option.
+    pub selected_count: Option<usize>,
+}
+
+/// A number of hit items out of a total. Its [`Display`] implementation prints e.g. `3/4 (75.0%)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CoverageCount {
+    /// How many items were hit.
+    pub hit: usize,
+    /// How many items there are.
+    pub total: usize,
+}
+
+impl CoverageCount {
+    /// The share of hit items in percent. Nothing to hit counts as fully covered.
+    pub fn percentage(&self) -> f32 {
+        if self.total == 0 {
+            100.0
+        } else {
+            self.hit as f32 / self.total as f32 * 100.0
+        }
+    }
+
+    fn count(hits: impl IntoIterator<Item = bool>) -> Self {
+        hits.into_iter()
+            .fold(Self::default(), |count, is_hit| Self {
+                hit: count.hit + usize::from(is_hit),
+                total: count.total + 1,
+            })
+    }
+}
+
+impl std::ops::Add for CoverageCount {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            hit: self.hit + rhs.hit,
+            total: self.total + rhs.total,
+        }
+    }
+}
+
+impl std::iter::Sum for CoverageCount {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), std::ops::Add::add)
+    }
+}
+
+impl Display for CoverageCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{} ({:.1}%)", self.hit, self.total, self.percentage())
+    }
+}
+
+impl CoverageReport {
+    /// Compares the data against every line in the [`Compilation::string_table`] and every node and instruction in the [`Compilation::program`].
+    /// Instructions are mapped to source lines via the [`Compilation::debug_info`].
+    pub fn new(compilation: &Compilation, data: &CoverageData) -> Self {
+        let option_line_ids = option_line_ids(compilation);
+        let mut nodes: BTreeMap<&str, NodeCoverage> = BTreeMap::new();
+        let mut file_names: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut source_lines: BTreeMap<&str, BTreeMap<usize, usize>> = BTreeMap::new();
+
+        for (node_name, node) in compilation
+            .program
+            .iter()
+            .flat_map(|program| &program.nodes)
+        {
+            let debug_info = compilation.debug_info.get(node_name);
+            let executed = |index| data.is_instruction_executed(node_name, index);
+            let mut node_coverage = NodeCoverage {
+                node_name: node_name.clone(),
+                visit_count: data.node_visits.get(node_name).copied().unwrap_or_default(),
+                instructions: CoverageCount::count((0..node.instructions.len()).map(executed)),
+                ..Default::default()
+            };
+            if let Some(debug_info) = debug_info {
+                file_names.insert(node_name, &debug_info.file_name);
+                let file_lines = source_lines.entry(&debug_info.file_name).or_default();
+                for (&index, position) in &debug_info.line_positions {
+                    let Some(position) = position else {
+                        continue;
+                    };
+                    let line_number = position.line + 1;
+                    let hit_count = file_lines.entry(line_number).or_default();
+                    *hit_count = (*hit_count).max(usize::from(executed(index)));
+                    node_coverage.line_number = Some(
+                        node_coverage
+                            .line_number
+                            .map_or(line_number, |first| first.min(line_number)),
+                    );
+                }
+            }
+            nodes.insert(node_name, node_coverage);
+        }
+
+        for (line_id, string_info) in sorted_string_infos(compilation) {
+            let line_coverage = line_coverage(
+                line_id,
+                string_info,
+                data,
+                option_line_ids.contains(line_id),
+            );
+            let file_lines = source_lines.entry(&string_info.file_name).or_default();
+            let hit_count = file_lines.entry(string_info.line_number).or_default();
+            *hit_count = (*hit_count).max(line_coverage.hit_count);
+
+            file_names
+                .entry(&string_info.node_name)
+                .or_insert(&string_info.file_name);
+            nodes
+                .entry(&string_info.node_name)
+                .or_insert_with(|| NodeCoverage {
+                    node_name: string_info.node_name.clone(),
+                    visit_count: data
+                        .node_visits
+                        .get(&string_info.node_name)
+                        .copied()
+                        .unwrap_or_default(),
+                    ..Default::default()
+                })
+                .lines
+                .push(line_coverage);
+        }
+
+        let mut files: BTreeMap<&str, FileCoverage> = BTreeMap::new();
+        for (node_name, node_coverage) in nodes {
+            let file_name = file_names.get(node_name).copied().unwrap_or_default();
+            files
+                .entry(file_name)
+                .or_insert_with(|| FileCoverage {
+                    file_name: file_name.to_owned(),
+                    source_lines: source_lines.remove(file_name).unwrap_or_default(),
+                    ..Default::default()
+                })
+                .nodes
+                .push(node_coverage);
+        }
+        let files = files
+            .into_values()
+            .map(|mut file| {
+                file.nodes.sort_by(|lhs, rhs| {
+                    (lhs.line_number, &lhs.node_name).cmp(&(rhs.line_number, &rhs.node_name))
+                });
+                file
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// How many lines and options of the project were delivered or offered at least once.
+    pub fn lines(&self) -> CoverageCount {
+        self.files.iter().map(FileCoverage::lines).sum()
+    }
+
+    /// How many options of the project were selected at least once.
+    pub fn options(&self) -> CoverageCount {
+        self.files.iter().map(FileCoverage::options).sum()
+    }
+
+    /// How many nodes of the project were visited at least once.
+    pub fn nodes(&self) -> CoverageCount {
+        self.files.iter().map(FileCoverage::nodes).sum()
+    }
+
+    /// How many instructions of the project were executed at least once.
+    pub fn instructions(&self) -> CoverageCount {
+        self.files.iter().map(FileCoverage::instructions).sum()
+    }
+
+    /// Writes the report as an [LCOV](https://github.com/linux-test-project/lcov) tracefile, usually saved as `lcov.info`.
+    ///
+    /// Nodes are written as functions, source lines as lines and options as branches, one branch block per node.
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+        // Writing into a `String` never fails
+        self.write_lcov(&mut lcov).unwrap();
+        lcov
+    }
+
+    fn write_lcov(&self, lcov: &mut String) -> fmt::Result {
+        for file in &self.files {
+            writeln!(lcov, "TN:")?;
+            writeln!(lcov, "SF:{}", file.file_name)?;
+            for node in &file.nodes {
+                let line_number = node.line_number.unwrap_or_default();
+                writeln!(lcov, "FN:{line_number},{}", node.node_name)?;
+            }
+            for node in &file.nodes {
+                writeln!(lcov, "FNDA:{},{}", node.visit_count, node.node_name)?;
+            }
+            let nodes = file.nodes();
+            writeln!(lcov, "FNF:{}", nodes.total)?;
+            writeln!(lcov, "FNH:{}", nodes.hit)?;
+            for (block, node) in file.nodes.iter().enumerate() {
+                let options = node
+                    .lines
+                    .iter()
+                    .filter_map(|line| Some((line, line.selected_count?)));
+                for (branch, (line, selected_count)) in options.enumerate() {
+                    let taken = if line.hit_count == 0 {
+                        "-".to_owned()
+                    } else {
+                        selected_count.to_string()
+                    };
+                    writeln!(lcov, "BRDA:{},{block},{branch},{taken}", line.line_number)?;
+                }
+            }
+            let options = file.options();
+            writeln!(lcov, "BRF:{}", options.total)?;
+            writeln!(lcov, "BRH:{}", options.hit)?;
+            for (line_number, hit_count) in &file.source_lines {
+                writeln!(lcov, "DA:{line_number},{hit_count}")?;
+            }
+            writeln!(lcov, "LF:{}", file.source_lines.len())?;
+            let lines_hit = file.source_lines.values().filter(|&&count| count > 0);
+            writeln!(lcov, "LH:{}", lines_hit.count())?;
+            writeln!(lcov, "end_of_record")?;
+        }
+        Ok(())
+    }
+}
+
+impl FileCoverage {
+    /// How many lines and options of the file were delivered or offered at least once.
+    pub fn lines(&self) -> CoverageCount {
+        self.nodes.iter().map(NodeCoverage::lines).sum()
+    }
+
+    /// How many options of the file were selected at least once.
+    pub fn options(&self) -> CoverageCount {
+        self.nodes.iter().map(NodeCoverage::options).sum()
+    }
+
+    /// How many nodes of the file were visited at least once.
+    pub fn nodes(&self) -> CoverageCount {
+        CoverageCount::count(self.nodes.iter().map(|node| node.visit_count > 0))
+    }
+
+    /// How many instructions of the file were executed at least once.
+    pub fn instructions(&self) -> CoverageCount {
+        self.nodes.iter().map(|node| node.instructions).sum()
+    }
+}
+
+impl NodeCoverage {
+    /// How many lines and options of the node were delivered or offered at least once.
+    pub fn lines(&self) -> CoverageCount {
+        CoverageCount::count(self.lines.iter().map(|line| line.hit_count > 0))
+    }
+
+    /// How many options of the node were selected at least once.
+    pub fn options(&self) -> CoverageCount {
+        CoverageCount::count(
+            self.lines
+                .iter()
+                .filter_map(|line| line.selected_count)
+                .map(|count| count > 0),
+        )
+    }
+}
+
+impl Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            writeln!(
+                f,
+                "{}: lines {}, options {}, instructions {}",
+                file.file_name,
+                file.lines(),
+                file.options(),
+                file.instructions()
+            )?;
+            for node in &file.nodes {
+                writeln!(
+                    f,
+                    "  {} (visited {}x): lines {}, options {}, instructions {}",
+                    node.node_name,
+                    node.visit_count,
+                    node.lines(),
+                    node.options(),
+                    node.instructions
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn line_coverage(
+    line_id: &LineId,
+    string_info: &StringInfo,
+    data: &CoverageData,
+    is_option: bool,
+) -> LineCoverage {
+    let count = |counts: &BTreeMap<LineId, usize>| counts.get(line_id).copied().unwrap_or_default();
+    let (hit_count, selected_count) = if is_option {
+        (
+            count(&data.offered_options),
+            Some(count(&data.selected_options)),
+        )
+    } else {
+        (count(&data.lines), None)
+    };
+    LineCoverage {
+        line_id: line_id.clone(),
+        line_number: string_info.line_number,
+        hit_count,
+        selected_count,
+    }
+}
+
+/// The IDs of all lines that are used as options, i.e. are the first operand of an [`OpCode::AddOption`] instruction.
+fn option_line_ids(compilation: &Compilation) -> HashSet<LineId> {
+    compilation
+        .program
+        .iter()
+        .flat_map(|program| program.nodes.values())
+        .flat_map(|node| &node.instructions)
+        .filter(|instruction| instruction.opcode() == OpCode::AddOption)
+        .filter_map(|instruction| {
+            let line_id: String = instruction.operands.first()?.clone().try_into().ok()?;
+            Some(LineId(line_id))
+        })
+        .collect()
+}
@@ -0,0 +1,716 @@
+//! Playing through a Yarn project automatically to find problems that only show up at runtime.
+//!
+//! An [`Explorer`] runs a fresh [`Dialogue`] over and over, picking a different path through the options every time,
+//! either exhaustively in breadth-first order or at random from a seed. The resulting [`ExplorationReport`] lists
+//! runtime errors, panics, dead ends and content that no playthrough reached, which makes it suitable to run in CI against a whole project,
+//! in the spirit of a fuzzer.
+//!
+//! Note that a jump loop that doesn't deliver any content in between, like a node consisting only of `<<jump Start>>`,
+//! never returns control to the explorer. Use the [`InfiniteJumpLoopChecker`](crate::runtime::InfiniteJumpLoopChecker) to find those statically.
+
+use crate::compiler::Compilation;
+use crate::core::{Library, LineId, Type, YarnValue};
+use crate::coverage::{CoverageData, CoverageRecorder, CoverageReport};
+use crate::localization::sorted_string_infos;
+use crate::runtime::{
+    Command, Dialogue, DialogueEvent, DialogueOption, MemoryVariableStorage, OptionId,
+    StringTableTextProvider, VariableStorage,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{self, Debug, Display};
+use std::panic::{self, AssertUnwindSafe};
+
+/// A function that stands in for the game when the dialogue runs a command. See [`Explorer::with_command_stub`].
+pub type CommandStub = Box<dyn FnMut(&Command, &mut dyn VariableStorage) -> Result<(), String>>;
+
+/// Plays through the [`Compilation::program`] of a [`Compilation`] automatically.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner::prelude::*;
+/// # use yarnspinner::exploration::*;
+/// let file = YarnFile {
+///     file_name: "example.yarn".to_string(),
+///     source: "title: Start\n---\n-> Stay\n-> Leave\n    <<jump Nowhere>>\n===".to_string(),
+/// };
+/// let compilation = YarnCompiler::new().add_file(file).compile().unwrap();
+///
+/// let report = Explorer::new(&compilation).explore();
+///
+/// assert_eq!(2, report.playthrough_count);
+/// // Choosing "Leave" fails because the node `Nowhere` does not exist
+/// assert_eq!(1, report.issues.len());
+/// ```
+pub struct Explorer<'a> {
+    compilation: &'a Compilation,
+    strategy: ExplorationStrategy,
+    start_nodes: Vec<String>,
+    library: Library,
+    command_stub: CommandStub,
+    variables: Vec<(String, Vec<YarnValue>)>,
+    max_depth: usize,
+    max_playthroughs: usize,
+    max_steps: usize,
+}
+
+impl Debug for Explorer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Explorer")
+            .field("strategy", &self.strategy)
+            .field("start_nodes", &self.start_nodes)
+            .field("library", &self.library)
+            .field("command_stub", &"<CommandStub>")
+            .field("variables", &self.variables)
+            .field("max_depth", &self.max_depth)
+            .field("max_playthroughs", &self.max_playthroughs)
+            .field("max_steps", &self.max_steps)
+            .finish()
+    }
+}
+
+/// How an [`Explorer`] picks the options to select.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ExplorationStrategy {
+    /// Selects every available option, one after another, exploring shorter paths first.
+    /// This finds everything there is to find, but the number of paths grows exponentially with the number of options.
+    #[default]
+    BreadthFirst,
+    /// Selects a random available option every time. The same seed always results in the same playthroughs.
+    Random {
+        /// The seed of the random number generator.
+        seed: u64,
+        /// How many playthroughs to run.
+        playthroughs: usize,
+    },
+}
+
+impl<'a> Explorer<'a> {
+    /// Creates a new [`Explorer`] for the given [`Compilation`].
+    /// By default, it explores breadth-first from the `Start` node, ignores all commands and gives up on playthroughs after 32 options.
+    pub fn new(compilation: &'a Compilation) -> Self {
+        Self {
+            compilation,
+            strategy: ExplorationStrategy::default(),
+            start_nodes: vec!["Start".to_owned()],
+            library: Library::new(),
+            command_stub: Box::new(|_, _| Ok(())),
+            variables: Vec::new(),
+            max_depth: 32,
+            max_playthroughs: 10_000,
+            max_steps: 10_000,
+        }
+    }
+
+    /// Sets the [`ExplorationStrategy`].
+    pub fn with_strategy(mut self, strategy: ExplorationStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the nodes the playthroughs start at. Defaults to `Start`.
+    pub fn with_start_nodes(
+        mut self,
+        start_nodes: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.start_nodes = start_nodes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Adds the functions the Yarn scripts call, in addition to the ones every [`Dialogue`] provides.
+    pub fn with_library(mut self, library: Library) -> Self {
+        self.library.import(library);
+        self
+    }
+
+    /// Sets the function that is called instead of the game whenever the dialogue runs a command.
+    /// It may change variables to simulate what the command would do in the game.
+    /// Returning an error reports an [`ExplorationIssueKind::CommandFailed`], which is useful to e.g. catch commands with the wrong parameters.
+    ///
+    /// By default, all commands are ignored.
+    pub fn with_command_stub(
+        mut self,
+        command_stub: impl FnMut(&Command, &mut dyn VariableStorage) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.command_stub = Box::new(command_stub);
+        self
+    }
+
+    /// Explores the dialogue once for every given value of the variable, overriding its initial value.
+    /// Multiple variables are explored in every combination of their values.
+    /// Passing no values leaves the variable at its initial value instead.
+    pub fn with_variable_values(
+        mut self,
+        name: impl Into<String>,
+        values: impl IntoIterator<Item = impl Into<YarnValue>>,
+    ) -> Self {
+        let name = name.into();
+        let values: Vec<_> = values.into_iter().map(Into::into).collect();
+        self.variables
+            .retain(|(existing_name, _)| existing_name != &name);
+        // An empty list would leave no combination of values at all and thus no playthroughs
+        if !values.is_empty() {
+            self.variables.push((name, values));
+        }
+        self
+    }
+
+    /// Explores both `true` and `false` for every boolean variable declared in the [`Compilation`], see [`Explorer::with_variable_values`].
+    pub fn with_boolean_variables_explored(mut self) -> Self {
+        let names: Vec<_> = self
+            .compilation
+            .declarations
+            .iter()
+            .filter(|declaration| declaration.r#type == Type::Boolean && !declaration.is_implicit)
+            .map(|declaration| declaration.name.clone())
+            .collect();
+        for name in names {
+            self = self.with_variable_values(name, [false, true]);
+        }
+        self
+    }
+
+    /// Sets after how many selected options a playthrough is stopped. Defaults to 32.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets after how many playthroughs breadth-first exploration gives up. Defaults to 10 000.
+    pub fn with_max_playthroughs(mut self, max_playthroughs: usize) -> Self {
+        self.max_playthroughs = max_playthroughs;
+        self
+    }
+
+    /// Sets after how many calls to [`Dialogue::continue_`] a single playthrough is considered to be stuck in a loop. Defaults to 10 000.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Runs the playthroughs and reports what was found.
+    pub fn explore(&mut self) -> ExplorationReport {
+        let mut report = ExplorationReport {
+            is_exhaustive: matches!(self.strategy, ExplorationStrategy::BreadthFirst),
+            ..Default::default()
+        };
+        match self.strategy {
+            ExplorationStrategy::BreadthFirst => self.explore_breadth_first(&mut report),
+            ExplorationStrategy::Random { seed, playthroughs } => {
+                self.explore_randomly(&mut report, seed, playthroughs)
+            }
+        }
+
+        let coverage_report = CoverageReport::new(self.compilation, &report.coverage);
+        report.unvisited_nodes = coverage_report
+            .files
+            .iter()
+            .flat_map(|file| &file.nodes)
+            .filter(|node| node.visit_count == 0)
+            .map(|node| node.node_name.clone())
+            .collect();
+        report.unreached_lines = sorted_string_infos(self.compilation)
+            .into_iter()
+            .map(|(line_id, _)| line_id)
+            .filter(|line_id| {
+                !report.coverage.lines.contains_key(line_id)
+                    && !report.coverage.offered_options.contains_key(line_id)
+            })
+            .cloned()
+            .collect();
+        report
+    }
+
+    fn explore_breadth_first(&mut self, report: &mut ExplorationReport) {
+        let mut queue: VecDeque<Playthrough> = self.initial_playthroughs().collect();
+        while let Some(playthrough) = queue.pop_front() {
+            if report.playthrough_count >= self.max_playthroughs {
+                report.is_exhaustive = false;
+                break;
+            }
+            match self.run(playthrough, None) {
+                RunOutcome::Branched {
+                    playthrough,
+                    options,
+                } => queue.extend(options.iter().map(|option| {
+                    let mut playthrough = playthrough.clone();
+                    playthrough.choices.push(Choice::from(option));
+                    playthrough
+                })),
+                RunOutcome::Ended {
+                    playthrough,
+                    ending,
+                    coverage,
+                } => {
+                    if ending == Ending::Truncated {
+                        report.is_exhaustive = false;
+                    }
+                    report.add(playthrough, ending, &coverage);
+                }
+            }
+        }
+    }
+
+    fn explore_randomly(&mut self, report: &mut ExplorationReport, seed: u64, playthroughs: usize) {
+        let mut rng = Rng(seed);
+        let initial_playthroughs: Vec<_> = self.initial_playthroughs().collect();
+        if initial_playthroughs.is_empty() {
+            return;
+        }
+        for _ in 0..playthroughs {
+            let playthrough = initial_playthroughs[rng.below(initial_playthroughs.len())].clone();
+            if let RunOutcome::Ended {
+                playthrough,
+                ending,
+                coverage,
+            } = self.run(playthrough, Some(&mut rng))
+            {
+                report.add(playthrough, ending, &coverage);
+            }
+        }
+    }
+
+    /// Every combination of start node and variable values, without any choices made yet.
+    fn initial_playthroughs(&self) -> impl Iterator<Item = Playthrough> + '_ {
+        let combination_count: usize = self
+            .variables
+            .iter()
+            .map(|(_, values)| values.len())
+            .product();
+        self.start_nodes.iter().flat_map(move |start_node| {
+            (0..combination_count).map(move |mut combination| {
+                let mut variables = BTreeMap::new();
+                for (name, values) in &self.variables {
+                    variables.insert(name.clone(), values[combination % values.len()].clone());
+                    combination /= values.len();
+                }
+                Playthrough {
+                    start_node: start_node.clone(),
+                    variables,
+                    ..Default::default()
+                }
+            })
+        })
+    }
+
+    /// Plays through the dialogue, following the choices already made in `playthrough`.
+    /// Afterwards, new choices are made at random if an `rng` is given. Otherwise, the options are returned to be explored later.
+    fn run(&mut self, playthrough: Playthrough, rng: Option<&mut Rng>) -> RunOutcome {
+        let mut state = RunState {
+            playthrough,
+            recorder: CoverageRecorder::new(),
+        };
+        let progress = panic::catch_unwind(AssertUnwindSafe(|| self.try_run(&mut state, rng)));
+        let ending = match progress {
+            Ok(Progress::Ended(ending)) => ending,
+            Ok(Progress::Branched(options)) => {
+                return RunOutcome::Branched {
+                    playthrough: state.playthrough,
+                    options,
+                };
+            }
+            Err(payload) => Ending::Issue(ExplorationIssueKind::Panic {
+                message: panic_message(payload.as_ref()),
+            }),
+        };
+        RunOutcome::Ended {
+            playthrough: state.playthrough,
+            ending,
+            coverage: state.recorder.into_data(),
+        }
+    }
+
+    /// Stops at the first choice that has not been made yet if there is no `rng` to make it.
+    fn try_run(&mut self, state: &mut RunState, mut rng: Option<&mut Rng>) -> Progress {
+        let dialogue_error = |error: &dyn Display| {
+            Ending::Issue(ExplorationIssueKind::DialogueError {
+                message: error.to_string(),
+            })
+        };
+        let mut dialogue = match self.create_dialogue(&state.playthrough) {
+            Ok(dialogue) => dialogue,
+            Err(message) => {
+                return Ending::Issue(ExplorationIssueKind::DialogueError { message }).into()
+            }
+        };
+        // The known choices and their lines are added again while replaying them
+        let known_choices = std::mem::take(&mut state.playthrough.choices);
+        state.playthrough.line_count = 0;
+
+        for _ in 0..self.max_steps {
+            let events = match state.recorder.continue_(&mut dialogue) {
+                Ok(events) => events,
+                Err(error) => return dialogue_error(&error).into(),
+            };
+            for event in events {
+                match event {
+                    DialogueEvent::Line(_) => state.playthrough.line_count += 1,
+                    DialogueEvent::Command(command) => {
+                        if let Err(message) =
+                            (self.command_stub)(&command, dialogue.variable_storage_mut())
+                        {
+                            return Ending::Issue(ExplorationIssueKind::CommandFailed {
+                                command: command.raw,
+                                message,
+                            })
+                            .into();
+                        }
+                    }
+                    DialogueEvent::Options(options) => {
+                        let available_options: Vec<_> = options
+                            .into_iter()
+                            .filter(|option| option.is_available)
+                            .collect();
+                        if available_options.is_empty() {
+                            return Ending::Issue(ExplorationIssueKind::DeadEnd).into();
+                        }
+                        let depth = state.playthrough.choices.len();
+                        let option_id = if let Some(choice) = known_choices.get(depth) {
+                            choice.option_id
+                        } else if depth >= self.max_depth {
+                            return Ending::Truncated.into();
+                        } else if let Some(rng) = rng.as_deref_mut() {
+                            available_options[rng.below(available_options.len())].id
+                        } else {
+                            return Progress::Branched(available_options);
+                        };
+                        let Some(option) = available_options
+                            .iter()
+                            .find(|option| option.id == option_id)
+                        else {
+                            return Ending::Issue(ExplorationIssueKind::DialogueError {
+                                message: format!("{option_id:?} is no longer available when replaying the playthrough"),
+                            })
+                            .into();
+                        };
+                        state.playthrough.choices.push(Choice::from(option));
+                        if let Err(error) =
+                            state.recorder.set_selected_option(&mut dialogue, option_id)
+                        {
+                            return dialogue_error(&error).into();
+                        }
+                    }
+                    DialogueEvent::DialogueComplete => return Ending::Complete.into(),
+                    _ => {}
+                }
+            }
+        }
+        Ending::Issue(ExplorationIssueKind::StepLimitExceeded {
+            steps: self.max_steps,
+        })
+        .into()
+    }
+
+    fn create_dialogue(&self, playthrough: &Playthrough) -> Result<Dialogue, String> {
+        let Some(program) = self.compilation.program.clone() else {
+            return Err("The compilation contains no program".to_owned());
+        };
+        let mut text_provider = StringTableTextProvider::new();
+        text_provider.extend_base_language(
+            self.compilation
+                .string_table
+                .iter()
+                .map(|(line_id, string_info)| (line_id.clone(), string_info.text.clone()))
+                .collect(),
+        );
+        let mut dialogue = Dialogue::new(
+            Box::new(MemoryVariableStorage::new()),
+            Box::new(text_provider),
+        );
+        dialogue.library_mut().import(self.library.clone());
+        dialogue.add_program(program);
+        for (name, value) in &playthrough.variables {
+            dialogue
+                .variable_storage_mut()
+                .set(name.clone(), value.clone())
+                .map_err(|error| error.to_string())?;
+        }
+        dialogue
+            .set_node(&playthrough.start_node)
+            .map_err(|error| error.to_string())?;
+        Ok(dialogue)
+    }
+}
+
+struct RunState {
+    playthrough: Playthrough,
+    recorder: CoverageRecorder,
+}
+
+enum RunOutcome {
+    Branched {
+        playthrough: Playthrough,
+        options: Vec<DialogueOption>,
+    },
+    Ended {
+        playthrough: Playthrough,
+        ending: Ending,
+        coverage: CoverageData,
+    },
+}
+
+/// How far [`Explorer::try_run`] got.
+enum Progress {
+    Ended(Ending),
+    /// Reached a choice that is yet to be made between these options.
+    Branched(Vec<DialogueOption>),
+}
+
+impl From<Ending> for Progress {
+    fn from(ending: Ending) -> Self {
+        Self::Ended(ending)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Ending {
+    Complete,
+    Truncated,
+    Issue(ExplorationIssueKind),
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Unknown panic".to_owned()
+    }
+}
+
+/// A small SplitMix64 generator. Good enough to pick options and stable across platforms and versions, which is what matters for reproducing a seed.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, upper_bound: usize) -> usize {
+        (self.next_u64() % upper_bound as u64) as usize
+    }
+}
+
+/// What an [`Explorer`] found. Its [`Display`] implementation prints a summary followed by one issue per line.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExplorationReport {
+    /// How many playthroughs were run to their end, regardless of how they ended.
+    pub playthrough_count: usize,
+    /// How many playthroughs reached the end of the dialogue without issues.
+    pub completed_count: usize,
+    /// How many playthroughs were stopped because they reached the maximum depth.
+    pub truncated_count: usize,
+    /// Whether every path through the dialogue was explored.
+    /// Only then do [`ExplorationReport::unreached_lines`] and [`ExplorationReport::unvisited_nodes`] prove that content is unreachable.
+    pub is_exhaustive: bool,
+    /// The problems found, in the order they were encountered.
+    pub issues: Vec<ExplorationIssue>,
+    /// The playthrough that selected the most options. Ties are won by the playthrough that was run first.
+    pub deepest_playthrough: Option<Playthrough>,
+    /// The lines and options that no playthrough delivered or offered, sorted by file name and line number.
+    pub unreached_lines: Vec<LineId>,
+    /// The nodes that no playthrough visited, sorted by file name and position.
+    pub unvisited_nodes: Vec<String>,
+    /// The combined coverage of all playthroughs, e.g. to create a [`CoverageReport`].
+    pub coverage: CoverageData,
+}
+
+impl ExplorationReport {
+    /// Returns `true` if no issues were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The maximum number of options selected in a single playthrough.
+    pub fn max_depth(&self) -> usize {
+        self.deepest_playthrough
+            .as_ref()
+            .map(|playthrough| playthrough.choices.len())
+            .unwrap_or_default()
+    }
+
+    fn add(&mut self, playthrough: Playthrough, ending: Ending, coverage: &CoverageData) {
+        self.playthrough_count += 1;
+        self.coverage.merge(coverage);
+        let is_deeper = match &self.deepest_playthrough {
+            Some(deepest) => playthrough.choices.len() > deepest.choices.len(),
+            None => true,
+        };
+        if is_deeper {
+            self.deepest_playthrough = Some(playthrough.clone());
+        }
+        match ending {
+            Ending::Complete => self.completed_count += 1,
+            Ending::Truncated => self.truncated_count += 1,
+            Ending::Issue(kind) => self.issues.push(ExplorationIssue { playthrough, kind }),
+        }
+    }
+}
+
+impl Display for ExplorationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} playthroughs: {} completed, {} truncated, {} with issues, maximum depth {}",
+            self.playthrough_count,
+            self.completed_count,
+            self.truncated_count,
+            self.issues.len(),
+            self.max_depth()
+        )?;
+        let reached = if self.is_exhaustive {
+            "unreachable"
+        } else {
+            "not reached"
+        };
+        if !self.unvisited_nodes.is_empty() {
+            writeln!(f, "Nodes {reached}: {}", self.unvisited_nodes.join(", "))?;
+        }
+        if !self.unreached_lines.is_empty() {
+            let line_ids: Vec<_> = self
+                .unreached_lines
+                .iter()
+                .map(|id| id.0.as_str())
+                .collect();
+            writeln!(f, "Lines {reached}: {}", line_ids.join(", "))?;
+        }
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A single path through the dialogue.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Playthrough {
+    /// The node the playthrough started at.
+    pub start_node: String,
+    /// The values the explored variables were set to before starting, see [`Explorer::with_variable_values`].
+    pub variables: BTreeMap<String, YarnValue>,
+    /// The options that were selected, in order.
+    pub choices: Vec<Choice>,
+    /// How many lines were delivered.
+    pub line_count: usize,
+}
+
+impl Display for Playthrough {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.start_node)?;
+        if !self.variables.is_empty() {
+            let variables: Vec<_> = self
+                .variables
+                .iter()
+                .map(|(name, value)| format!("{name} = {value}"))
+                .collect();
+            write!(f, " ({})", variables.join(", "))?;
+        }
+        for choice in &self.choices {
+            write!(f, " -> \"{}\"", choice.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// An option selected during a [`Playthrough`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Choice {
+    /// The ID that was passed to [`Dialogue::set_selected_option`].
+    pub option_id: OptionId,
+    /// The ID of the option's line.
+    pub line_id: LineId,
+    /// The text of the option as it was shown.
+    pub text: String,
+}
+
+impl From<&DialogueOption> for Choice {
+    fn from(option: &DialogueOption) -> Self {
+        Self {
+            option_id: option.id,
+            line_id: option.line.id.clone(),
+            text: option.line.text.clone(),
+        }
+    }
+}
+
+/// A problem found during a [`Playthrough`]. Part of an [`ExplorationReport`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExplorationIssue {
+    /// The path that led to the problem. Its last choice is the one made right before the problem occurred.
+    pub playthrough: Playthrough,
+    /// What went wrong.
+    pub kind: ExplorationIssueKind,
+}
+
+impl Display for ExplorationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.playthrough, self.kind)
+    }
+}
+
+/// The kinds of problems an [`Explorer`] can find.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ExplorationIssueKind {
+    /// The [`Dialogue`] returned a [`DialogueError`](crate::runtime::DialogueError), e.g. because a node or function does not exist.
+    DialogueError {
+        /// The description of the error.
+        message: String,
+    },
+    /// The [`Dialogue`] panicked.
+    Panic {
+        /// The panic message.
+        message: String,
+    },
+    /// The [`CommandStub`] returned an error for this command.
+    CommandFailed {
+        /// The text of the command, without the surrounding `<<` and `>>`.
+        command: String,
+        /// The error returned by the stub.
+        message: String,
+    },
+    /// Options were offered, but none of them was available, so the player would be stuck.
+    DeadEnd,
+    /// The dialogue did not finish within the given number of steps, which hints at an infinite loop.
+    StepLimitExceeded {
+        /// The maximum number of steps of a playthrough.
+        steps: usize,
+    },
+}
+
+impl Display for ExplorationIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExplorationIssueKind::DialogueError { message } => {
+                write!(f, "Dialogue error: {message}")
+            }
+            ExplorationIssueKind::Panic { message } => write!(f, "Panic: {message}"),
+            ExplorationIssueKind::CommandFailed { command, message } => {
+                write!(f, "Command <<{command}>> failed: {message}")
+            }
+            ExplorationIssueKind::DeadEnd => {
+                f.write_str("Dead end: none of the options are available")
+            }
+            ExplorationIssueKind::StepLimitExceeded { steps } => {
+                write!(
+                    f,
+                    "Did not finish within {steps} steps, is there an infinite loop?"
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//! Visualizing the structure of a Yarn project as a graph of its nodes.
+//!
+//! A [`FlowGraph`] is built from a [`Compilation`] by looking at the compiled instructions of every node, so no [`Dialogue`](crate::runtime::Dialogue) needs to run.
+//! It can be written as [Graphviz DOT](https://graphviz.org/doc/info/lang.html), as a [Mermaid](https://mermaid.js.org/syntax/flowchart.html) flowchart,
+//! or, with the `serde` feature, as JSON.
+
+use crate::compiler::Compilation;
+use crate::core::{Instruction, LineId, Node, OpCode, OperandValue};
+use crate::runtime::{NodeWalker, PathCondition, StackValue, WalkStep};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{self, Write};
+
+/// A graph with the nodes of a Yarn project as vertices and the ways to get from one node to another as edges.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner::prelude::*;
+/// # use yarnspinner::flow_graph::FlowGraph;
+/// let file = YarnFile {
+///     file_name: "example.yarn".to_string(),
+///     source: "title: Start\n---\n-> Go to the shop\n    <<jump Shop>>\n===\ntitle: Shop\n---\nWelcome!\n===".to_string(),
+/// };
+/// let compilation = YarnCompiler::new().add_file(file).compile().unwrap();
+///
+/// let graph = FlowGraph::from_compilation(&compilation);
+/// assert!(graph.to_dot().contains(r#""Start" -> "Shop" [label="Go to the shop"]"#));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlowGraph {
+    /// The nodes of the project, sorted by name.
+    pub nodes: Vec<FlowNode>,
+    /// The edges between the nodes, sorted by the name of their source node.
+    pub edges: Vec<FlowEdge>,
+}
+
+/// A node of the Yarn project. Part of a [`FlowGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlowNode {
+    /// The name of the node, as given by its `title` header.
+    pub name: String,
+    /// The tags of the node, as given by its `tags` header.
+    pub tags: Vec<String>,
+}
+
+/// A way to get from one node to another. Part of a [`FlowGraph`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlowEdge {
+    /// The name of the node the edge starts at.
+    pub source: String,
+    /// Where the edge leads to.
+    pub target: FlowEdgeTarget,
+    /// The option that has to be selected to take this edge, if any.
+    pub option: Option<FlowOption>,
+    /// The conditions of the `<<if>>` statements that have to hold to take this edge, as Yarn expressions.
+    pub conditions: Vec<String>,
+}
+
+/// The destination of a [`FlowEdge`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FlowEdgeTarget {
+    /// A jump to the node with the given name, like `<<jump Shop>>`. The node does not necessarily exist.
+    Node(String),
+    /// A jump to a node whose name is only known at runtime, like `<<jump {$destination}>>`.
+    Dynamic {
+        /// The expression that determines the destination, as a Yarn expression.
+        expression: String,
+    },
+}
+
+/// An option that leads to a [`FlowEdge`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlowOption {
+    /// The ID of the line that is shown for the option.
+    pub line_id: LineId,
+    /// The text of the option in the base language, if it is found in the [`Compilation::string_table`].
+    pub text: Option<String>,
+    /// The condition of the option, like `$gold > 10` in `-> Buy <<if $gold > 10>>`, as a Yarn expression.
+    pub condition: Option<String>,
+}
+
+impl FlowGraph {
+    /// Builds the graph of the [`Compilation::program`]. Returns an empty graph if the compilation has no program.
+    pub fn from_compilation(compilation: &Compilation) -> Self {
+        let Some(program) = compilation.program.as_ref() else {
+            return Self::default();
+        };
+        let nodes: BTreeMap<_, _> = program.nodes.iter().collect();
+        let graph_nodes = nodes
+            .values()
+            .map(|node| FlowNode {
+                name: node.name.clone(),
+                tags: node.tags.clone(),
+            })
+            .collect();
+        let edges = nodes
+            .values()
+            .flat_map(|node| node_edges(node, compilation))
+            .collect();
+        Self {
+            nodes: graph_nodes,
+            edges,
+        }
+    }
+
+    /// Writes the graph in the [Graphviz DOT](https://graphviz.org/doc/info/lang.html) language.
+    /// Jumps to nodes that don't exist are drawn dashed, as are jumps whose destination is only known at runtime.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        // Writing into a `String` never fails
+        self.write_dot(&mut dot).unwrap();
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String) -> fmt::Result {
+        writeln!(dot, "digraph Dialogue {{")?;
+        writeln!(dot, "    node [shape=box];")?;
+        for node in &self.nodes {
+            writeln!(dot, "    {};", dot_string(&node.name))?;
+        }
+        for missing_node in self.missing_nodes() {
+            writeln!(dot, "    {} [style=dashed];", dot_string(missing_node))?;
+        }
+        for (index, edge) in self.edges.iter().enumerate() {
+            let label = edge.label();
+            let label = (!label.is_empty())
+                .then(|| format!("label={}", dot_string(&label)))
+                .into_iter();
+            let (target, attributes) = match &edge.target {
+                FlowEdgeTarget::Node(name) => (dot_string(name), label.collect::<Vec<_>>()),
+                FlowEdgeTarget::Dynamic { expression } => {
+                    let target = format!("dynamic_{index}");
+                    writeln!(
+                        dot,
+                        "    {target} [label={}, shape=diamond, style=dashed];",
+                        dot_string(&format!("{{{expression}}}"))
+                    )?;
+                    (target, label.chain(["style=dashed".to_owned()]).collect())
+                }
+            };
+            write!(dot, "    {} -> {target}", dot_string(&edge.source))?;
+            if !attributes.is_empty() {
+                write!(dot, " [{}]", attributes.join(", "))?;
+            }
+            writeln!(dot, ";")?;
+        }
+        writeln!(dot, "}}")
+    }
+
+    /// Writes the graph as a [Mermaid](https://mermaid.js.org/syntax/flowchart.html) flowchart.
+    /// Jumps to nodes that don't exist are drawn dashed, as are jumps whose destination is only known at runtime.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::new();
+        // Writing into a `String` never fails
+        self.write_mermaid(&mut mermaid).unwrap();
+        mermaid
+    }
+
+    fn write_mermaid(&self, mermaid: &mut String) -> fmt::Result {
+        writeln!(mermaid, "flowchart TD")?;
+        let mut ids = BTreeMap::new();
+        for node in &self.nodes {
+            let id = format!("n{}", ids.len());
+            writeln!(mermaid, "    {id}[{}]", mermaid_string(&node.name))?;
+            ids.insert(node.name.as_str(), id);
+        }
+        for missing_node in self.missing_nodes() {
+            let id = format!("n{}", ids.len());
+            writeln!(mermaid, "    {id}[{}]", mermaid_string(missing_node))?;
+            writeln!(mermaid, "    style {id} stroke-dasharray: 5 5")?;
+            ids.insert(missing_node, id);
+        }
+        for (index, edge) in self.edges.iter().enumerate() {
+            let source = &ids[edge.source.as_str()];
+            let label = edge.label();
+            let label = if label.is_empty() {
+                String::new()
+            } else {
+                format!("|{}|", mermaid_string(&label))
+            };
+            match &edge.target {
+                FlowEdgeTarget::Node(name) => {
+                    writeln!(mermaid, "    {source} -->{label} {}", ids[name.as_str()])?
+                }
+                FlowEdgeTarget::Dynamic { expression } => writeln!(
+                    mermaid,
+                    "    {source} -.->{label} dynamic{index}{{{}}}",
+                    mermaid_string(&format!("{{{expression}}}"))
+                )?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the graph as JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        // Serializing plain data into a `String` never fails
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    /// The names of the nodes that are jumped to but don't exist, sorted by name.
+    fn missing_nodes(&self) -> Vec<&str> {
+        let existing_nodes: HashSet<_> = self.nodes.iter().map(|node| node.name.as_str()).collect();
+        let mut missing_nodes: Vec<_> = self
+            .edges
+            .iter()
+            .filter_map(|edge| match &edge.target {
+                FlowEdgeTarget::Node(name) if !existing_nodes.contains(name.as_str()) => {
+                    Some(name.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        missing_nodes.sort_unstable();
+        missing_nodes.dedup();
+        missing_nodes
+    }
+}
+
+impl FlowEdge {
+    /// The option text and conditions of this edge, one per line.
+    fn label(&self) -> String {
+        let option = self.option.iter().flat_map(|option| {
+            let text = option
+                .text
+                .clone()
+                .unwrap_or_else(|| option.line_id.to_string());
+            let condition = option
+                .condition
+                .iter()
+                .map(|condition| format!("if {condition}"));
+            [text].into_iter().chain(condition)
+        });
+        let conditions = self
+            .conditions
+            .iter()
+            .map(|condition| format!("if {condition}"));
+        option.chain(conditions).collect::<Vec<_>>().join("\n")
+    }
+}
+
+fn dot_string(text: &str) -> String {
+    let escaped = text
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n");
+    format!("\"{escaped}\"")
+}
+
+fn mermaid_string(text: &str) -> String {
+    let escaped = text.replace('"', "#quot;").replace('\n', "<br>");
+    format!("\"{escaped}\"")
+}
+
+/// Collects the edges of a single node.
+fn node_edges(node: &Node, compilation: &Compilation) -> Vec<FlowEdge> {
+    let walker = NodeWalker::new(node);
+    let mut edges = walk_edges(&walker, 0, None);
+    for (index, instruction) in node.instructions.iter().enumerate() {
+        if instruction.opcode() != OpCode::AddOption {
+            continue;
+        }
+        let line_id = LineId(operand_string(instruction, 0).unwrap_or_default());
+        let option = FlowOption {
+            text: compilation
+                .string_table
+                .get(&line_id)
+                .map(|string_info| string_info.text.clone()),
+            line_id,
+            condition: walker
+                .value_at(index)
+                .map(|condition| Expression::from(condition).text),
+        };
+        // The second operand is the label of the code that runs when the option is selected
+        let destination =
+            operand_string(instruction, 1).and_then(|label| walker.label_index(&label));
+        if let Some(destination) = destination {
+            edges.extend(walk_edges(&walker, destination, Some(option)));
+        }
+    }
+    let mut seen = HashSet::new();
+    edges.retain(|edge| seen.insert(edge.clone()));
+    edges
+}
+
+/// Follows the instructions from the given index until the node is left or options are shown, collecting the jumps on the way.
+fn walk_edges(walker: &NodeWalker, start: usize, option: Option<FlowOption>) -> Vec<FlowEdge> {
+    let node = walker.node();
+    let mut edges = Vec::new();
+    walker.walk(start, |step| {
+        let WalkStep::Instruction { index, conditions } = step else {
+            return false;
+        };
+        if node.instructions[index].opcode() == OpCode::RunNode {
+            let target = match walker.jump_target(index) {
+                Some(name) => FlowEdgeTarget::Node(name.to_owned()),
+                None => FlowEdgeTarget::Dynamic {
+                    expression: walker
+                        .value_at(index)
+                        .map(|value| Expression::from(value).text)
+                        .unwrap_or_else(|| Expression::unknown().text),
+                },
+            };
+            edges.push(FlowEdge {
+                source: node.name.clone(),
+                target,
+                option: option.clone(),
+                conditions: conditions.iter().map(condition_text).collect(),
+            });
+        }
+        true
+    });
+    edges
+}
+
+fn condition_text(condition: &PathCondition) -> String {
+    let expression = Expression::from(&condition.value);
+    if condition.holds {
+        expression.text
+    } else {
+        format!("!{}", expression.as_operand())
+    }
+}
+
+/// A [`StackValue`] turned back into Yarn syntax.
+#[derive(Debug, Clone)]
+struct Expression {
+    text: String,
+    /// Whether the text must be put in parentheses when used as an operand.
+    is_compound: bool,
+}
+
+impl Expression {
+    fn unknown() -> Self {
+        Self::simple("?".to_owned())
+    }
+
+    fn simple(text: String) -> Self {
+        Self {
+            text,
+            is_compound: false,
+        }
+    }
+
+    fn as_operand(&self) -> String {
+        if self.is_compound {
+            format!("({})", self.text)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+impl From<&StackValue> for Expression {
+    fn from(value: &StackValue) -> Self {
+        match value {
+            StackValue::Constant(OperandValue::StringValue(string)) => {
+                Self::simple(format!("{string:?}"))
+            }
+            StackValue::Constant(OperandValue::FloatValue(float)) => {
+                Self::simple(float.to_string())
+            }
+            StackValue::Constant(OperandValue::BoolValue(boolean)) => {
+                Self::simple(boolean.to_string())
+            }
+            StackValue::Null => Self::simple("null".to_owned()),
+            StackValue::Variable(name) => Self::simple(name.clone()),
+            StackValue::FunctionCall { name, parameters } => {
+                let parameters: Vec<_> = parameters.iter().map(Expression::from).collect();
+                call_expression(name, &parameters)
+            }
+            StackValue::Unknown => Self::unknown(),
+        }
+    }
+}
+
+/// Turns a function call back into Yarn syntax. Operators are called as functions named like `Number.Add`.
+fn call_expression(function_name: &str, parameters: &[Expression]) -> Expression {
+    let operator = function_name
+        .rsplit_once('.')
+        .and_then(|(_, method)| operator_symbol(method));
+    match (operator, parameters) {
+        (Some(symbol), [operand]) => Expression {
+            text: format!("{symbol}{}", operand.as_operand()),
+            is_compound: false,
+        },
+        (Some(symbol), [lhs, rhs]) => Expression {
+            text: format!("{} {symbol} {}", lhs.as_operand(), rhs.as_operand()),
+            is_compound: true,
+        },
+        _ => {
+            let parameters: Vec<_> = parameters.iter().map(|p| p.text.as_str()).collect();
+            Expression {
+                text: format!("{function_name}({})", parameters.join(", ")),
+                is_compound: false,
+            }
+        }
+    }
+}
+
+fn operator_symbol(method: &str) -> Option<&'static str> {
+    let symbol = match method {
+        "EqualTo" => "==",
+        "NotEqualTo" => "!=",
+        "GreaterThan" => ">",
+        "GreaterThanOrEqualTo" => ">=",
+        "LessThan" => "<",
+        "LessThanOrEqualTo" => "<=",
+        "And" => "&&",
+        "Or" => "||",
+        "Xor" => "^",
+        "Not" => "!",
+        "UnarySubtract" => "-",
+        "Add" => "+",
+        "Subtract" => "-",
+        "Multiply" => "*",
+        "Divide" => "/",
+        "Modulo" => "%",
+        _ => return None,
+    };
+    Some(symbol)
+}
+
+fn operand_string(instruction: &Instruction, index: usize) -> Option<String> {
+    let operand = instruction.operands.get(index)?.clone();
+    operand.try_into().ok()
+}
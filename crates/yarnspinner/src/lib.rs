@@ -11,26 +11,29 @@ pub mod prelude {
     //! Everything you need to get started using Yarn Spinner.
     pub use crate::compiler::{
         Compilation, CompilationType, Compiler as YarnCompiler, CompilerError, File as YarnFile,
-        LineInfo, Result as YarnCompilerResult, StringInfo,
+        FunctionDeclaration, LineInfo, Result as YarnCompilerResult, StringInfo,
     };
     pub use crate::core::{
         yarn_library, IntoYarnValueFromNonYarnValue, Library as YarnLibrary, LineId,
         Program as YarnProgram, YarnFn, YarnValue,
     };
     pub use crate::runtime::{
-        Command as YarnCommand, CompiledProgramAnalyser as YarnAnalyser,
+        AttributeMarkerProcessor, Command as YarnCommand, CommandParameterType, CommandParseError,
+        CommandSignature, CommandSignatures, CompiledProgramAnalyser as YarnAnalyser,
         Context as YarnAnalysisContext, Dialogue, DialogueError, DialogueEvent, DialogueOption,
-        Language, Line as YarnLine, MarkupAttribute, MarkupValue, OptionId,
-        Result as YarnRuntimeResult, StringTable, TextProvider, VariableStorage,
+        Language, LanguageError, Line as YarnLine, MarkupAttribute, MarkupAttributeMarker,
+        MarkupNode, MarkupValue, OptionId, Result as YarnRuntimeResult, SeenLines, StringTable,
+        TextProvider, TextRun, VariableStorage,
     };
 }
 
 pub mod core {
     //! Core types and traits that are used by both the compiler and runtime.
     pub use yarnspinner_core::prelude::{
-        yarn_fn_type, yarn_library, Header, Instruction, IntoYarnValueFromNonYarnValue,
-        InvalidOpCodeError, Library, LineId, Node, Position, Program, Type, UntypedYarnFn, YarnFn,
-        YarnFnParam, YarnFnParamItem, YarnValue, YarnValueCastError, YarnValueWrapper,
+        yarn_fn_type, yarn_library, FormatSpecifier, Header, Instruction,
+        IntoYarnValueFromNonYarnValue, InvalidFormatSpecifierError, InvalidOpCodeError, Library,
+        LineId, Node, OpCode, Operand, OperandValue, Position, Program, Type, UntypedYarnFn,
+        YarnFn, YarnFnParam, YarnFnParamItem, YarnValue, YarnValueCastError, YarnValueWrapper,
         YarnValueWrapperIter,
     };
 }
@@ -40,11 +43,20 @@ pub mod compiler {
     pub use yarnspinner_compiler::Result;
 }
 
+pub mod coverage;
+pub mod exploration;
+pub mod flow_graph;
+pub mod localization;
+pub mod replay;
+pub mod variable_storage;
+
 pub mod runtime {
     //! Types and traits used by the runtime, in particular the [`Dialogue`] struct.
     pub use yarnspinner_runtime::markup::{
-        MarkupAttribute, MarkupParseError, MarkupValue, CHARACTER_ATTRIBUTE,
-        CHARACTER_ATTRIBUTE_NAME_PROPERTY, TRIM_WHITESPACE_PROPERTY,
+        markup_tree, text_runs, AttributeMarkerProcessor, LineParser, MarkupAttribute,
+        MarkupAttributeMarker, MarkupNode, MarkupParseError, MarkupValue, ParsedMarkup, TagType,
+        TextRun, CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY,
+        REPLACEMENT_MARKER_CONTENTS, TRIM_WHITESPACE_PROPERTY,
     };
     pub use yarnspinner_runtime::prelude::*;
     pub use yarnspinner_runtime::Result;
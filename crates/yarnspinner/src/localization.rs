@@ -0,0 +1,216 @@
+//! Engine-agnostic tools for localizing Yarn projects.
+//!
+//! The [`Compilation::string_table`] of a compiled project holds every line that needs to be translated.
+//! The functions in this module turn it into files that translators and localization vendors can work with,
+//! and read their translations back into a [`StringTable`] that can be passed to [`StringTableTextProvider::extend_translation`].
+//!
+//! [`StringTableTextProvider::extend_translation`]: crate::runtime::StringTableTextProvider::extend_translation
+
+use crate::compiler::{Compilation, StringInfo};
+use crate::core::{FormatSpecifier, LineId};
+use crate::runtime::{LineParser, MarkupParseError, ParsedMarkup, StringTable};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashSet};
+
+mod gettext;
+mod pseudo_localization;
+mod validation;
+mod xliff;
+
+pub use self::{gettext::*, pseudo_localization::*, validation::*, xliff::*};
+
+/// A translation read from a localization file, together with a report of how well it matches
+/// the [`Compilation`] it was imported for.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TranslationImport {
+    /// The language the file declares its translations to be in, if any.
+    pub language: Option<String>,
+
+    /// The translated lines. Only contains lines that are part of the [`Compilation`] and have a non-empty translation.
+    pub string_table: StringTable,
+
+    /// The IDs of lines that are part of the [`Compilation`], but have no translation in the imported file.
+    /// Sorted by file name and line number.
+    pub missing_line_ids: Vec<LineId>,
+
+    /// The IDs of lines that have a translation in the imported file, but are not part of the [`Compilation`].
+    /// These are usually left over from lines that were deleted or had their `#line:` tag changed.
+    pub extra_line_ids: Vec<LineId>,
+
+    /// The IDs of lines that were translated, but whose translation is probably out of date.
+    /// This is the case when the translator flagged the translation as such,
+    /// or when the base language text the translation was made for differs from the text in the [`Compilation`],
+    /// i.e. their [`compute_lock`] values don't match.
+    ///
+    /// These translations are still part of the [`TranslationImport::string_table`].
+    /// Sorted by file name and line number.
+    pub outdated_line_ids: Vec<LineId>,
+}
+
+impl TranslationImport {
+    pub(crate) fn new(
+        language: Option<String>,
+        lines: impl IntoIterator<Item = ImportedLine>,
+        compilation: &Compilation,
+    ) -> Self {
+        let mut string_table = StringTable::new();
+        let mut extra_line_ids = Vec::new();
+        let mut outdated_line_ids = HashSet::new();
+        for line in lines {
+            let Some(string_info) = compilation.string_table.get(&line.line_id) else {
+                extra_line_ids.push(line.line_id);
+                continue;
+            };
+            if line.translation.is_empty() {
+                continue;
+            }
+            let base_text_changed = line.source_text.is_some_and(|source_text| {
+                compute_lock(&source_text) != compute_lock(&string_info.text)
+            });
+            if line.is_flagged_outdated || base_text_changed {
+                outdated_line_ids.insert(line.line_id.clone());
+            }
+            string_table.insert(line.line_id, line.translation);
+        }
+        let line_ids_in_order = || {
+            sorted_string_infos(compilation)
+                .into_iter()
+                .map(|(line_id, _)| line_id)
+        };
+        let missing_line_ids = line_ids_in_order()
+            .filter(|line_id| !string_table.contains_key(line_id))
+            .cloned()
+            .collect();
+        let outdated_line_ids = line_ids_in_order()
+            .filter(|line_id| outdated_line_ids.contains(*line_id))
+            .cloned()
+            .collect();
+        let mut seen = HashSet::new();
+        extra_line_ids.retain(|line_id| seen.insert(line_id.clone()));
+        extra_line_ids.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+
+        Self {
+            language,
+            string_table,
+            missing_line_ids,
+            extra_line_ids,
+            outdated_line_ids,
+        }
+    }
+
+    /// Returns `true` if every line of the [`Compilation`] has an up-to-date translation and no unknown lines were found.
+    pub fn is_complete(&self) -> bool {
+        self.missing_line_ids.is_empty()
+            && self.extra_line_ids.is_empty()
+            && self.outdated_line_ids.is_empty()
+    }
+}
+
+/// A single line as read from a localization file.
+#[derive(Debug, Clone)]
+pub(crate) struct ImportedLine {
+    pub(crate) line_id: LineId,
+    /// The base language text the translation was made for, if the format stores it.
+    pub(crate) source_text: Option<String>,
+    /// The translated text. Empty if the line was not translated.
+    pub(crate) translation: String,
+    /// Whether the translator marked this translation as needing review.
+    pub(crate) is_flagged_outdated: bool,
+}
+
+/// Computes a short hash of a line's text, which can be used to detect whether a translation was made for an older version of a line.
+/// This is the same value that is stored in the `lock` column of the strings files used by `bevy_yarnspinner`.
+pub fn compute_lock(text: &str) -> String {
+    const MAX_CHARS: usize = 8;
+    let hash = Sha256::digest(text);
+    let hex = format!("{hash:x}");
+    hex.chars().take(MAX_CHARS).collect()
+}
+
+/// Returns the entries of the [`Compilation::string_table`] in a stable order, namely sorted by file name, then line number, then line ID.
+pub(crate) fn sorted_string_infos(compilation: &Compilation) -> Vec<(&LineId, &StringInfo)> {
+    let mut string_infos: Vec<_> = compilation.string_table.iter().collect();
+    string_infos.sort_by(|(lhs_id, lhs), (rhs_id, rhs)| {
+        (&lhs.file_name, lhs.line_number, &lhs_id.0).cmp(&(
+            &rhs.file_name,
+            rhs.line_number,
+            &rhs_id.0,
+        ))
+    });
+    string_infos
+}
+
+/// Groups the output of [`sorted_string_infos`] by file name, keeping the order.
+pub(crate) fn string_infos_by_file(
+    compilation: &Compilation,
+) -> Vec<(&str, Vec<(&LineId, &StringInfo)>)> {
+    let mut files: Vec<(&str, Vec<_>)> = Vec::new();
+    for (line_id, string_info) in sorted_string_infos(compilation) {
+        let file_name = string_info.file_name.as_str();
+        match files.last_mut() {
+            Some((last_file_name, entries)) if *last_file_name == file_name => {
+                entries.push((line_id, string_info))
+            }
+            _ => files.push((file_name, vec![(line_id, string_info)])),
+        }
+    }
+    files
+}
+
+/// Returns the hashtags of a line that are meant for humans, i.e. all tags except for the `#line:` tag.
+pub(crate) fn metadata_tags(string_info: &StringInfo) -> impl Iterator<Item = &str> {
+    string_info
+        .metadata
+        .iter()
+        .map(String::as_str)
+        .filter(|tag| !tag.starts_with("line:"))
+}
+
+/// Finds the indices of all `{0}`-style placeholders that the compiler generates for inline expressions.
+pub(crate) fn placeholder_indices(text: &str) -> BTreeSet<usize> {
+    text.match_indices('{')
+        .filter_map(|(start, _)| parse_placeholder(&text[start..]))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Parses the placeholder `text` starts with, e.g. `{0}` or `{0:N0}` for an expression with a [`FormatSpecifier`].
+/// Returns its index and its length in bytes.
+pub(crate) fn parse_placeholder(text: &str) -> Option<(usize, usize)> {
+    let rest = text.strip_prefix('{')?;
+    let end = rest.find('}')?;
+    let (index, format_specifier) = match rest[..end].split_once(':') {
+        Some((index, format_specifier)) => (index, Some(format_specifier)),
+        None => (&rest[..end], None),
+    };
+    if index.is_empty() || !index.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if format_specifier.is_some_and(|specifier| specifier.parse::<FormatSpecifier>().is_err()) {
+        return None;
+    }
+    Some((index.parse().ok()?, end + 2))
+}
+
+/// Parses the markup of a text with a fresh [`LineParser`]. Placeholders are replaced by their index beforehand,
+/// since the [`Dialogue`](crate::runtime::Dialogue) only parses the markup after substituting them.
+pub(crate) fn parse_markup_with_placeholders(text: &str) -> Result<ParsedMarkup, MarkupParseError> {
+    let mut substituted_text = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        substituted_text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match parse_placeholder(rest) {
+            Some((index, len)) => {
+                substituted_text.push_str(&index.to_string());
+                rest = &rest[len..];
+            }
+            None => {
+                substituted_text.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    substituted_text.push_str(rest);
+    LineParser::new().parse_markup(&substituted_text)
+}
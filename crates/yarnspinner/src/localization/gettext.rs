@@ -0,0 +1,261 @@
+//! Reading and writing [GNU gettext](https://www.gnu.org/software/gettext/manual/html_node/PO-Files.html) PO and POT files,
+//! as used by tools such as Poedit and Weblate.
+//!
+//! Every line becomes one entry whose `msgctxt` is the line's [`LineId`], so that lines with the same text can be translated independently.
+//! The source location of a line is exported as a `#:` reference and its hashtags as `#.` extracted comments.
+
+use crate::compiler::Compilation;
+use crate::core::LineId;
+use crate::localization::{metadata_tags, sorted_string_infos, ImportedLine, TranslationImport};
+use std::error::Error;
+use std::fmt::{self, Display, Write};
+
+/// Exports the [`Compilation::string_table`] as a POT file, i.e. a PO template without translations.
+///
+/// Entries are sorted by file name and line number.
+pub fn export_pot(compilation: &Compilation) -> String {
+    let mut pot = String::new();
+    // Writing into a `String` never fails
+    write_pot(&mut pot, compilation).unwrap();
+    pot
+}
+
+fn write_pot(pot: &mut String, compilation: &Compilation) -> fmt::Result {
+    writeln!(pot, r#"msgid """#)?;
+    writeln!(pot, r#"msgstr """#)?;
+    writeln!(pot, r#""MIME-Version: 1.0\n""#)?;
+    writeln!(pot, r#""Content-Type: text/plain; charset=UTF-8\n""#)?;
+    writeln!(pot, r#""Content-Transfer-Encoding: 8bit\n""#)?;
+    for (line_id, string_info) in sorted_string_infos(compilation) {
+        writeln!(pot)?;
+        for tag in metadata_tags(string_info) {
+            writeln!(pot, "#. #{tag}")?;
+        }
+        writeln!(
+            pot,
+            "#: {}:{}",
+            string_info.file_name, string_info.line_number
+        )?;
+        writeln!(pot, "msgctxt {}", quote(&line_id.0))?;
+        writeln!(pot, "msgid {}", quote(&string_info.text))?;
+        writeln!(pot, r#"msgstr """#)?;
+    }
+    Ok(())
+}
+
+/// Reads the translations out of a PO file and validates them against the [`Compilation`] they are meant for.
+///
+/// Entries are matched to lines by their `msgctxt`, which must hold the [`LineId`]. Entries with an empty `msgstr`
+/// are treated as untranslated and reported in [`TranslationImport::missing_line_ids`]. Obsolete entries (`#~`) are ignored.
+///
+/// Entries with the `fuzzy` flag, as well as entries whose `msgid` no longer matches the current text of the line,
+/// are reported in [`TranslationImport::outdated_line_ids`]. This corresponds to a mismatching `lock` in a strings file.
+///
+/// Pass the resulting [`TranslationImport::string_table`] to [`StringTableTextProvider::extend_translation`].
+///
+/// [`StringTableTextProvider::extend_translation`]: crate::runtime::StringTableTextProvider::extend_translation
+pub fn import_po(po: &str, compilation: &Compilation) -> Result<TranslationImport, PoError> {
+    let mut language = None;
+    let mut lines = Vec::new();
+    let mut entry = PoEntry::default();
+    for (index, line) in po.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("#~") {
+            continue;
+        }
+        let starts_new_entry = line.starts_with('#')
+            || line.starts_with("msgctxt")
+            || (line.starts_with("msgid") && !line.starts_with("msgid_plural"));
+        if starts_new_entry && entry.msgstr.is_some() {
+            finish_entry(std::mem::take(&mut entry), &mut language, &mut lines)?;
+        }
+
+        if let Some(flags) = line.strip_prefix("#,") {
+            entry.is_fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+        } else if line.starts_with('#') {
+            // Other comments are meant for humans
+        } else if let Some(value) = line.strip_prefix("msgctxt") {
+            entry.msgctxt = Some(unquote(value, line_number)?);
+            entry.current_field = PoField::Msgctxt;
+        } else if let Some(value) = line.strip_prefix("msgid_plural") {
+            // Yarn lines have no plural forms, so only the singular is used
+            unquote(value, line_number)?;
+            entry.current_field = PoField::Ignored;
+        } else if let Some(value) = line.strip_prefix("msgid") {
+            entry.msgid = Some(unquote(value, line_number)?);
+            entry.line_number = line_number;
+            entry.current_field = PoField::Msgid;
+        } else if let Some(value) = line.strip_prefix("msgstr[0]") {
+            entry.msgstr = Some(unquote(value, line_number)?);
+            entry.current_field = PoField::Msgstr;
+        } else if line.starts_with("msgstr[") {
+            entry.current_field = PoField::Ignored;
+        } else if let Some(value) = line.strip_prefix("msgstr") {
+            entry.msgstr = Some(unquote(value, line_number)?);
+            entry.current_field = PoField::Msgstr;
+        } else if line.starts_with('"') {
+            let continuation = unquote(line, line_number)?;
+            let field = match entry.current_field {
+                PoField::Msgctxt => entry.msgctxt.as_mut(),
+                PoField::Msgid => entry.msgid.as_mut(),
+                PoField::Msgstr => entry.msgstr.as_mut(),
+                PoField::Ignored => continue,
+                PoField::None => None,
+            };
+            let Some(field) = field else {
+                return Err(PoError::UnexpectedLine {
+                    line: line_number,
+                    content: line.to_owned(),
+                });
+            };
+            field.push_str(&continuation);
+        } else {
+            return Err(PoError::UnexpectedLine {
+                line: line_number,
+                content: line.to_owned(),
+            });
+        }
+    }
+    if entry.msgid.is_some() {
+        finish_entry(entry, &mut language, &mut lines)?;
+    }
+
+    Ok(TranslationImport::new(language, lines, compilation))
+}
+
+fn finish_entry(
+    entry: PoEntry,
+    language: &mut Option<String>,
+    lines: &mut Vec<ImportedLine>,
+) -> Result<(), PoError> {
+    let msgid = entry.msgid.unwrap_or_default();
+    let msgstr = entry.msgstr.unwrap_or_default();
+    let Some(msgctxt) = entry.msgctxt else {
+        if msgid.is_empty() {
+            // This is the header
+            *language = msgstr
+                .lines()
+                .find_map(|header| header.strip_prefix("Language:"))
+                .map(|language| language.trim().to_owned())
+                .filter(|language| !language.is_empty());
+            return Ok(());
+        }
+        return Err(PoError::MissingContext {
+            line: entry.line_number,
+            msgid,
+        });
+    };
+    lines.push(ImportedLine {
+        line_id: LineId(msgctxt),
+        source_text: Some(msgid),
+        translation: msgstr,
+        is_flagged_outdated: entry.is_fuzzy,
+    });
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct PoEntry {
+    msgctxt: Option<String>,
+    msgid: Option<String>,
+    msgstr: Option<String>,
+    is_fuzzy: bool,
+    line_number: usize,
+    current_field: PoField,
+}
+
+/// The field that a continuation line (a line consisting of only a string) is appended to.
+#[derive(Debug, Default, Clone, Copy)]
+enum PoField {
+    #[default]
+    None,
+    Msgctxt,
+    Msgid,
+    Msgstr,
+    Ignored,
+}
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str(r#"\""#),
+            '\\' => quoted.push_str(r"\\"),
+            '\n' => quoted.push_str(r"\n"),
+            '\r' => quoted.push_str(r"\r"),
+            '\t' => quoted.push_str(r"\t"),
+            _ => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn unquote(value: &str, line: usize) -> Result<String, PoError> {
+    let value = value.trim();
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or(PoError::InvalidString { line })?;
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut characters = inner.chars();
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unquoted.push(character);
+            continue;
+        }
+        match characters.next() {
+            Some('n') => unquoted.push('\n'),
+            Some('r') => unquoted.push('\r'),
+            Some('t') => unquoted.push('\t'),
+            Some(escaped @ ('"' | '\\')) => unquoted.push(escaped),
+            _ => return Err(PoError::InvalidString { line }),
+        }
+    }
+    Ok(unquoted)
+}
+
+/// An error that occurred while reading a PO file with [`import_po`]. Line numbers are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoError {
+    /// A line that is neither a comment, a keyword nor a string.
+    UnexpectedLine {
+        /// The number of the line.
+        line: usize,
+        /// The text of the line.
+        content: String,
+    },
+    /// A string that is not properly quoted or contains an unknown escape sequence.
+    InvalidString {
+        /// The number of the line the string is on.
+        line: usize,
+    },
+    /// An entry without a `msgctxt`, so it cannot be associated with a line.
+    MissingContext {
+        /// The number of the line the entry starts on.
+        line: usize,
+        /// The untranslated text of the entry.
+        msgid: String,
+    },
+}
+
+impl Error for PoError {}
+
+impl Display for PoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoError::UnexpectedLine { line, content } => {
+                write!(f, "Unexpected content on line {line} of PO file: {content}")
+            }
+            PoError::InvalidString { line } => {
+                write!(f, "Invalid string on line {line} of PO file")
+            }
+            PoError::MissingContext { line, msgid } => write!(
+                f,
+                "The entry on line {line} of PO file has no msgctxt, so it cannot be matched to a line ID: {msgid}"
+            ),
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Generating a pseudo-localization, i.e. a fake translation that keeps the base language text readable while making
+//! it look foreign enough to spot untranslated, hard-coded strings and UI that breaks when translations are longer than the original.
+
+use crate::compiler::StringInfo;
+use crate::core::LineId;
+use crate::localization::{parse_markup_with_placeholders, parse_placeholder};
+use crate::runtime::{
+    MarkupValue, ParsedMarkup, StringTable, CHARACTER_ATTRIBUTE, CHARACTER_ATTRIBUTE_NAME_PROPERTY,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::iter;
+
+/// A language code that is conventionally used for pseudo-localizations and thus won't clash with a real translation.
+/// Can be passed anywhere an [`Into<Language>`](crate::runtime::Language) is expected.
+pub const PSEUDO_LOCALIZATION_LANGUAGE: &str = "en-XA";
+
+/// Settings for generating a pseudo-localization. The default settings turn `Mae: Hello {0}!` into `Mae: ⟦Ĥéļļö {0}!~~⟧`.
+///
+/// Only the text that ends up being displayed is changed. Interpolation placeholders like `{0}`, markup like `[b]` or
+/// `[plural value={0} one="apple" other="apples"/]` and the character name prefix are left untouched,
+/// so the resulting lines pass [`validate_translation`](crate::localization::validate_translation).
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner::localization::*;
+/// # use yarnspinner::runtime::*;
+/// # use std::collections::HashMap;
+/// # let compilation = yarnspinner::compiler::Compilation::default();
+/// let mut text_provider = StringTableTextProvider::new();
+/// let pseudo_localization = PseudoLocalization::default().with_expansion_percent(50);
+/// text_provider.extend_translation(
+///     PSEUDO_LOCALIZATION_LANGUAGE,
+///     pseudo_localization.localize_string_table(&compilation.string_table),
+/// );
+/// text_provider.set_language(Some(PSEUDO_LOCALIZATION_LANGUAGE.into()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PseudoLocalization {
+    /// Whether to replace letters with accented variants, e.g. `a` with `å`. Defaults to `true`.
+    pub accents: bool,
+    /// By how many percent the displayed text is lengthened with [`PseudoLocalization::padding`].
+    /// Defaults to 30, which is about as much as a translation from English to German adds.
+    pub expansion_percent: usize,
+    /// The character used to lengthen the text. Defaults to `~`.
+    pub padding: char,
+    /// The markers placed around the displayed text, which makes truncated text easy to spot. Defaults to `⟦` and `⟧`.
+    ///
+    /// Note that `[` and `]` start markup in Yarn, so they must be escaped as `\[` and `\]` if you want to use them here.
+    pub brackets: Option<(String, String)>,
+}
+
+impl Default for PseudoLocalization {
+    fn default() -> Self {
+        Self {
+            accents: true,
+            expansion_percent: 30,
+            padding: '~',
+            brackets: Some(("⟦".to_owned(), "⟧".to_owned())),
+        }
+    }
+}
+
+impl PseudoLocalization {
+    /// Creates a new [`PseudoLocalization`] with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets [`PseudoLocalization::accents`].
+    pub fn with_accents(mut self, accents: bool) -> Self {
+        self.accents = accents;
+        self
+    }
+
+    /// Sets [`PseudoLocalization::expansion_percent`].
+    pub fn with_expansion_percent(mut self, expansion_percent: usize) -> Self {
+        self.expansion_percent = expansion_percent;
+        self
+    }
+
+    /// Sets [`PseudoLocalization::padding`].
+    pub fn with_padding(mut self, padding: char) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets [`PseudoLocalization::brackets`].
+    pub fn with_brackets(mut self, open: impl Into<String>, close: impl Into<String>) -> Self {
+        self.brackets = Some((open.into(), close.into()));
+        self
+    }
+
+    /// Removes the [`PseudoLocalization::brackets`].
+    pub fn without_brackets(mut self) -> Self {
+        self.brackets = None;
+        self
+    }
+
+    /// Pseudo-localizes every line of a base language string table, such as [`Compilation::string_table`](crate::compiler::Compilation::string_table).
+    /// The result can be passed to [`StringTableTextProvider::extend_translation`](crate::runtime::StringTableTextProvider::extend_translation).
+    pub fn localize_string_table(&self, string_table: &HashMap<LineId, StringInfo>) -> StringTable {
+        string_table
+            .iter()
+            .map(|(line_id, string_info)| (line_id.clone(), self.localize_line(&string_info.text)))
+            .collect()
+    }
+
+    /// Pseudo-localizes a single line of text.
+    ///
+    /// Text whose markup cannot be parsed is returned unchanged, since there is no telling which parts of it would be displayed.
+    pub fn localize_line(&self, text: &str) -> String {
+        let Ok(parsed_markup) = parse_markup_with_placeholders(text) else {
+            return text.to_owned();
+        };
+        let (character_prefix, mut rest) = split_character_prefix(text, &parsed_markup);
+
+        let mut localized = String::with_capacity(text.len() * 2);
+        localized.push_str(character_prefix);
+        if let Some((open, _)) = &self.brackets {
+            localized.push_str(open);
+        }
+        let mut displayed_character_count = 0;
+        while let Some(character) = rest.chars().next() {
+            let verbatim_len = match character {
+                '\\' if rest[1..].starts_with(['[', ']']) => {
+                    displayed_character_count += 1;
+                    2
+                }
+                '[' => markup_len(rest),
+                '{' => parse_placeholder(rest).map_or(0, |(_, len)| len),
+                _ => 0,
+            };
+            if verbatim_len > 0 {
+                localized.push_str(&rest[..verbatim_len]);
+                rest = &rest[verbatim_len..];
+                continue;
+            }
+            if !character.is_whitespace() {
+                displayed_character_count += 1;
+            }
+            localized.push(if self.accents {
+                accented(character)
+            } else {
+                character
+            });
+            rest = &rest[character.len_utf8()..];
+        }
+        let padding_count = (displayed_character_count * self.expansion_percent).div_ceil(100);
+        localized.extend(iter::repeat_n(self.padding, padding_count));
+        if let Some((_, close)) = &self.brackets {
+            localized.push_str(close);
+        }
+        localized
+    }
+}
+
+/// Splits off the implicit `Name: ` prefix that the [`LineParser`](crate::runtime::LineParser) turns into the `character` attribute.
+fn split_character_prefix<'a>(text: &'a str, parsed_markup: &ParsedMarkup) -> (&'a str, &'a str) {
+    let character_name = parsed_markup
+        .attributes
+        .iter()
+        .find(|attribute| attribute.name == CHARACTER_ATTRIBUTE)
+        .and_then(|attribute| attribute.properties.get(CHARACTER_ATTRIBUTE_NAME_PROPERTY))
+        .map(MarkupValue::to_string);
+    let rest = character_name.and_then(|character_name| {
+        let rest = text
+            .strip_prefix(character_name.as_str())?
+            .strip_prefix(':')?;
+        Some(rest.trim_start())
+    });
+    match rest {
+        Some(rest) => text.split_at(text.len() - rest.len()),
+        None => ("", text),
+    }
+}
+
+/// Returns the length in bytes of the markup tag `text` starts with, including the brackets.
+/// Brackets inside quoted property values don't end the tag.
+fn markup_len(text: &str) -> usize {
+    let mut is_in_quotes = false;
+    let mut is_escaped = false;
+    for (index, character) in text.char_indices().skip(1) {
+        match character {
+            _ if is_escaped => is_escaped = false,
+            '\\' => is_escaped = true,
+            '"' => is_in_quotes = !is_in_quotes,
+            ']' if !is_in_quotes => return index + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+fn accented(character: char) -> char {
+    match character {
+        'A' => 'Å',
+        'B' => 'Ɓ',
+        'C' => 'Ç',
+        'D' => 'Đ',
+        'E' => 'É',
+        'F' => 'Ƒ',
+        'G' => 'Ĝ',
+        'H' => 'Ĥ',
+        'I' => 'Î',
+        'J' => 'Ĵ',
+        'K' => 'Ķ',
+        'L' => 'Ļ',
+        'M' => 'Ṁ',
+        'N' => 'Ń',
+        'O' => 'Ö',
+        'P' => 'Þ',
+        'Q' => 'Ǫ',
+        'R' => 'Ŕ',
+        'S' => 'Š',
+        'T' => 'Ŧ',
+        'U' => 'Û',
+        'V' => 'Ṽ',
+        'W' => 'Ŵ',
+        'X' => 'Ẋ',
+        'Y' => 'Ý',
+        'Z' => 'Ž',
+        'a' => 'å',
+        'b' => 'ƀ',
+        'c' => 'ç',
+        'd' => 'đ',
+        'e' => 'é',
+        'f' => 'ƒ',
+        'g' => 'ĝ',
+        'h' => 'ĥ',
+        'i' => 'î',
+        'j' => 'ĵ',
+        'k' => 'ķ',
+        'l' => 'ļ',
+        'm' => 'ṁ',
+        'n' => 'ń',
+        'o' => 'ö',
+        'p' => 'þ',
+        'q' => 'ǫ',
+        'r' => 'ŕ',
+        's' => 'š',
+        't' => 'ŧ',
+        'u' => 'û',
+        'v' => 'ṽ',
+        'w' => 'ŵ',
+        'x' => 'ẋ',
+        'y' => 'ý',
+        'z' => 'ž',
+        _ => character,
+    }
+}
@@ -0,0 +1,280 @@
+//! Checks translations for mistakes that would otherwise only show up at runtime, such as a dropped `{0}` placeholder or broken markup.
+
+use crate::compiler::Compilation;
+use crate::core::LineId;
+use crate::localization::{
+    parse_markup_with_placeholders, placeholder_indices, sorted_string_infos,
+};
+use crate::runtime::{
+    MarkupAttribute, MarkupValue, StringTable, CHARACTER_ATTRIBUTE,
+    CHARACTER_ATTRIBUTE_NAME_PROPERTY,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+/// The markers that the [`Dialogue`](crate::runtime::Dialogue) replaces with text depending on a value.
+const REPLACEMENT_MARKERS: [&str; 3] = ["select", "plural", "ordinal"];
+
+/// Compares every line of `translation` against its base language text in the [`Compilation::string_table`].
+///
+/// Lines that are missing from `translation` or are not part of the [`Compilation`] are not checked.
+/// Use the report of the import functions, e.g. [`TranslationImport::missing_line_ids`](crate::localization::TranslationImport::missing_line_ids), for these.
+/// The issues in the resulting report are sorted by file name and line number.
+pub fn validate_translation(
+    compilation: &Compilation,
+    translation: &StringTable,
+) -> ValidationReport {
+    let issues = sorted_string_infos(compilation)
+        .into_iter()
+        .filter_map(|(line_id, string_info)| {
+            let translated_text = translation.get(line_id)?;
+            Some((line_id, string_info, translated_text))
+        })
+        .flat_map(|(line_id, string_info, translated_text)| {
+            validate_translated_line(&string_info.text, translated_text)
+                .into_iter()
+                .map(|kind| TranslationIssue {
+                    line_id: line_id.clone(),
+                    file_name: string_info.file_name.clone(),
+                    line_number: string_info.line_number,
+                    kind,
+                })
+        })
+        .collect();
+    ValidationReport { issues }
+}
+
+/// Compares a single translated text against the base language text it was translated from.
+///
+/// The following is checked:
+/// - The same `{0}`, `{1}`, etc. placeholders are used.
+/// - The markup of the translation can be parsed and contains the same attributes.
+/// - All `select`, `plural` and `ordinal` markers are still present. Translations may add new ones,
+///   since other languages may need to distinguish cases the base language doesn't.
+/// - The character name prefix (`Name: `) is kept.
+pub fn validate_translated_line(
+    base_text: &str,
+    translated_text: &str,
+) -> Vec<TranslationIssueKind> {
+    let mut issues = Vec::new();
+
+    let base_placeholders = placeholder_indices(base_text);
+    let translated_placeholders = placeholder_indices(translated_text);
+    issues.extend(
+        base_placeholders
+            .difference(&translated_placeholders)
+            .map(|&index| TranslationIssueKind::MissingPlaceholder { index }),
+    );
+    issues.extend(
+        translated_placeholders
+            .difference(&base_placeholders)
+            .map(|&index| TranslationIssueKind::UnexpectedPlaceholder { index }),
+    );
+
+    // The base text is not our responsibility. If it is broken, the compiler or runtime will complain about it.
+    let Ok(base_attributes) = parse_attributes(base_text) else {
+        return issues;
+    };
+    let translated_attributes = match parse_attributes(translated_text) {
+        Ok(attributes) => attributes,
+        Err(error) => {
+            issues.push(TranslationIssueKind::InvalidMarkup { error });
+            return issues;
+        }
+    };
+
+    let base_character = character_name(&base_attributes);
+    let translated_character = character_name(&translated_attributes);
+    if base_character != translated_character {
+        issues.push(TranslationIssueKind::CharacterNameMismatch {
+            expected: base_character,
+            found: translated_character,
+        });
+    }
+
+    let base_names = attribute_name_counts(&base_attributes);
+    let translated_names = attribute_name_counts(&translated_attributes);
+    for (name, &base_count) in &base_names {
+        let translated_count = translated_names.get(name).copied().unwrap_or_default();
+        if translated_count >= base_count {
+            continue;
+        }
+        let issue = if REPLACEMENT_MARKERS.contains(&name.as_str()) {
+            TranslationIssueKind::MissingReplacementMarker { name: name.clone() }
+        } else {
+            TranslationIssueKind::MissingAttribute { name: name.clone() }
+        };
+        issues.push(issue);
+    }
+    for (name, &translated_count) in &translated_names {
+        let base_count = base_names.get(name).copied().unwrap_or_default();
+        if translated_count > base_count && !REPLACEMENT_MARKERS.contains(&name.as_str()) {
+            issues.push(TranslationIssueKind::UnexpectedAttribute { name: name.clone() });
+        }
+    }
+
+    issues
+}
+
+/// Parses the markup of a text the same way the [`Dialogue`](crate::runtime::Dialogue) would after substituting the placeholders.
+fn parse_attributes(text: &str) -> Result<Vec<MarkupAttribute>, String> {
+    parse_markup_with_placeholders(text)
+        .map(|parsed_markup| parsed_markup.attributes)
+        .map_err(|error| error.to_string())
+}
+
+fn character_name(attributes: &[MarkupAttribute]) -> Option<String> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name == CHARACTER_ATTRIBUTE)
+        .and_then(|attribute| attribute.properties.get(CHARACTER_ATTRIBUTE_NAME_PROPERTY))
+        .map(MarkupValue::to_string)
+}
+
+fn attribute_name_counts(attributes: &[MarkupAttribute]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for attribute in attributes {
+        if attribute.name != CHARACTER_ATTRIBUTE {
+            *counts.entry(attribute.name.clone()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// The result of [`validate_translation`].
+///
+/// Its [`Display`] implementation prints one issue per line, which is suited for CI logs.
+/// With the `serde` feature, it can also be serialized for further processing.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValidationReport {
+    /// All issues found, sorted by file name and line number.
+    pub issues: Vec<TranslationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no issues were found.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A problem found in the translation of a single line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TranslationIssue {
+    /// The ID of the line whose translation has the issue.
+    pub line_id: LineId,
+    /// The file the line is defined in.
+    pub file_name: String,
+    /// The line number at which the line is defined in the file.
+    pub line_number: usize,
+    /// What is wrong with the translation.
+    pub kind: TranslationIssueKind,
+}
+
+impl Display for TranslationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} ({}): {}",
+            self.file_name, self.line_number, self.line_id, self.kind
+        )
+    }
+}
+
+/// The kinds of problems [`validate_translated_line`] can find.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TranslationIssueKind {
+    /// The base text contains the placeholder `{index}`, but the translation doesn't.
+    MissingPlaceholder {
+        /// The index of the placeholder.
+        index: usize,
+    },
+    /// The translation contains the placeholder `{index}`, but the base text doesn't.
+    UnexpectedPlaceholder {
+        /// The index of the placeholder.
+        index: usize,
+    },
+    /// The markup of the translation cannot be parsed, e.g. because a close marker has no matching open marker.
+    InvalidMarkup {
+        /// The description of the parse error.
+        error: String,
+    },
+    /// The base text contains a markup attribute with this name that is missing or was not closed in the translation.
+    MissingAttribute {
+        /// The name of the attribute.
+        name: String,
+    },
+    /// The translation contains a markup attribute with this name that the base text doesn't.
+    UnexpectedAttribute {
+        /// The name of the attribute.
+        name: String,
+    },
+    /// The base text contains a `select`, `plural` or `ordinal` marker that the translation doesn't.
+    MissingReplacementMarker {
+        /// The name of the marker.
+        name: String,
+    },
+    /// The character name prefix of the translation differs from the one in the base text.
+    CharacterNameMismatch {
+        /// The character name of the base text, if it has one.
+        expected: Option<String>,
+        /// The character name of the translation, if it has one.
+        found: Option<String>,
+    },
+}
+
+impl Display for TranslationIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslationIssueKind::MissingPlaceholder { index } => {
+                write!(f, "Placeholder {{{index}}} is missing")
+            }
+            TranslationIssueKind::UnexpectedPlaceholder { index } => {
+                write!(f, "Placeholder {{{index}}} does not exist in the base text")
+            }
+            TranslationIssueKind::InvalidMarkup { error } => write!(f, "Invalid markup: {error}"),
+            TranslationIssueKind::MissingAttribute { name } => {
+                write!(f, "Markup attribute [{name}] is missing or not closed")
+            }
+            TranslationIssueKind::UnexpectedAttribute { name } => {
+                write!(
+                    f,
+                    "Markup attribute [{name}] does not exist in the base text"
+                )
+            }
+            TranslationIssueKind::MissingReplacementMarker { name } => {
+                write!(f, "Marker [{name}] is missing")
+            }
+            TranslationIssueKind::CharacterNameMismatch { expected, found } => {
+                match (expected, found) {
+                    (Some(expected), Some(found)) => {
+                        write!(
+                            f,
+                            "Expected character name \"{expected}\", found \"{found}\""
+                        )
+                    }
+                    (Some(expected), None) => write!(f, "Character name \"{expected}\" is missing"),
+                    (None, Some(found)) => write!(
+                        f,
+                        "Found character name \"{found}\", but the base text has none"
+                    ),
+                    (None, None) => write!(f, "Character name mismatch"),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,365 @@
+//! Reading and writing [XLIFF](https://en.wikipedia.org/wiki/XLIFF) files, the XML format most translation tools and vendors work with.
+//!
+//! Both XLIFF 1.2 and XLIFF 2.0 are supported. Every line becomes one translation unit whose ID is the line's [`LineId`].
+//! The node, source location and hashtags of a line are exported as notes so translators have some context to work with.
+
+use crate::compiler::Compilation;
+use crate::core::LineId;
+use crate::localization::{metadata_tags, string_infos_by_file, ImportedLine, TranslationImport};
+use crate::runtime::Language;
+use std::error::Error;
+use std::fmt::{self, Display, Write};
+use xml::escape::{escape_str_attribute, escape_str_pcdata};
+use xml::reader::{EventReader, XmlEvent};
+
+/// The version of the XLIFF standard to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum XliffVersion {
+    /// XLIFF 1.2, which is still the most widely supported version.
+    #[default]
+    V1_2,
+    /// XLIFF 2.0
+    V2_0,
+}
+
+/// Exports the [`Compilation::string_table`] as an XLIFF document.
+///
+/// `source_language` is the language the Yarn files are written in.
+/// If `target_language` is set, it is declared in the document so that translation tools can pick it up.
+/// The resulting document contains no translations, only the source texts.
+///
+/// Lines are grouped by the file they come from and sorted by line number.
+pub fn export_xliff(
+    compilation: &Compilation,
+    version: XliffVersion,
+    source_language: &Language,
+    target_language: Option<&Language>,
+) -> String {
+    let mut xliff = String::new();
+    // Writing into a `String` never fails
+    match version {
+        XliffVersion::V1_2 => {
+            write_xliff_1_2(&mut xliff, compilation, source_language, target_language)
+        }
+        XliffVersion::V2_0 => {
+            write_xliff_2_0(&mut xliff, compilation, source_language, target_language)
+        }
+    }
+    .unwrap();
+    xliff
+}
+
+fn write_xliff_1_2(
+    xliff: &mut String,
+    compilation: &Compilation,
+    source_language: &Language,
+    target_language: Option<&Language>,
+) -> fmt::Result {
+    writeln!(xliff, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        xliff,
+        r#"<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">"#
+    )?;
+    let target_language = target_language
+        .map(|language| {
+            format!(
+                r#" target-language="{}""#,
+                escape_str_attribute(&language.to_string())
+            )
+        })
+        .unwrap_or_default();
+    for (file_name, string_infos) in string_infos_by_file(compilation) {
+        writeln!(
+            xliff,
+            r#"  <file original="{}" source-language="{}"{target_language} datatype="plaintext">"#,
+            escape_str_attribute(file_name),
+            escape_str_attribute(&source_language.to_string()),
+        )?;
+        writeln!(xliff, "    <body>")?;
+        for (line_id, string_info) in string_infos {
+            writeln!(
+                xliff,
+                r#"      <trans-unit id="{}" resname="{}">"#,
+                escape_str_attribute(&line_id.0),
+                escape_str_attribute(&line_id.0),
+            )?;
+            writeln!(
+                xliff,
+                r#"        <source xml:space="preserve">{}</source>"#,
+                escape_str_pcdata(&string_info.text)
+            )?;
+            writeln!(xliff, r#"        <context-group purpose="location">"#)?;
+            writeln!(
+                xliff,
+                r#"          <context context-type="sourcefile">{}</context>"#,
+                escape_str_pcdata(&string_info.file_name)
+            )?;
+            writeln!(
+                xliff,
+                r#"          <context context-type="linenumber">{}</context>"#,
+                string_info.line_number
+            )?;
+            writeln!(xliff, "        </context-group>")?;
+            writeln!(
+                xliff,
+                r#"        <note from="node">{}</note>"#,
+                escape_str_pcdata(&string_info.node_name)
+            )?;
+            for tag in metadata_tags(string_info) {
+                writeln!(
+                    xliff,
+                    r#"        <note from="tag">#{}</note>"#,
+                    escape_str_pcdata(tag)
+                )?;
+            }
+            writeln!(xliff, "      </trans-unit>")?;
+        }
+        writeln!(xliff, "    </body>")?;
+        writeln!(xliff, "  </file>")?;
+    }
+    writeln!(xliff, "</xliff>")
+}
+
+fn write_xliff_2_0(
+    xliff: &mut String,
+    compilation: &Compilation,
+    source_language: &Language,
+    target_language: Option<&Language>,
+) -> fmt::Result {
+    writeln!(xliff, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    let target_language = target_language
+        .map(|language| {
+            format!(
+                r#" trgLang="{}""#,
+                escape_str_attribute(&language.to_string())
+            )
+        })
+        .unwrap_or_default();
+    writeln!(
+        xliff,
+        r#"<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="{}"{target_language}>"#,
+        escape_str_attribute(&source_language.to_string()),
+    )?;
+    for (index, (file_name, string_infos)) in
+        string_infos_by_file(compilation).into_iter().enumerate()
+    {
+        writeln!(
+            xliff,
+            r#"  <file id="f{}" original="{}">"#,
+            index + 1,
+            escape_str_attribute(file_name),
+        )?;
+        for (line_id, string_info) in string_infos {
+            writeln!(
+                xliff,
+                r#"    <unit id="{}" name="{}">"#,
+                escape_str_attribute(&line_id.0),
+                escape_str_attribute(&line_id.0),
+            )?;
+            writeln!(xliff, "      <notes>")?;
+            writeln!(
+                xliff,
+                r#"        <note category="location">{}:{}</note>"#,
+                escape_str_pcdata(&string_info.file_name),
+                string_info.line_number
+            )?;
+            writeln!(
+                xliff,
+                r#"        <note category="node">{}</note>"#,
+                escape_str_pcdata(&string_info.node_name)
+            )?;
+            for tag in metadata_tags(string_info) {
+                writeln!(
+                    xliff,
+                    r#"        <note category="tag">#{}</note>"#,
+                    escape_str_pcdata(tag)
+                )?;
+            }
+            writeln!(xliff, "      </notes>")?;
+            writeln!(xliff, r#"      <segment>"#)?;
+            writeln!(
+                xliff,
+                r#"        <source xml:space="preserve">{}</source>"#,
+                escape_str_pcdata(&string_info.text)
+            )?;
+            writeln!(xliff, "      </segment>")?;
+            writeln!(xliff, "    </unit>")?;
+        }
+        writeln!(xliff, "  </file>")?;
+    }
+    writeln!(xliff, "</xliff>")
+}
+
+/// Reads the translations out of an XLIFF 1.2 or 2.0 document and validates them against the [`Compilation`] they are meant for.
+///
+/// The version is detected from the document itself. Translation units without a target or with an empty target are
+/// treated as untranslated and reported in [`TranslationImport::missing_line_ids`].
+/// Translations whose source text differs from the current text of the line, whose XLIFF 1.2 target `state` is
+/// `needs-review-translation` or `needs-update`, or whose XLIFF 2.0 segment `state` is `initial`,
+/// are reported in [`TranslationImport::outdated_line_ids`].
+/// Pass the resulting [`TranslationImport::string_table`] to [`StringTableTextProvider::extend_translation`].
+///
+/// [`StringTableTextProvider::extend_translation`]: crate::runtime::StringTableTextProvider::extend_translation
+pub fn import_xliff(
+    xliff: &str,
+    compilation: &Compilation,
+) -> Result<TranslationImport, XliffError> {
+    let mut version = None;
+    let mut language = None;
+    let mut lines = Vec::new();
+    let mut current_unit: Option<ImportedLine> = None;
+    let mut current_text: Option<UnitText> = None;
+    // XLIFF 1.2 allows alternative translations, which we ignore
+    let mut is_in_alt_trans = false;
+
+    for event in EventReader::from_str(xliff) {
+        match event? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                let attribute = |attribute_name: &str| {
+                    attributes
+                        .iter()
+                        .find(|attribute| attribute.name.local_name == attribute_name)
+                        .map(|attribute| attribute.value.clone())
+                };
+                match (version, name.local_name.as_str()) {
+                    (None, "xliff") => {
+                        let version_string = attribute("version").unwrap_or_default();
+                        version = Some(match version_string.as_str() {
+                            "1.2" => XliffVersion::V1_2,
+                            "2.0" | "2.1" => XliffVersion::V2_0,
+                            _ => return Err(XliffError::UnsupportedVersion(version_string)),
+                        });
+                        language = attribute("trgLang");
+                    }
+                    (None, element) => return Err(XliffError::NotXliff(Some(element.to_owned()))),
+                    (Some(XliffVersion::V1_2), "file") if language.is_none() => {
+                        language = attribute("target-language");
+                    }
+                    (Some(XliffVersion::V1_2), "alt-trans") => is_in_alt_trans = true,
+                    (Some(XliffVersion::V1_2), unit @ "trans-unit")
+                    | (Some(XliffVersion::V2_0), unit @ "unit") => {
+                        let id = attribute("id")
+                            .ok_or_else(|| XliffError::MissingId(unit.to_owned()))?;
+                        current_unit = Some(ImportedLine {
+                            line_id: LineId(id),
+                            source_text: None,
+                            translation: String::new(),
+                            is_flagged_outdated: false,
+                        });
+                    }
+                    (Some(_), "source") if !is_in_alt_trans && current_unit.is_some() => {
+                        current_text = Some(UnitText::Source);
+                    }
+                    (Some(_), "target") if !is_in_alt_trans => {
+                        if let Some(unit) = current_unit.as_mut() {
+                            current_text = Some(UnitText::Target);
+                            unit.is_flagged_outdated |= attribute("state").is_some_and(|state| {
+                                OUTDATED_TARGET_STATES.contains(&state.as_str())
+                            });
+                        }
+                    }
+                    (Some(XliffVersion::V2_0), "segment") => {
+                        if let Some(unit) = current_unit.as_mut() {
+                            unit.is_flagged_outdated |= attribute("state").is_some_and(|state| {
+                                OUTDATED_SEGMENT_STATES.contains(&state.as_str())
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            XmlEvent::Characters(text) | XmlEvent::Whitespace(text) | XmlEvent::CData(text) => {
+                let Some(unit) = current_unit.as_mut() else {
+                    continue;
+                };
+                // XLIFF 2.0 units may be split into multiple segments, so we always append
+                match current_text {
+                    Some(UnitText::Source) => unit
+                        .source_text
+                        .get_or_insert_with(String::new)
+                        .push_str(&text),
+                    Some(UnitText::Target) => unit.translation.push_str(&text),
+                    None => {}
+                }
+            }
+            XmlEvent::EndElement { name } => match name.local_name.as_str() {
+                "source" | "target" => current_text = None,
+                "alt-trans" => is_in_alt_trans = false,
+                "trans-unit" | "unit" => lines.extend(current_unit.take()),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    if version.is_none() {
+        return Err(XliffError::NotXliff(None));
+    }
+
+    Ok(TranslationImport::new(language, lines, compilation))
+}
+
+/// Target states of XLIFF 1.2 which signal that the translation needs to be looked at again.
+const OUTDATED_TARGET_STATES: &[&str] = &["needs-review-translation", "needs-update"];
+
+/// Segment states of XLIFF 2.0 which signal that the translation needs to be looked at again.
+const OUTDATED_SEGMENT_STATES: &[&str] = &["initial"];
+
+#[derive(Debug, Clone, Copy)]
+enum UnitText {
+    Source,
+    Target,
+}
+
+/// An error that occurred while reading an XLIFF document with [`import_xliff`].
+#[derive(Debug)]
+pub enum XliffError {
+    /// The document is not well-formed XML.
+    Xml(xml::reader::Error),
+    /// The root element has a `version` attribute this crate cannot read. Contains the value of the attribute.
+    UnsupportedVersion(String),
+    /// The document does not start with an `<xliff>` element. Contains the name of the root element, if there is one.
+    NotXliff(Option<String>),
+    /// A translation unit without an `id` attribute was found. Contains the name of its element, like `trans-unit` or `unit`.
+    MissingId(String),
+}
+
+impl Error for XliffError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            XliffError::Xml(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Display for XliffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XliffError::Xml(e) => write!(f, "Failed to parse XLIFF document: {e}"),
+            XliffError::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported XLIFF version \"{version}\". Supported versions are 1.2 and 2.0"
+            ),
+            XliffError::NotXliff(Some(element)) => {
+                write!(f, "Expected an <xliff> root element, but found <{element}>")
+            }
+            XliffError::NotXliff(None) => {
+                write!(
+                    f,
+                    "Expected an <xliff> root element, but the document is empty"
+                )
+            }
+            XliffError::MissingId(element) => {
+                write!(f, "Found a <{element}> element without an \"id\" attribute")
+            }
+        }
+    }
+}
+
+impl From<xml::reader::Error> for XliffError {
+    fn from(e: xml::reader::Error) -> Self {
+        XliffError::Xml(e)
+    }
+}
@@ -0,0 +1,463 @@
+//! Recording play sessions and replaying them headlessly, e.g. to reproduce a bug report.
+//!
+//! A [`SessionRecorder`] drives a [`Dialogue`] and logs everything that goes into and comes out of it as a [`SessionRecording`]:
+//! the start node, the initial variables and seen lines, the batches of events returned by [`Dialogue::continue_`], the selected options
+//! and the variables set by the game, in the order they happened. With the `serde` feature, the recording can be stored as JSON
+//! and attached to a bug report. The format is versioned by [`SessionRecording::format_version`].
+//!
+//! [`replay`] feeds the same inputs into a fresh [`Dialogue`] running the same [`Program`](crate::core::Program) and compares
+//! the events it returns against the recorded ones. The first difference is reported as a [`Divergence`],
+//! e.g. because the script was changed since the session was recorded.
+
+use crate::core::{LineId, YarnValue};
+use crate::runtime::{Dialogue, DialogueEvent, OptionId, VariableStorageError};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::time::Duration;
+use web_time::Instant;
+
+/// The current version of the [`SessionRecording`] format. Bumped whenever the format changes in an incompatible way.
+pub const SESSION_RECORDING_FORMAT_VERSION: u32 = 1;
+
+/// Records a session of a [`Dialogue`] by driving it via [`SessionRecorder::continue_`] and [`SessionRecorder::set_selected_option`].
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner::prelude::*;
+/// # use yarnspinner::replay::*;
+/// # use yarnspinner::runtime::*;
+/// # let file = YarnFile {
+/// #     file_name: "example.yarn".to_string(),
+/// #     source: "title: Start\n---\nHello!\n-> Hi\n-> Bye\n    See you!\n===".to_string(),
+/// # };
+/// # let compilation = YarnCompiler::new().add_file(file).compile().unwrap();
+/// # let create_dialogue = || {
+/// #     let mut text_provider = StringTableTextProvider::new();
+/// #     text_provider.extend_base_language(
+/// #         compilation.string_table.iter().map(|(id, info)| (id.clone(), info.text.clone())).collect(),
+/// #     );
+/// #     let mut dialogue = Dialogue::new(Box::new(MemoryVariableStorage::new()), Box::new(text_provider));
+/// #     dialogue.add_program(compilation.program.clone().unwrap());
+/// #     dialogue
+/// # };
+/// let mut dialogue = create_dialogue();
+/// let mut recorder = SessionRecorder::start(&mut dialogue, "Start").unwrap();
+/// loop {
+///     let events = recorder.continue_(&mut dialogue).unwrap();
+///     if events.contains(&DialogueEvent::DialogueComplete) {
+///         break;
+///     }
+///     if events.iter().any(|event| matches!(event, DialogueEvent::Options(_))) {
+///         recorder.set_selected_option(&mut dialogue, OptionId(1)).unwrap();
+///     }
+/// }
+/// let recording = recorder.into_recording();
+///
+/// let report = replay(&recording, &mut create_dialogue()).unwrap();
+/// assert!(report.divergence.is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SessionRecorder {
+    recording: SessionRecording,
+    started_at: Instant,
+}
+
+impl SessionRecorder {
+    /// Starts recording a session. Stores the variables currently in the [`Dialogue`]'s [`VariableStorage`](crate::runtime::VariableStorage)
+    /// and its [`Dialogue::seen_lines`] as the initial ones and calls [`Dialogue::set_node`] with the start node.
+    pub fn start(
+        dialogue: &mut Dialogue,
+        start_node: impl Into<String>,
+    ) -> crate::runtime::Result<Self> {
+        let start_node = start_node.into();
+        dialogue.set_node(start_node.clone())?;
+        Ok(Self {
+            recording: SessionRecording {
+                format_version: SESSION_RECORDING_FORMAT_VERSION,
+                start_node,
+                seed: None,
+                initial_variables: dialogue
+                    .variable_storage()
+                    .variables()
+                    .into_iter()
+                    .collect(),
+                initial_seen_lines: dialogue.seen_lines().line_ids(),
+                entries: Vec::new(),
+            },
+            started_at: Instant::now(),
+        })
+    }
+
+    /// Stores the seed of the game's random number generator in the recording, see [`SessionRecording::seed`].
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.recording.seed = Some(seed);
+        self
+    }
+
+    /// The session recorded so far.
+    pub fn recording(&self) -> &SessionRecording {
+        &self.recording
+    }
+
+    /// Consumes the recorder and returns the session recorded so far.
+    pub fn into_recording(self) -> SessionRecording {
+        self.recording
+    }
+
+    /// Calls [`Dialogue::continue_`] and records the returned events, or the error.
+    pub fn continue_(
+        &mut self,
+        dialogue: &mut Dialogue,
+    ) -> crate::runtime::Result<Vec<DialogueEvent>> {
+        let result = dialogue.continue_();
+        let (events, error) = match &result {
+            Ok(events) => (events.clone(), None),
+            Err(error) => (Vec::new(), Some(error.to_string())),
+        };
+        self.record(|elapsed| SessionEntry::Continued {
+            elapsed,
+            events,
+            error,
+        });
+        result
+    }
+
+    /// Calls [`Dialogue::set_selected_option`] and records the selection, along with the error if there was one.
+    pub fn set_selected_option(
+        &mut self,
+        dialogue: &mut Dialogue,
+        option_id: OptionId,
+    ) -> crate::runtime::Result<()> {
+        let result = dialogue.set_selected_option(option_id).map(|_| ());
+        let error = result.as_ref().err().map(ToString::to_string);
+        self.record(|elapsed| SessionEntry::OptionSelected {
+            elapsed,
+            option_id,
+            error,
+        });
+        result
+    }
+
+    /// Sets a variable in the [`Dialogue`]'s [`VariableStorage`](crate::runtime::VariableStorage) and records the change.
+    pub fn set_variable(
+        &mut self,
+        dialogue: &mut Dialogue,
+        name: impl Into<String>,
+        value: impl Into<YarnValue>,
+    ) -> Result<(), VariableStorageError> {
+        let name = name.into();
+        let value = value.into();
+        dialogue
+            .variable_storage_mut()
+            .set(name.clone(), value.clone())?;
+        self.record_variable_set(name, value);
+        Ok(())
+    }
+
+    /// Records that the game set a variable directly in the [`VariableStorage`](crate::runtime::VariableStorage), e.g. in a command handler.
+    pub fn record_variable_set(&mut self, name: impl Into<String>, value: impl Into<YarnValue>) {
+        let name = name.into();
+        let value = value.into();
+        self.record(|elapsed| SessionEntry::VariableSet {
+            elapsed,
+            name,
+            value,
+        });
+    }
+
+    fn record(&mut self, entry: impl FnOnce(Duration) -> SessionEntry) {
+        let elapsed = self.started_at.elapsed();
+        self.recording.entries.push(entry(elapsed));
+    }
+}
+
+/// A session recorded by a [`SessionRecorder`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SessionRecording {
+    /// The version of the format, see [`SESSION_RECORDING_FORMAT_VERSION`].
+    pub format_version: u32,
+    /// The node the session started at.
+    pub start_node: String,
+    /// The seed of the game's random number generator, if it has one.
+    ///
+    /// The [`Dialogue`] itself is deterministic, so this is only needed if the game provides Yarn functions with random results.
+    /// Reseed them with this value before calling [`replay`].
+    pub seed: Option<u64>,
+    /// The variables in the [`VariableStorage`](crate::runtime::VariableStorage) when the session started.
+    pub initial_variables: BTreeMap<String, YarnValue>,
+    /// The IDs of the lines in [`Dialogue::seen_lines`] when the session started, sorted.
+    /// They determine [`Line::is_seen`](crate::runtime::Line::is_seen) and thereby the recorded events.
+    pub initial_seen_lines: Vec<LineId>,
+    /// Everything that happened during the session, in order.
+    pub entries: Vec<SessionEntry>,
+}
+
+impl SessionRecording {
+    /// Serializes the recording as JSON, e.g. to attach it to a bug report.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserializes a recording written by [`SessionRecording::to_json`].
+    /// Recordings of other format versions are loaded as well, but rejected by [`replay`].
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Something that happened during a recorded session.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SessionEntry {
+    /// [`Dialogue::continue_`] was called.
+    Continued {
+        /// The time that had elapsed since the recording started.
+        elapsed: Duration,
+        /// The events that were returned. Empty if the call failed.
+        events: Vec<DialogueEvent>,
+        /// The error that was returned, if any.
+        error: Option<String>,
+    },
+    /// [`Dialogue::set_selected_option`] was called.
+    OptionSelected {
+        /// The time that had elapsed since the recording started.
+        elapsed: Duration,
+        /// The selected option, i.e. its index in the last [`DialogueEvent::Options`].
+        option_id: OptionId,
+        /// The error that was returned, if any.
+        error: Option<String>,
+    },
+    /// The game set a variable.
+    VariableSet {
+        /// The time that had elapsed since the recording started.
+        elapsed: Duration,
+        /// The name of the variable, including the leading `$`.
+        name: String,
+        /// The new value.
+        value: YarnValue,
+    },
+}
+
+impl SessionEntry {
+    /// The time that had elapsed since the recording started.
+    pub fn elapsed(&self) -> Duration {
+        match self {
+            Self::Continued { elapsed, .. }
+            | Self::OptionSelected { elapsed, .. }
+            | Self::VariableSet { elapsed, .. } => *elapsed,
+        }
+    }
+}
+
+/// Replays a recorded session against a [`Dialogue`] and compares the events.
+///
+/// The dialogue must have the [`Program`](crate::core::Program) loaded and should otherwise be fresh, e.g. with an empty [`VariableStorage`](crate::runtime::VariableStorage) and no seen lines.
+/// The recorded initial variables and seen lines are set, the start node is selected and all inputs are fed in again in order.
+/// Replaying stops at the first [`Divergence`].
+pub fn replay(
+    recording: &SessionRecording,
+    dialogue: &mut Dialogue,
+) -> Result<ReplayReport, ReplayError> {
+    if recording.format_version != SESSION_RECORDING_FORMAT_VERSION {
+        return Err(ReplayError::UnsupportedFormatVersion {
+            version: recording.format_version,
+        });
+    }
+    let initial_variables = recording
+        .initial_variables
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    dialogue
+        .variable_storage_mut()
+        .extend(initial_variables)
+        .map_err(ReplayError::InitialVariables)?;
+    dialogue
+        .seen_lines_mut()
+        .extend(recording.initial_seen_lines.iter().cloned());
+
+    let mut report = ReplayReport::default();
+    if let Err(error) = dialogue.set_node(recording.start_node.clone()) {
+        report.divergence = Some(Divergence::StartNode {
+            error: error.to_string(),
+        });
+        return Ok(report);
+    }
+
+    for (entry_index, entry) in recording.entries.iter().enumerate() {
+        report.divergence = replay_entry(entry_index, entry, dialogue);
+        if report.divergence.is_some() {
+            break;
+        }
+        report.replayed_entries += 1;
+    }
+    Ok(report)
+}
+
+fn replay_entry(
+    entry_index: usize,
+    entry: &SessionEntry,
+    dialogue: &mut Dialogue,
+) -> Option<Divergence> {
+    match entry {
+        SessionEntry::Continued { events, error, .. } => match dialogue.continue_() {
+            Ok(actual_events) => {
+                if let Some(error) = error {
+                    return Some(Divergence::Error {
+                        entry_index,
+                        expected: Some(error.clone()),
+                        actual: None,
+                    });
+                }
+                let event_count = events.len().max(actual_events.len());
+                (0..event_count)
+                    .find(|&i| events.get(i) != actual_events.get(i))
+                    .map(|event_index| Divergence::Event {
+                        entry_index,
+                        event_index,
+                        expected: events.get(event_index).cloned(),
+                        actual: actual_events.get(event_index).cloned(),
+                    })
+            }
+            Err(actual_error) => {
+                let actual_error = actual_error.to_string();
+                (error.as_ref() != Some(&actual_error)).then(|| Divergence::Error {
+                    entry_index,
+                    expected: error.clone(),
+                    actual: Some(actual_error),
+                })
+            }
+        },
+        SessionEntry::OptionSelected {
+            option_id, error, ..
+        } => {
+            let actual_error = dialogue
+                .set_selected_option(*option_id)
+                .err()
+                .map(|error| error.to_string());
+            (error != &actual_error).then(|| Divergence::Error {
+                entry_index,
+                expected: error.clone(),
+                actual: actual_error,
+            })
+        }
+        SessionEntry::VariableSet { name, value, .. } => dialogue
+            .variable_storage_mut()
+            .set(name.clone(), value.clone())
+            .err()
+            .map(|error| Divergence::Error {
+                entry_index,
+                expected: None,
+                actual: Some(error.to_string()),
+            }),
+    }
+}
+
+/// The result of [`replay`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReplayReport {
+    /// How many entries of the [`SessionRecording`] were replayed without a divergence.
+    pub replayed_entries: usize,
+    /// The first difference between the recording and the replay, if any.
+    pub divergence: Option<Divergence>,
+}
+
+/// A difference between a [`SessionRecording`] and its replay.
+/// Entries and events are identified by their index in [`SessionRecording::entries`] and [`SessionEntry::Continued`] respectively.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Divergence {
+    /// The start node could not be selected, e.g. because it was removed.
+    StartNode {
+        /// The error returned when selecting the start node.
+        error: String,
+    },
+    /// The dialogue returned a different event than recorded.
+    Event {
+        /// The index of the entry whose events differ.
+        entry_index: usize,
+        /// The index of the first differing event.
+        event_index: usize,
+        /// The recorded event, or [`None`] if the replay returned more events.
+        expected: Option<DialogueEvent>,
+        /// The event of the replay, or [`None`] if it returned fewer events.
+        actual: Option<DialogueEvent>,
+    },
+    /// The dialogue returned a different error than recorded, or returned one where none was recorded, or the other way around.
+    Error {
+        /// The index of the entry whose errors differ.
+        entry_index: usize,
+        /// The recorded error, if any.
+        expected: Option<String>,
+        /// The error of the replay, if any.
+        actual: Option<String>,
+    },
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Divergence::StartNode { error } => {
+                write!(f, "Failed to select the start node: {error}")
+            }
+            Divergence::Event {
+                entry_index,
+                event_index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Entry {entry_index}, event {event_index}: expected {expected:?}, but got {actual:?}"
+            ),
+            Divergence::Error {
+                entry_index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Entry {entry_index}: expected error {expected:?}, but got {actual:?}"
+            ),
+        }
+    }
+}
+
+/// An error that prevented [`replay`] from starting.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The recording was made with a format version this version of Yarn Spinner cannot replay.
+    UnsupportedFormatVersion {
+        /// The [`SessionRecording::format_version`] of the recording.
+        version: u32,
+    },
+    /// The recorded initial variables could not be set.
+    InitialVariables(VariableStorageError),
+}
+
+impl Error for ReplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReplayError::InitialVariables(error) => Some(error),
+            ReplayError::UnsupportedFormatVersion { .. } => None,
+        }
+    }
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::UnsupportedFormatVersion { version } => write!(
+                f,
+                "Cannot replay a session recording of format version {version}, only version {SESSION_RECORDING_FORMAT_VERSION} is supported"
+            ),
+            ReplayError::InitialVariables(error) => {
+                write!(f, "Failed to set the initial variables: {error}")
+            }
+        }
+    }
+}
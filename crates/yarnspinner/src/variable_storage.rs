@@ -0,0 +1,200 @@
+//! [`VariableStorage`] implementations beyond the [`MemoryVariableStorage`].
+//!
+//! A [`TypedVariableStorage`] knows the variables declared in a Yarn project and rejects values of the wrong type,
+//! so that a game setting `$gold` to a string fails right away instead of breaking the script that reads it later.
+//!
+//! With the `serde` feature, the [`FileVariableStorage`] and the [`JournalVariableStorage`] persist variables to disk,
+//! e.g. for save games. They load their variables when opened, so a [`Dialogue`](crate::runtime::Dialogue) created with them
+//! continues where the last session left off. Both write JSON by default, or [RON](https://github.com/ron-rs/ron) with the `ron` feature.
+
+use crate::compiler::{Compilation, Declaration};
+use crate::core::{Type, YarnValue};
+use crate::runtime::{MemoryVariableStorage, VariableStorage, VariableStorageError};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[cfg(feature = "serde")]
+mod file;
+#[cfg(feature = "serde")]
+mod journal;
+#[cfg(feature = "serde")]
+mod persistence;
+
+#[cfg(feature = "serde")]
+pub use self::{file::*, journal::*, persistence::*};
+
+type Result<T> = std::result::Result<T, VariableStorageError>;
+
+/// A [`VariableStorage`] that wraps another one and only accepts values matching the [`Type`] their variable was declared with.
+/// Violations are reported as [`VariableStorageError::TypeMismatch`].
+///
+/// By default, variables that were not declared are rejected with [`VariableStorageError::UndeclaredVariable`].
+/// Use [`TypedVariableStorage::with_undeclared_variables_allowed`] to store them without any checks instead, e.g. for values only the game cares about.
+///
+/// The declarations also provide the default values and descriptions of all variables, which is useful for debug UIs.
+///
+/// ## Example
+///
+/// ```rust
+/// # use yarnspinner::prelude::*;
+/// # use yarnspinner::variable_storage::*;
+/// let file = YarnFile {
+///     file_name: "example.yarn".to_string(),
+///     source: "title: Start\n---\n/// The player's money\n<<declare $gold = 0>>\n===".to_string(),
+/// };
+/// let compilation = YarnCompiler::new().add_file(file).compile().unwrap();
+/// let mut variable_storage = TypedVariableStorage::from_compilation(&compilation);
+///
+/// assert!(variable_storage.set("$gold".to_string(), 10.into()).is_ok());
+/// assert!(variable_storage.set("$gold".to_string(), "lots".into()).is_err());
+/// assert_eq!(Some(&YarnValue::from(0)), variable_storage.default_value("$gold"));
+/// assert_eq!(Some("The player's money"), variable_storage.description("$gold"));
+/// ```
+#[derive(Debug)]
+pub struct TypedVariableStorage {
+    storage: Box<dyn VariableStorage>,
+    declarations: Arc<HashMap<String, Declaration>>,
+    allow_undeclared_variables: bool,
+}
+
+impl TypedVariableStorage {
+    /// Creates a storage that keeps its values in a [`MemoryVariableStorage`] and checks them against the given declarations.
+    /// Declarations of functions are ignored.
+    pub fn new(declarations: impl IntoIterator<Item = Declaration>) -> Self {
+        Self::wrap(Box::new(MemoryVariableStorage::new()), declarations)
+    }
+
+    /// Creates a storage for the variables declared in the [`Compilation::declarations`], keeping its values in a [`MemoryVariableStorage`].
+    pub fn from_compilation(compilation: &Compilation) -> Self {
+        Self::new(compilation.declarations.iter().cloned())
+    }
+
+    /// Creates a storage that keeps its values in the given storage and checks them against the given declarations.
+    /// Values that are already in the wrapped storage are not checked.
+    pub fn wrap(
+        storage: Box<dyn VariableStorage>,
+        declarations: impl IntoIterator<Item = Declaration>,
+    ) -> Self {
+        let declarations = declarations
+            .into_iter()
+            .filter(|declaration| !matches!(declaration.r#type, Type::Function(_)))
+            .map(|declaration| (declaration.name.clone(), declaration))
+            .collect();
+        Self {
+            storage,
+            declarations: Arc::new(declarations),
+            allow_undeclared_variables: false,
+        }
+    }
+
+    /// Sets whether variables that were not declared are stored without any checks instead of being rejected. Defaults to `false`.
+    pub fn with_undeclared_variables_allowed(mut self, allowed: bool) -> Self {
+        self.allow_undeclared_variables = allowed;
+        self
+    }
+
+    /// Returns the declaration of the given variable, if it was declared.
+    pub fn declaration(&self, name: &str) -> Option<&Declaration> {
+        self.declarations.get(name)
+    }
+
+    /// Returns the declarations of all variables, sorted by name.
+    pub fn declarations(&self) -> Vec<&Declaration> {
+        let mut declarations: Vec<_> = self.declarations.values().collect();
+        declarations.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+        declarations
+    }
+
+    /// Returns the value the given variable has before anything is assigned to it, if it was declared.
+    pub fn default_value(&self, name: &str) -> Option<&YarnValue> {
+        self.declaration(name)?.default_value.as_ref()
+    }
+
+    /// Returns the description of the given variable, i.e. the `///` comment above its `<<declare>>`, if there is one.
+    pub fn description(&self, name: &str) -> Option<&str> {
+        self.declaration(name)?.description.as_deref()
+    }
+
+    /// Returns the wrapped storage.
+    pub fn inner(&self) -> &dyn VariableStorage {
+        self.storage.as_ref()
+    }
+
+    fn validate(&self, name: &str, value: &YarnValue) -> Result<()> {
+        let Some(declaration) = self.declarations.get(name) else {
+            return if self.allow_undeclared_variables || !name.starts_with('$') {
+                // Invalid names are reported by the wrapped storage
+                Ok(())
+            } else {
+                Err(VariableStorageError::UndeclaredVariable {
+                    name: name.to_owned(),
+                })
+            };
+        };
+        let actual = value_type(value);
+        match &declaration.r#type {
+            Type::Any => Ok(()),
+            expected if *expected == actual => Ok(()),
+            expected => Err(VariableStorageError::TypeMismatch {
+                name: name.to_owned(),
+                expected: expected.clone(),
+                actual,
+            }),
+        }
+    }
+}
+
+fn value_type(value: &YarnValue) -> Type {
+    match value {
+        YarnValue::Number(_) => Type::Number,
+        YarnValue::String(_) => Type::String,
+        YarnValue::Boolean(_) => Type::Boolean,
+    }
+}
+
+impl VariableStorage for TypedVariableStorage {
+    fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+        Box::new(Self {
+            storage: self.storage.clone_shallow(),
+            declarations: self.declarations.clone(),
+            allow_undeclared_variables: self.allow_undeclared_variables,
+        })
+    }
+
+    fn set(&mut self, name: String, value: YarnValue) -> Result<()> {
+        self.validate(&name, &value)?;
+        self.storage.set(name, value)
+    }
+
+    fn get(&self, name: &str) -> Result<YarnValue> {
+        self.storage.get(name)
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.storage.contains(name)
+    }
+
+    fn extend(&mut self, values: HashMap<String, YarnValue>) -> Result<()> {
+        for (name, value) in &values {
+            self.validate(name, value)?;
+        }
+        VariableStorage::extend(self.storage.as_mut(), values)
+    }
+
+    fn variables(&self) -> HashMap<String, YarnValue> {
+        self.storage.variables()
+    }
+
+    fn clear(&mut self) {
+        self.storage.clear();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
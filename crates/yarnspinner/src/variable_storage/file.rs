@@ -0,0 +1,185 @@
+use crate::core::YarnValue;
+use crate::runtime::{VariableStorage, VariableStorageError};
+use crate::variable_storage::persistence::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+/// A [`VariableStorage`] that keeps all variables in memory and writes them to a JSON or RON file whenever they change.
+///
+/// The file is replaced atomically, so it contains either the old or the new variables even if the game crashes while writing.
+/// Every [`VariableStorage::set`] rewrites the whole file, while [`VariableStorage::extend`] writes all values at once.
+/// For projects with many variables that change often, consider the [`JournalVariableStorage`](crate::variable_storage::JournalVariableStorage) instead.
+///
+/// Clones, including the ones created by [`VariableStorage::clone_shallow`], share the same variables and file.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use yarnspinner::prelude::*;
+/// # use yarnspinner::runtime::StringTableTextProvider;
+/// # use yarnspinner::variable_storage::*;
+/// // Loads the variables of the last session, if there was one
+/// let variable_storage = FileVariableStorage::open("saves/variables.json")?;
+/// let dialogue = Dialogue::new(Box::new(variable_storage), Box::new(StringTableTextProvider::new()));
+/// # Ok::<(), PersistenceError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileVariableStorage(Arc<RwLock<FileState>>);
+
+#[derive(Debug)]
+struct FileState {
+    path: PathBuf,
+    format: FileFormat,
+    options: PersistenceOptions,
+    values: HashMap<String, YarnValue>,
+}
+
+/// The contents of a file written by [`FileVariableStorage`].
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VariablesFile {
+    #[serde(default)]
+    variables: BTreeMap<String, YarnValue>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    internal: BTreeMap<String, YarnValue>,
+}
+
+impl FileVariableStorage {
+    /// Opens the storage at the given path with the default [`PersistenceOptions`] and loads its variables.
+    /// The file is created the first time a variable is set.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, PersistenceError> {
+        Self::open_with_options(path, PersistenceOptions::default())
+    }
+
+    /// Opens the storage at the given path and loads its variables.
+    /// The file is created the first time a variable is set.
+    pub fn open_with_options(
+        path: impl Into<PathBuf>,
+        options: PersistenceOptions,
+    ) -> Result<Self, PersistenceError> {
+        let path = path.into();
+        let format = options.format_for(&path);
+        let mut values = HashMap::new();
+        if let Some(source) = read_if_exists(&path)? {
+            let file: VariablesFile =
+                format
+                    .parse(&source)
+                    .map_err(|message| PersistenceError::Parse {
+                        path: path.clone(),
+                        line: None,
+                        message,
+                    })?;
+            values.extend(file.variables);
+            if options.internal_variables == InternalVariables::Persist {
+                values.extend(file.internal);
+            }
+        }
+        Ok(Self(Arc::new(RwLock::new(FileState {
+            path,
+            format,
+            options,
+            values,
+        }))))
+    }
+
+    /// The path of the file the variables are written to.
+    pub fn path(&self) -> PathBuf {
+        self.0.read().unwrap().path.clone()
+    }
+
+    /// Writes all variables to the file, even if nothing changed since the last write.
+    pub fn save(&self) -> Result<(), PersistenceError> {
+        let state = self.0.read().unwrap();
+        state.write(&state.values)
+    }
+}
+
+impl FileState {
+    fn write(&self, values: &HashMap<String, YarnValue>) -> Result<(), PersistenceError> {
+        let (internal, variables) = persisted_values(&self.options, values)
+            .into_iter()
+            .partition(|(name, _)| is_internal_variable(name));
+        let contents = self
+            .format
+            .to_string_pretty(&VariablesFile {
+                variables,
+                internal,
+            })
+            .map_err(|message| PersistenceError::Serialize { message })?;
+        write_atomically(&self.path, &contents)
+    }
+}
+
+impl VariableStorage for FileVariableStorage {
+    fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, name: String, value: YarnValue) -> Result<(), VariableStorageError> {
+        validate_name(&name)?;
+        let mut state = self.0.write().unwrap();
+        let is_persisted = state.options.is_persisted(&name);
+        let old_value = state.values.insert(name.clone(), value.clone());
+        if !is_persisted || old_value.as_ref() == Some(&value) {
+            return Ok(());
+        }
+        if let Err(error) = state.write(&state.values) {
+            match old_value {
+                Some(old_value) => state.values.insert(name, old_value),
+                None => state.values.remove(&name),
+            };
+            return Err(error.into());
+        }
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<YarnValue, VariableStorageError> {
+        validate_name(name)?;
+        self.0
+            .read()
+            .unwrap()
+            .values
+            .get(name)
+            .cloned()
+            .ok_or_else(|| VariableStorageError::VariableNotFound {
+                name: name.to_owned(),
+            })
+    }
+
+    fn extend(&mut self, values: HashMap<String, YarnValue>) -> Result<(), VariableStorageError> {
+        for name in values.keys() {
+            validate_name(name)?;
+        }
+        let mut state = self.0.write().unwrap();
+        let mut new_values = state.values.clone();
+        new_values.extend(values);
+        state.write(&new_values)?;
+        state.values = new_values;
+        Ok(())
+    }
+
+    fn variables(&self) -> HashMap<String, YarnValue> {
+        self.0.read().unwrap().values.clone()
+    }
+
+    fn clear(&mut self) {
+        let mut state = self.0.write().unwrap();
+        state.values.clear();
+        if let Err(error) = state.write(&state.values) {
+            log::error!(
+                "Failed to clear the variables in {}: {error}",
+                state.path.display()
+            );
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -0,0 +1,323 @@
+use crate::core::YarnValue;
+use crate::runtime::{VariableStorage, VariableStorageError};
+use crate::variable_storage::persistence::*;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// The default for [`JournalVariableStorage::with_compaction_threshold`].
+pub const DEFAULT_COMPACTION_THRESHOLD: usize = 1024;
+
+/// A [`VariableStorage`] that keeps all variables in memory and appends every change to a journal file, one change per line.
+///
+/// Appending a line is much cheaper than rewriting a whole file, which makes this a good fit for projects with many variables that change often.
+/// When loading, the changes are replayed in order. A last line that was cut off because the game crashed while writing it is ignored.
+/// Likewise, after a failed write, the journal is rewritten from the values in memory before the next change is appended.
+/// Once the journal has more lines than the [compaction threshold](JournalVariableStorage::with_compaction_threshold),
+/// it is atomically replaced by a single line holding all current values.
+///
+/// [`VariableStorage::extend`] writes all values in a single line, so they are either all persisted or none of them are.
+/// Clones, including the ones created by [`VariableStorage::clone_shallow`], share the same variables and journal.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// # use yarnspinner::prelude::*;
+/// # use yarnspinner::runtime::StringTableTextProvider;
+/// # use yarnspinner::variable_storage::*;
+/// let options = PersistenceOptions::new().with_internal_variables(InternalVariables::InMemory);
+/// let variable_storage = JournalVariableStorage::open_with_options("saves/variables.jsonl", options)?
+///     .with_compaction_threshold(256);
+/// let dialogue = Dialogue::new(Box::new(variable_storage), Box::new(StringTableTextProvider::new()));
+/// # Ok::<(), PersistenceError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct JournalVariableStorage(Arc<RwLock<JournalState>>);
+
+#[derive(Debug)]
+struct JournalState {
+    path: PathBuf,
+    format: FileFormat,
+    options: PersistenceOptions,
+    values: HashMap<String, YarnValue>,
+    file: Option<fs::File>,
+    entry_count: usize,
+    compaction_threshold: usize,
+    /// Set when an append failed, as part of the line may have been written anyway.
+    needs_compaction: bool,
+}
+
+/// A single line of the journal.
+#[derive(Debug, Serialize, Deserialize)]
+enum JournalEntry {
+    Set { name: String, value: YarnValue },
+    Extend { values: BTreeMap<String, YarnValue> },
+}
+
+impl JournalVariableStorage {
+    /// Opens the journal at the given path with the default [`PersistenceOptions`] and replays it.
+    /// The file is created the first time a variable is set.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, PersistenceError> {
+        Self::open_with_options(path, PersistenceOptions::default())
+    }
+
+    /// Opens the journal at the given path and replays it.
+    /// The file is created the first time a variable is set.
+    pub fn open_with_options(
+        path: impl Into<PathBuf>,
+        options: PersistenceOptions,
+    ) -> Result<Self, PersistenceError> {
+        let path = path.into();
+        let format = options.format_for(&path);
+        let mut state = JournalState {
+            path,
+            format,
+            options,
+            values: HashMap::new(),
+            file: None,
+            entry_count: 0,
+            compaction_threshold: DEFAULT_COMPACTION_THRESHOLD,
+            needs_compaction: false,
+        };
+        if let Some(source) = read_if_exists(&state.path)? {
+            let is_cut_off = state.replay(&source)?;
+            if is_cut_off {
+                // Appending to a partial line would corrupt the next entry
+                state.compact()?;
+            }
+        }
+        Ok(Self(Arc::new(RwLock::new(state))))
+    }
+
+    /// Sets after how many lines the journal is compacted. Defaults to [`DEFAULT_COMPACTION_THRESHOLD`].
+    pub fn with_compaction_threshold(self, compaction_threshold: usize) -> Self {
+        self.0.write().unwrap().compaction_threshold = compaction_threshold;
+        self
+    }
+
+    /// The path of the journal file.
+    pub fn path(&self) -> PathBuf {
+        self.0.read().unwrap().path.clone()
+    }
+
+    /// Replaces the journal by a single line holding all current values.
+    pub fn compact(&self) -> Result<(), PersistenceError> {
+        self.0.write().unwrap().compact()
+    }
+}
+
+impl JournalState {
+    /// Applies all entries in the source. Returns whether the last line was cut off.
+    fn replay(&mut self, source: &str) -> Result<bool, PersistenceError> {
+        let lines: Vec<_> = source.lines().collect();
+        for (index, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = match self.format.parse(line) {
+                Ok(entry) => entry,
+                Err(_) if index == lines.len() - 1 && !source.ends_with('\n') => return Ok(true),
+                Err(message) => {
+                    return Err(PersistenceError::Parse {
+                        path: self.path.clone(),
+                        line: Some(index + 1),
+                        message,
+                    })
+                }
+            };
+            self.apply(entry);
+            self.entry_count += 1;
+        }
+        Ok(false)
+    }
+
+    fn apply(&mut self, entry: JournalEntry) {
+        match entry {
+            JournalEntry::Set { name, value } => {
+                if self.options.is_persisted(&name) {
+                    self.values.insert(name, value);
+                }
+            }
+            JournalEntry::Extend { values } => self.values.extend(
+                values
+                    .into_iter()
+                    .filter(|(name, _)| self.options.is_persisted(name)),
+            ),
+        }
+    }
+
+    /// Appends the entry to the journal. Call [`JournalState::compact_if_needed`] once the entry was applied to the values.
+    fn append(&mut self, entry: &JournalEntry) -> Result<(), PersistenceError> {
+        let mut line = self
+            .format
+            .to_line(entry)
+            .map_err(|message| PersistenceError::Serialize { message })?;
+        line.push('\n');
+        if self.needs_compaction {
+            // Appending after a torn line would leave a corrupt line in the middle of the journal,
+            // which can't be told apart from a broken file when loading
+            self.compact()?;
+        }
+        let mut file = match self.file.take() {
+            Some(file) => file,
+            None => open_for_appending(&self.path)
+                .map_err(|error| PersistenceError::io(&self.path, error))?,
+        };
+        if let Err(error) = file.write_all(line.as_bytes()) {
+            self.needs_compaction = true;
+            return Err(PersistenceError::io(&self.path, error));
+        }
+        self.file = Some(file);
+        self.entry_count += 1;
+        Ok(())
+    }
+
+    fn compact_if_needed(&mut self) {
+        if self.entry_count <= self.compaction_threshold {
+            return;
+        }
+        // The change itself is already persisted, so the journal is just longer than it should be
+        if let Err(error) = self.compact() {
+            log::warn!("Failed to compact {}: {error}", self.path.display());
+        }
+    }
+
+    fn compact(&mut self) -> Result<(), PersistenceError> {
+        let values = persisted_values(&self.options, &self.values);
+        let mut contents = String::new();
+        if !values.is_empty() {
+            contents = self
+                .format
+                .to_line(&JournalEntry::Extend { values })
+                .map_err(|message| PersistenceError::Serialize { message })?;
+            contents.push('\n');
+        }
+        // The old handle would keep appending to the replaced file
+        self.file = None;
+        write_atomically(&self.path, &contents)?;
+        self.entry_count = usize::from(!contents.is_empty());
+        self.needs_compaction = false;
+        Ok(())
+    }
+}
+
+fn open_for_appending(path: &Path) -> io::Result<fs::File> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl VariableStorage for JournalVariableStorage {
+    fn clone_shallow(&self) -> Box<dyn VariableStorage> {
+        Box::new(self.clone())
+    }
+
+    fn set(&mut self, name: String, value: YarnValue) -> Result<(), VariableStorageError> {
+        validate_name(&name)?;
+        let mut state = self.0.write().unwrap();
+        if state.options.is_persisted(&name) && state.values.get(&name) != Some(&value) {
+            state.append(&JournalEntry::Set {
+                name: name.clone(),
+                value: value.clone(),
+            })?;
+        }
+        state.values.insert(name, value);
+        state.compact_if_needed();
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<YarnValue, VariableStorageError> {
+        validate_name(name)?;
+        self.0
+            .read()
+            .unwrap()
+            .values
+            .get(name)
+            .cloned()
+            .ok_or_else(|| VariableStorageError::VariableNotFound {
+                name: name.to_owned(),
+            })
+    }
+
+    fn extend(&mut self, values: HashMap<String, YarnValue>) -> Result<(), VariableStorageError> {
+        for name in values.keys() {
+            validate_name(name)?;
+        }
+        let mut state = self.0.write().unwrap();
+        let persisted_values = persisted_values(&state.options, &values);
+        if !persisted_values.is_empty() {
+            state.append(&JournalEntry::Extend {
+                values: persisted_values,
+            })?;
+        }
+        state.values.extend(values);
+        state.compact_if_needed();
+        Ok(())
+    }
+
+    fn variables(&self) -> HashMap<String, YarnValue> {
+        self.0.read().unwrap().values.clone()
+    }
+
+    fn clear(&mut self) {
+        let mut state = self.0.write().unwrap();
+        state.values.clear();
+        if let Err(error) = state.compact() {
+            log::error!(
+                "Failed to clear the variables in {}: {error}",
+                state.path.display()
+            );
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_journal_after_torn_append() {
+        let directory = std::env::temp_dir()
+            .join("yarnspinner_journal_tests")
+            .join("torn_append");
+        let _ = fs::remove_dir_all(&directory);
+        let path = directory.join("variables.jsonl");
+        let mut variable_storage = JournalVariableStorage::open(&path).unwrap();
+        variable_storage.set("$gold".to_owned(), 5.into()).unwrap();
+
+        // Simulate a write that fails after part of the line reached the disk
+        let mut file = open_for_appending(&path).unwrap();
+        file.write_all(br#"{"Set":{"name":"$gold","val"#).unwrap();
+        variable_storage.0.write().unwrap().file = Some(fs::File::open(&path).unwrap());
+        assert!(variable_storage.set("$gold".to_owned(), 10.into()).is_err());
+        variable_storage
+            .set("$met".to_owned(), true.into())
+            .unwrap();
+
+        let reopened_variable_storage = JournalVariableStorage::open(&path).unwrap();
+        assert_eq!(
+            variable_storage.variables(),
+            reopened_variable_storage.variables()
+        );
+        assert_eq!(
+            YarnValue::from(5),
+            reopened_variable_storage.get("$gold").unwrap()
+        );
+    }
+}
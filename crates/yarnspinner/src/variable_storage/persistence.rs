@@ -0,0 +1,249 @@
+use crate::core::YarnValue;
+use crate::runtime::VariableStorageError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The prefix of variables the [`Dialogue`](crate::runtime::Dialogue) uses to track its own state, such as how often a node was visited.
+/// See [`InternalVariables`].
+pub const INTERNAL_VARIABLE_PREFIX: &str = "$Yarn.Internal.";
+
+/// The text format variables are persisted in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub enum FileFormat {
+    /// [JSON](https://www.json.org/).
+    #[default]
+    Json,
+    /// [RON](https://github.com/ron-rs/ron), the Rusty Object Notation.
+    #[cfg(feature = "ron")]
+    Ron,
+}
+
+impl FileFormat {
+    /// Picks the format by the extension of the path: `.ron` files use [`FileFormat::Ron`] if the `ron` feature is enabled,
+    /// everything else uses [`FileFormat::Json`].
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            #[cfg(feature = "ron")]
+            Some("ron") => Self::Ron,
+            _ => Self::Json,
+        }
+    }
+
+    pub(crate) fn to_string_pretty(self, value: &impl Serialize) -> Result<String, String> {
+        match self {
+            Self::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Serializes the value into a single line.
+    pub(crate) fn to_line(self, value: &impl Serialize) -> Result<String, String> {
+        match self {
+            Self::Json => serde_json::to_string(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::to_string(value).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub(crate) fn parse<T: DeserializeOwned>(self, source: &str) -> Result<T, String> {
+        match self {
+            Self::Json => serde_json::from_str(source).map_err(|e| e.to_string()),
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::from_str(source).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// How the variables starting with [`INTERNAL_VARIABLE_PREFIX`] are persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub enum InternalVariables {
+    /// Persist them along with the other variables, but separated from them so that they don't clutter the file.
+    /// Use this for save games, so that `visited` and `visited_count` keep working after loading.
+    #[default]
+    Persist,
+    /// Only keep them in memory. They are neither written nor loaded, so `visited` starts over in every session.
+    InMemory,
+}
+
+/// Options shared by the [`FileVariableStorage`](crate::variable_storage::FileVariableStorage) and the [`JournalVariableStorage`](crate::variable_storage::JournalVariableStorage).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct PersistenceOptions {
+    /// The format of the file. If `None`, it is picked by [`FileFormat::from_path`].
+    pub format: Option<FileFormat>,
+    /// How the variables used by the dialogue to track its own state are persisted.
+    pub internal_variables: InternalVariables,
+}
+
+impl PersistenceOptions {
+    /// Creates the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets [`PersistenceOptions::format`].
+    pub fn with_format(mut self, format: FileFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Sets [`PersistenceOptions::internal_variables`].
+    pub fn with_internal_variables(mut self, internal_variables: InternalVariables) -> Self {
+        self.internal_variables = internal_variables;
+        self
+    }
+
+    pub(crate) fn format_for(&self, path: &Path) -> FileFormat {
+        self.format.unwrap_or_else(|| FileFormat::from_path(path))
+    }
+
+    /// Whether the given variable is written to and read from disk.
+    pub(crate) fn is_persisted(&self, name: &str) -> bool {
+        self.internal_variables == InternalVariables::Persist || !is_internal_variable(name)
+    }
+}
+
+pub(crate) fn is_internal_variable(name: &str) -> bool {
+    name.starts_with(INTERNAL_VARIABLE_PREFIX)
+}
+
+pub(crate) fn validate_name(name: &str) -> Result<(), VariableStorageError> {
+    if name.starts_with('$') {
+        Ok(())
+    } else {
+        Err(VariableStorageError::InvalidVariableName {
+            name: name.to_owned(),
+        })
+    }
+}
+
+/// Reads the file at the given path, returning `None` if it doesn't exist yet.
+pub(crate) fn read_if_exists(path: &Path) -> Result<Option<String>, PersistenceError> {
+    match fs::read_to_string(path) {
+        Ok(source) => Ok(Some(source)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(PersistenceError::io(path, error)),
+    }
+}
+
+/// Replaces the file at the given path so that it either has its old or its new contents, even if the process is killed while writing.
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> Result<(), PersistenceError> {
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(".tmp");
+    let temporary_path = PathBuf::from(temporary_path);
+    let write = || -> io::Result<()> {
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::File::create(&temporary_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temporary_path, path)
+    };
+    write().map_err(|error| PersistenceError::io(path, error))
+}
+
+/// An error that occurred while loading or saving variables.
+#[derive(Debug)]
+pub enum PersistenceError {
+    /// The file could not be read or written.
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+        /// The underlying error.
+        error: io::Error,
+    },
+    /// The file exists, but is not valid.
+    Parse {
+        /// The path of the file.
+        path: PathBuf,
+        /// The 1-based number of the invalid line. Only set for journals, which are parsed line by line.
+        line: Option<usize>,
+        /// What is wrong with the file.
+        message: String,
+    },
+    /// A value could not be serialized, e.g. because it is a number that the format cannot represent.
+    Serialize {
+        /// Why the value could not be serialized.
+        message: String,
+    },
+}
+
+impl PersistenceError {
+    pub(crate) fn io(path: &Path, error: io::Error) -> Self {
+        Self::Io {
+            path: path.to_owned(),
+            error,
+        }
+    }
+}
+
+impl Error for PersistenceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PersistenceError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for PersistenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistenceError::Io { path, error } => {
+                write!(f, "Failed to access {}: {error}", path.display())
+            }
+            PersistenceError::Parse {
+                path,
+                line: Some(line),
+                message,
+            } => write!(
+                f,
+                "Failed to parse line {line} of {}: {message}",
+                path.display()
+            ),
+            PersistenceError::Parse {
+                path,
+                line: None,
+                message,
+            } => write!(f, "Failed to parse {}: {message}", path.display()),
+            PersistenceError::Serialize { message } => {
+                write!(f, "Failed to serialize variables: {message}")
+            }
+        }
+    }
+}
+
+impl From<PersistenceError> for VariableStorageError {
+    fn from(error: PersistenceError) -> Self {
+        VariableStorageError::InternalError {
+            error: Box::new(error),
+        }
+    }
+}
+
+/// Splits the values into the ones that are persisted and sorts them, so that the written files are stable.
+pub(crate) fn persisted_values<'a>(
+    options: &PersistenceOptions,
+    values: impl IntoIterator<Item = (&'a String, &'a YarnValue)>,
+) -> BTreeMap<String, YarnValue> {
+    values
+        .into_iter()
+        .filter(|(name, _)| options.is_persisted(name))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
@@ -0,0 +1,210 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::coverage::*;
+use yarnspinner::runtime::*;
+
+mod test_base;
+
+fn compile_test_file() -> Compilation {
+    let file = File {
+        file_name: "test.yarn".to_string(),
+        source: "title: Start
+---
+Mae: Hello! #line:hello
+-> Go left #line:left
+    Mae: Left it is. #line:went_left
+-> Go right #line:right
+    Mae: Right it is. #line:went_right
+<<jump End>>
+===
+title: End
+---
+Bye! #line:bye
+===
+title: Unused
+---
+Nobody sees me. #line:unused
+==="
+        .to_string(),
+    };
+    Compiler::new().add_file(file).compile().unwrap()
+}
+
+/// Plays through the `Start` node, always picking the option with the given ID.
+fn play(compilation: &Compilation, option_id: usize) -> CoverageData {
+    let mut dialogue = dialogue_from_compilation(compilation, MemoryVariableStorage::new());
+    dialogue.set_node("Start").unwrap();
+
+    let mut recorder = CoverageRecorder::new();
+    loop {
+        let events = recorder.continue_(&mut dialogue).unwrap();
+        for event in events {
+            match event {
+                DialogueEvent::Options(_) => recorder
+                    .set_selected_option(&mut dialogue, OptionId(option_id))
+                    .unwrap(),
+                DialogueEvent::DialogueComplete => return recorder.into_data(),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn records_lines_options_and_nodes() {
+    let compilation = compile_test_file();
+
+    let data = play(&compilation, 0);
+
+    assert_eq!(
+        vec!["line:bye", "line:hello", "line:went_left"],
+        data.lines
+            .keys()
+            .map(|id| id.0.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["line:left", "line:right"],
+        data.offered_options
+            .keys()
+            .map(|id| id.0.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["line:left"],
+        data.selected_options
+            .keys()
+            .map(|id| id.0.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(Some(&1), data.node_visits.get("Start"));
+    assert_eq!(Some(&1), data.node_visits.get("End"));
+    assert_eq!(None, data.node_visits.get("Unused"));
+    assert!(data.is_instruction_executed("Start", 0));
+    assert!(!data.instructions.contains_key("Unused"));
+}
+
+#[test]
+fn reports_coverage_per_file_and_node() {
+    let compilation = compile_test_file();
+
+    let report = CoverageReport::new(&compilation, &play(&compilation, 0));
+
+    assert_eq!(1, report.files.len());
+    let file = &report.files[0];
+    assert_eq!("test.yarn", file.file_name);
+    assert_eq!(
+        vec!["Start", "End", "Unused"],
+        file.nodes
+            .iter()
+            .map(|node| node.node_name.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![
+            LineCoverage {
+                line_id: "line:hello".into(),
+                line_number: 3,
+                hit_count: 1,
+                selected_count: None,
+            },
+            LineCoverage {
+                line_id: "line:left".into(),
+                line_number: 4,
+                hit_count: 1,
+                selected_count: Some(1),
+            },
+            LineCoverage {
+                line_id: "line:went_left".into(),
+                line_number: 5,
+                hit_count: 1,
+                selected_count: None,
+            },
+            LineCoverage {
+                line_id: "line:right".into(),
+                line_number: 6,
+                hit_count: 1,
+                selected_count: Some(0),
+            },
+            LineCoverage {
+                line_id: "line:went_right".into(),
+                line_number: 7,
+                hit_count: 0,
+                selected_count: None,
+            },
+        ],
+        file.nodes[0].lines
+    );
+    assert_eq!(CoverageCount { hit: 5, total: 7 }, report.lines());
+    assert_eq!(CoverageCount { hit: 1, total: 2 }, report.options());
+    assert_eq!(CoverageCount { hit: 2, total: 3 }, report.nodes());
+    let start_instructions = file.nodes[0].instructions;
+    assert!(start_instructions.hit > 0);
+    assert!(start_instructions.hit < start_instructions.total);
+    assert_eq!(0, file.nodes[2].instructions.hit);
+}
+
+#[test]
+fn merges_sessions() {
+    let compilation = compile_test_file();
+    let left = play(&compilation, 0);
+    let right = play(&compilation, 1);
+
+    let mut merged = left.clone();
+    merged.merge(&right);
+
+    assert_eq!(Some(&2), merged.lines.get(&"line:hello".into()));
+    assert_eq!(Some(&1), merged.selected_options.get(&"line:left".into()));
+    assert_eq!(Some(&1), merged.selected_options.get(&"line:right".into()));
+    assert_eq!(Some(&2), merged.node_visits.get("Start"));
+    let report = CoverageReport::new(&compilation, &merged);
+    assert_eq!(CoverageCount { hit: 6, total: 7 }, report.lines());
+    assert_eq!(CoverageCount { hit: 2, total: 2 }, report.options());
+    let executed_in_any_session = |index| {
+        left.is_instruction_executed("Start", index)
+            || right.is_instruction_executed("Start", index)
+    };
+    let start = &compilation.program.as_ref().unwrap().nodes["Start"];
+    for index in 0..start.instructions.len() {
+        assert_eq!(
+            executed_in_any_session(index),
+            merged.is_instruction_executed("Start", index)
+        );
+    }
+    let left_report = CoverageReport::new(&compilation, &left);
+    assert!(
+        report.files[0].nodes[0].instructions.hit > left_report.files[0].nodes[0].instructions.hit
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serializes_data_as_json() {
+    let compilation = compile_test_file();
+    let data = play(&compilation, 0);
+
+    let json = data.to_json().unwrap();
+
+    assert_eq!(data, CoverageData::from_json(&json).unwrap());
+}
+
+#[test]
+fn exports_lcov() {
+    let compilation = compile_test_file();
+    let report = CoverageReport::new(&compilation, &play(&compilation, 0));
+
+    let lcov = report.to_lcov();
+
+    assert!(lcov.starts_with("TN:\nSF:test.yarn\n"));
+    assert!(lcov.contains("FNDA:1,Start\n"));
+    assert!(lcov.contains("FNDA:0,Unused\n"));
+    assert!(lcov.contains("FNF:3\nFNH:2\n"));
+    assert!(lcov.contains("BRDA:4,0,0,1\n"));
+    assert!(lcov.contains("BRDA:6,0,1,0\n"));
+    assert!(lcov.contains("BRF:2\nBRH:1\n"));
+    assert!(lcov.contains("DA:3,1\n"));
+    assert!(lcov.contains("DA:7,0\n"));
+    assert!(lcov.contains("DA:12,1\n"));
+    assert!(lcov.contains("DA:16,0\n"));
+    assert!(lcov.ends_with("end_of_record\n"));
+}
@@ -218,6 +218,83 @@ fn test_line_hints() {
     assert!(line_hints_were_sent);
 }
 
+#[test]
+fn test_variable_changes() {
+    let result = Compiler::from_test_source(
+        "<<declare $gold = 0>>
+        <<declare $met = false>>
+        <<set $gold to 5>>
+        <<set $gold to 5>>
+        <<set $met to true>>",
+    )
+    .compile()
+    .unwrap();
+
+    let variable_changes = run_and_collect_variable_changes(result);
+
+    // Setting a variable to the value it already has is not reported
+    assert_eq!(
+        vec![
+            DialogueEvent::VariableChanged {
+                name: "$gold".to_owned(),
+                old: Some(0.into()),
+                new: 5.into(),
+            },
+            DialogueEvent::VariableChanged {
+                name: "$met".to_owned(),
+                old: Some(false.into()),
+                new: true.into(),
+            },
+        ],
+        variable_changes
+    );
+}
+
+#[test]
+fn test_variable_changes_skip_internal_variables() {
+    let result = Compiler::from_test_source(
+        "<<declare $gold = 0>>
+        <<if visited(\"Start\")>>
+            Welcome back.
+        <<endif>>
+        <<set $gold to 5>>",
+    )
+    .compile()
+    .unwrap();
+
+    let variable_changes = run_and_collect_variable_changes(result);
+
+    // Tracking visits of `Start` sets `$Yarn.Internal.Visiting.Start`, which is not reported
+    assert_eq!(
+        vec![DialogueEvent::VariableChanged {
+            name: "$gold".to_owned(),
+            old: Some(0.into()),
+            new: 5.into(),
+        }],
+        variable_changes
+    );
+}
+
+fn run_and_collect_variable_changes(compilation: Compilation) -> Vec<DialogueEvent> {
+    let mut dialogue = TestBase::new().with_compilation(compilation).dialogue;
+    dialogue.set_node("Start").unwrap();
+
+    let mut variable_changes = Vec::new();
+    loop {
+        let events = dialogue.continue_().unwrap();
+        let is_complete = events.contains(&DialogueEvent::DialogueComplete);
+        variable_changes.extend(
+            events
+                .into_iter()
+                .filter(|event| matches!(event, DialogueEvent::VariableChanged { .. })),
+        );
+        if is_complete {
+            break;
+        }
+    }
+    variable_changes
+}
+
 #[test]
 fn test_function_argument_type_inference() {
     let test_base = TestBase::new().extend_library(|library| {
@@ -322,7 +399,8 @@ fn test_selecting_option_from_inside_option_callback() {
                 DialogueEvent::Command(_)
                 | DialogueEvent::NodeComplete(_)
                 | DialogueEvent::NodeStart(_)
-                | DialogueEvent::LineHints(_) => {}
+                | DialogueEvent::LineHints(_)
+                | DialogueEvent::VariableChanged { .. } => {}
             }
         }
     }
@@ -0,0 +1,225 @@
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+use yarnspinner::exploration::*;
+
+fn compile(source: &str) -> Compilation {
+    let file = File {
+        file_name: "test.yarn".to_string(),
+        source: source.to_string(),
+    };
+    Compiler::new().add_file(file).compile().unwrap()
+}
+
+const GUARD: &str = "title: Start
+---
+<<declare $has_key = false>>
+Guard: Halt! #line:halt
+-> Talk #line:talk
+    <<jump Talk>>
+-> Leave #line:leave
+    <<jump Nowhere>>
+-> Open the door <<if $has_key>> #line:open
+    You open the door. #line:opened
+===
+title: Talk
+---
+Guard: What do you want? #line:want
+-> Nothing <<if false>> #line:nothing
+===
+title: Secret
+---
+Nobody finds this. #line:secret
+===";
+
+#[test]
+fn explores_every_option_breadth_first() {
+    let compilation = compile(GUARD);
+
+    let report = Explorer::new(&compilation).explore();
+
+    assert!(report.is_exhaustive);
+    assert_eq!(2, report.playthrough_count);
+    assert_eq!(0, report.completed_count);
+    assert_eq!(1, report.max_depth());
+    assert_eq!(
+        vec![
+            ExplorationIssueKind::DeadEnd,
+            ExplorationIssueKind::DialogueError {
+                message: "No node named \"Nowhere\" has been loaded.".to_owned()
+            },
+        ],
+        report
+            .issues
+            .iter()
+            .map(|issue| issue.kind.clone())
+            .collect::<Vec<_>>()
+    );
+    let dead_end = &report.issues[0].playthrough;
+    assert_eq!("Start", dead_end.start_node);
+    assert_eq!(vec![LineId::from("line:talk")], choice_line_ids(dead_end));
+    assert_eq!(2, dead_end.line_count);
+    assert_eq!(vec!["Secret"], report.unvisited_nodes);
+    assert_eq!(
+        vec![LineId::from("line:opened"), LineId::from("line:secret")],
+        report.unreached_lines
+    );
+}
+
+#[test]
+fn explores_variable_dependent_branches() {
+    let compilation = compile(GUARD);
+
+    let report = Explorer::new(&compilation)
+        .with_boolean_variables_explored()
+        .explore();
+
+    assert_eq!(5, report.playthrough_count);
+    assert_eq!(1, report.completed_count);
+    assert_eq!(4, report.issues.len());
+    assert_eq!(vec![LineId::from("line:secret")], report.unreached_lines);
+}
+
+#[test]
+fn keeps_initial_value_of_variables_without_values() {
+    let compilation = compile(GUARD);
+
+    let report = Explorer::new(&compilation)
+        .with_variable_values("$has_key", Vec::<bool>::new())
+        .explore();
+
+    assert_eq!(2, report.playthrough_count);
+    assert!(report
+        .issues
+        .iter()
+        .all(|issue| issue.playthrough.variables.is_empty()));
+}
+
+#[test]
+fn simulates_commands_with_stub() {
+    let compilation = compile(
+        "title: Start
+---
+<<declare $gold = 0>>
+<<give_gold 5>>
+<<if $gold > 3>>
+    Rich! #line:rich
+<<else>>
+    Poor. #line:poor
+<<endif>>
+<<dance>>
+===",
+    );
+
+    let report = Explorer::new(&compilation)
+        .with_command_stub(|command, variable_storage| match command.name.as_str() {
+            "give_gold" => {
+                let amount = f32::try_from(&command.parameters[0]).map_err(|e| e.to_string())?;
+                let gold = f32::try_from(variable_storage.get("$gold").unwrap()).unwrap();
+                variable_storage
+                    .set("$gold".to_owned(), (gold + amount).into())
+                    .map_err(|e| e.to_string())
+            }
+            _ => Err("Unknown command".to_owned()),
+        })
+        .explore();
+
+    assert_eq!(
+        vec![ExplorationIssueKind::CommandFailed {
+            command: "dance".to_owned(),
+            message: "Unknown command".to_owned()
+        }],
+        report
+            .issues
+            .iter()
+            .map(|issue| issue.kind.clone())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(vec![LineId::from("line:poor")], report.unreached_lines);
+}
+
+#[test]
+fn reports_panics() {
+    let compilation = compile(
+        "title: Start
+---
+<<{\"  \"}>>
+===",
+    );
+
+    let report = Explorer::new(&compilation).explore();
+
+    assert_eq!(1, report.issues.len());
+    assert!(matches!(
+        report.issues[0].kind,
+        ExplorationIssueKind::Panic { .. }
+    ));
+}
+
+#[test]
+fn stops_endless_playthroughs() {
+    let compilation = compile(
+        "title: Start
+---
+Again and again. #line:again
+<<jump Start>>
+===",
+    );
+
+    let report = Explorer::new(&compilation).with_max_steps(20).explore();
+
+    assert_eq!(
+        vec![ExplorationIssueKind::StepLimitExceeded { steps: 20 }],
+        report
+            .issues
+            .iter()
+            .map(|issue| issue.kind.clone())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn truncates_deep_playthroughs() {
+    let compilation = compile(
+        "title: Start
+---
+-> Left #line:left
+-> Right #line:right
+<<jump Start>>
+===",
+    );
+
+    let report = Explorer::new(&compilation).with_max_depth(3).explore();
+
+    assert!(!report.is_exhaustive);
+    assert_eq!(8, report.truncated_count);
+    assert_eq!(3, report.max_depth());
+    assert!(report.is_ok());
+}
+
+#[test]
+fn random_exploration_is_reproducible() {
+    let compilation = compile(GUARD);
+    let explore = |seed| {
+        Explorer::new(&compilation)
+            .with_boolean_variables_explored()
+            .with_strategy(ExplorationStrategy::Random {
+                seed,
+                playthroughs: 20,
+            })
+            .explore()
+    };
+
+    let report = explore(42);
+
+    assert!(!report.is_exhaustive);
+    assert_eq!(20, report.playthrough_count);
+    assert_eq!(report, explore(42));
+}
+
+fn choice_line_ids(playthrough: &Playthrough) -> Vec<LineId> {
+    playthrough
+        .choices
+        .iter()
+        .map(|choice| choice.line_id.clone())
+        .collect()
+}
@@ -0,0 +1,142 @@
+use yarnspinner::compiler::*;
+use yarnspinner::flow_graph::*;
+
+fn compile_test_file() -> Compilation {
+    let file = File {
+        file_name: "test.yarn".to_string(),
+        source: "title: Start
+---
+<<declare $gold = 0>>
+<<declare $destination = \"Start\">>
+Mae: Where to? #line:where
+-> To the shop #line:shop
+    <<jump Shop>>
+-> To the bank <<if $gold > 10>> #line:bank
+    <<jump Bank>>
+===
+title: Shop
+tags: store
+---
+Welcome! #line:welcome
+<<if $gold == 0>>
+    <<jump Nowhere>>
+<<endif>>
+<<jump {$destination}>>
+==="
+        .to_string(),
+    };
+    Compiler::new().add_file(file).compile().unwrap()
+}
+
+#[test]
+fn builds_graph_from_compilation() {
+    let compilation = compile_test_file();
+
+    let graph = FlowGraph::from_compilation(&compilation);
+
+    assert_eq!(
+        vec![
+            FlowNode {
+                name: "Shop".to_owned(),
+                tags: vec!["store".to_owned()],
+            },
+            FlowNode {
+                name: "Start".to_owned(),
+                tags: vec![],
+            },
+        ],
+        graph.nodes
+    );
+    assert_eq!(
+        vec![
+            FlowEdge {
+                source: "Shop".to_owned(),
+                target: FlowEdgeTarget::Node("Nowhere".to_owned()),
+                option: None,
+                conditions: vec!["$gold == 0".to_owned()],
+            },
+            FlowEdge {
+                source: "Shop".to_owned(),
+                target: FlowEdgeTarget::Dynamic {
+                    expression: "$destination".to_owned()
+                },
+                option: None,
+                conditions: vec!["!($gold == 0)".to_owned()],
+            },
+            FlowEdge {
+                source: "Start".to_owned(),
+                target: FlowEdgeTarget::Node("Shop".to_owned()),
+                option: Some(FlowOption {
+                    line_id: "line:shop".into(),
+                    text: Some("To the shop".to_owned()),
+                    condition: None,
+                }),
+                conditions: vec![],
+            },
+            FlowEdge {
+                source: "Start".to_owned(),
+                target: FlowEdgeTarget::Node("Bank".to_owned()),
+                option: Some(FlowOption {
+                    line_id: "line:bank".into(),
+                    text: Some("To the bank".to_owned()),
+                    condition: Some("$gold > 10".to_owned()),
+                }),
+                conditions: vec![],
+            },
+        ],
+        graph.edges
+    );
+}
+
+#[test]
+fn exports_dot() {
+    let compilation = compile_test_file();
+
+    let dot = FlowGraph::from_compilation(&compilation).to_dot();
+
+    assert!(dot.starts_with("digraph Dialogue {\n"));
+    assert!(dot.contains("    \"Bank\" [style=dashed];\n"));
+    assert!(dot.contains("    \"Shop\" -> \"Nowhere\" [label=\"if $gold == 0\"];\n"));
+    assert!(
+        dot.contains("    dynamic_1 [label=\"{$destination}\", shape=diamond, style=dashed];\n")
+    );
+    assert!(dot.contains("    \"Shop\" -> dynamic_1 [label=\"if !($gold == 0)\", style=dashed];\n"));
+    assert!(dot.contains("    \"Start\" -> \"Shop\" [label=\"To the shop\"];\n"));
+    assert!(dot.contains("    \"Start\" -> \"Bank\" [label=\"To the bank\\nif $gold > 10\"];\n"));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn exports_mermaid() {
+    let compilation = compile_test_file();
+
+    let mermaid = FlowGraph::from_compilation(&compilation).to_mermaid();
+
+    assert_eq!(
+        "flowchart TD
+    n0[\"Shop\"]
+    n1[\"Start\"]
+    n2[\"Bank\"]
+    style n2 stroke-dasharray: 5 5
+    n3[\"Nowhere\"]
+    style n3 stroke-dasharray: 5 5
+    n0 -->|\"if $gold == 0\"| n3
+    n0 -.->|\"if !($gold == 0)\"| dynamic1{\"{$destination}\"}
+    n1 -->|\"To the shop\"| n0
+    n1 -->|\"To the bank<br>if $gold > 10\"| n2
+",
+        mermaid
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn exports_json() {
+    let compilation = compile_test_file();
+    let graph = FlowGraph::from_compilation(&compilation);
+
+    let json = graph.to_json();
+
+    let deserialized: FlowGraph = serde_json::from_str(&json).unwrap();
+    assert_eq!(graph, deserialized);
+}
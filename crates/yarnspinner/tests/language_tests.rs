@@ -220,3 +220,42 @@ fn crashes_on_command_expression_evaluating_whitespace() {
         .with_compilation(result)
         .run_standard_testcase();
 }
+
+#[test]
+fn formats_inline_expressions_for_language() {
+    let compilation = Compiler::from_test_source(
+        "<<declare $gold = 1234.5>>\nYou have {$gold:N0} gold ({$gold}). #line:gold",
+    )
+    .compile()
+    .unwrap();
+    let mut text_provider = string_table_text_provider(&compilation);
+    text_provider.extend_translation(
+        "de-CH",
+        HashMap::from([("line:gold".into(), "Du hast {0:N2} Gold ({0}).".to_owned())]),
+    );
+    let mut dialogue = Dialogue::new(
+        Box::new(MemoryVariableStorage::new()),
+        Box::new(text_provider),
+    );
+    dialogue
+        .replace_program(compilation.program.unwrap())
+        .set_node("Start")
+        .unwrap();
+    let line_text = |dialogue: &mut Dialogue| {
+        dialogue
+            .continue_()
+            .unwrap()
+            .into_iter()
+            .find_map(|event| match event {
+                DialogueEvent::Line(line) => Some(line.text),
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    assert_eq!("You have 1,235 gold (1234.5).", line_text(&mut dialogue));
+
+    dialogue.set_language_code(Language::new("de-CH"));
+    dialogue.set_node("Start").unwrap();
+    assert_eq!("Du hast 1’234.50 Gold (1234.5).", line_text(&mut dialogue));
+}
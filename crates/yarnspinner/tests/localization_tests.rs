@@ -0,0 +1,483 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+use yarnspinner::localization::*;
+use yarnspinner::runtime::*;
+
+mod test_base;
+
+fn compile_test_file() -> Compilation {
+    let file = File {
+        file_name: "test.yarn".to_string(),
+        source: "title: Start
+---
+<<declare $name = \"Bob\">>
+Mae: Hello & welcome! #line:greeting
+Mae: Your name is {$name}. #line:name #greeting #important
+-> Yes #line:yes
+-> No #line:no
+==="
+        .to_string(),
+    };
+    Compiler::new().add_file(file).compile().unwrap()
+}
+
+fn translate(exported: &str, translations: &[(&str, &str)]) -> String {
+    translations
+        .iter()
+        .fold(exported.to_owned(), |document, (source, target)| {
+            let source = format!(r#"<source xml:space="preserve">{source}</source>"#);
+            let replacement = format!("{source}<target>{target}</target>");
+            document.replace(&source, &replacement)
+        })
+}
+
+#[test]
+fn exports_xliff_1_2_with_notes() {
+    let compilation = compile_test_file();
+
+    let xliff = export_xliff(
+        &compilation,
+        XliffVersion::V1_2,
+        &Language::new("en-US"),
+        Some(&Language::new("de-CH")),
+    );
+
+    assert!(xliff.contains(r#"<xliff version="1.2""#));
+    assert!(xliff.contains(r#"source-language="en-US" target-language="de-CH""#));
+    assert!(xliff.contains(r#"<trans-unit id="line:greeting" resname="line:greeting">"#));
+    assert!(xliff.contains("Mae: Hello &amp; welcome!"));
+    assert!(xliff.contains(r#"<context context-type="linenumber">5</context>"#));
+    assert!(xliff.contains(r#"<note from="node">Start</note>"#));
+    assert!(xliff.contains(r##"<note from="tag">#important</note>"##));
+    assert!(!xliff.contains("#line:"));
+    // Sorted by line number
+    assert!(xliff.find("line:greeting").unwrap() < xliff.find("line:no").unwrap());
+}
+
+#[test]
+fn exports_xliff_2_0_with_notes() {
+    let compilation = compile_test_file();
+
+    let xliff = export_xliff(
+        &compilation,
+        XliffVersion::V2_0,
+        &Language::new("en-US"),
+        None,
+    );
+
+    assert!(xliff.contains(r#"<xliff version="2.0""#));
+    assert!(xliff.contains(r#"srcLang="en-US">"#));
+    assert!(xliff.contains(r#"<unit id="line:name" name="line:name">"#));
+    assert!(xliff.contains(r#"<note category="location">test.yarn:5</note>"#));
+    assert!(xliff.contains(r##"<note category="tag">#greeting</note>"##));
+}
+
+#[test]
+fn round_trips_translations_through_xliff() {
+    let compilation = compile_test_file();
+    for version in [XliffVersion::V1_2, XliffVersion::V2_0] {
+        let exported = export_xliff(
+            &compilation,
+            version,
+            &Language::new("en-US"),
+            Some(&Language::new("de-CH")),
+        );
+        let translated = translate(
+            &exported,
+            &[
+                (
+                    "Mae: Hello &amp; welcome!",
+                    "Mae: Hallo &lt;3 &amp; willkommen!",
+                ),
+                ("Mae: Your name is {0}.", "Mae: Du heisst {0}."),
+                ("Yes", "Ja"),
+                ("No", ""),
+            ],
+        );
+
+        let import = import_xliff(&translated, &compilation).unwrap();
+
+        assert_eq!(Some("de-CH"), import.language.as_deref());
+        assert_eq!(
+            Some("Mae: Hallo <3 & willkommen!"),
+            import
+                .string_table
+                .get(&"line:greeting".into())
+                .map(String::as_str)
+        );
+        assert_eq!(
+            Some("Mae: Du heisst {0}."),
+            import
+                .string_table
+                .get(&"line:name".into())
+                .map(String::as_str)
+        );
+        assert_eq!(3, import.string_table.len());
+        assert_eq!(vec![LineId::from("line:no")], import.missing_line_ids);
+        assert!(import.extra_line_ids.is_empty());
+        assert!(!import.is_complete());
+    }
+}
+
+#[test]
+fn reports_extra_line_ids_in_xliff() {
+    let compilation = compile_test_file();
+    let xliff = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="en-US" trgLang="fr">
+  <file id="f1">
+    <unit id="line:yes"><segment><source>Yes</source><target>Oui</target></segment></unit>
+    <unit id="line:deleted"><segment><source>Gone</source><target>Parti</target></segment></unit>
+  </file>
+</xliff>"#;
+
+    let import = import_xliff(xliff, &compilation).unwrap();
+
+    assert_eq!(1, import.string_table.len());
+    assert_eq!(vec![LineId::from("line:deleted")], import.extra_line_ids);
+    assert_eq!(3, import.missing_line_ids.len());
+}
+
+#[test]
+fn rejects_invalid_xliff() {
+    let compilation = compile_test_file();
+
+    let result = import_xliff(r#"<xliff version="3.0"></xliff>"#, &compilation);
+    assert!(matches!(result, Err(XliffError::UnsupportedVersion(_))));
+
+    let result = import_xliff("<html></html>", &compilation);
+    assert!(matches!(result, Err(XliffError::NotXliff(Some(_)))));
+
+    let result = import_xliff(r#"<xliff version="1.2"><file>"#, &compilation);
+    assert!(matches!(result, Err(XliffError::Xml(_))));
+}
+
+#[test]
+fn exports_pot() {
+    let compilation = compile_test_file();
+
+    let pot = export_pot(&compilation);
+
+    assert!(pot.starts_with("msgid \"\"\nmsgstr \"\"\n"));
+    assert!(pot.contains(
+        "#. #greeting
+#. #important
+#: test.yarn:5
+msgctxt \"line:name\"
+msgid \"Mae: Your name is {0}.\"
+msgstr \"\"
+"
+    ));
+    assert!(pot.contains(
+        "#: test.yarn:6
+msgctxt \"line:yes\"
+msgid \"Yes\"
+msgstr \"\"
+"
+    ));
+}
+
+#[test]
+fn imports_po() {
+    let compilation = compile_test_file();
+    let po = export_pot(&compilation)
+        .replace(
+            "msgstr \"\"\n\"MIME-Version",
+            "msgstr \"\"\n\"Language: de\\n\"\n\"MIME-Version",
+        )
+        .replace(
+            "msgid \"Mae: Hello & welcome!\"\nmsgstr \"\"",
+            "msgid \"Mae: Hello & welcome!\"\nmsgstr \"Mae: Hallo \"\n\"& \\\"willkommen\\\"!\"",
+        )
+        .replace(
+            "msgid \"Yes\"\nmsgstr \"\"",
+            "msgid \"Yes\"\nmsgstr \"Ja\"",
+        )
+        .replace(
+            "msgctxt \"line:no\"\nmsgid \"No\"\nmsgstr \"\"",
+            "msgctxt \"line:no\"\nmsgid \"No\"\nmsgstr \"Nein\"\n\n#~ msgctxt \"line:old\"\n#~ msgid \"Old\"\n#~ msgstr \"Alt\"",
+        );
+
+    let import = import_po(&po, &compilation).unwrap();
+
+    assert_eq!(Some("de"), import.language.as_deref());
+    assert_eq!(
+        Some("Mae: Hallo & \"willkommen\"!"),
+        import
+            .string_table
+            .get(&"line:greeting".into())
+            .map(String::as_str)
+    );
+    assert_eq!(3, import.string_table.len());
+    assert_eq!(vec![LineId::from("line:name")], import.missing_line_ids);
+    assert!(import.extra_line_ids.is_empty());
+    assert!(import.outdated_line_ids.is_empty());
+}
+
+#[test]
+fn reports_outdated_po_translations() {
+    let compilation = compile_test_file();
+    let po = r#"
+#, fuzzy
+msgctxt "line:yes"
+msgid "Yes"
+msgstr "Ja"
+
+#, c-format
+msgctxt "line:no"
+msgid "Nope"
+msgstr "Nö"
+
+msgctxt "line:deleted"
+msgid "Gone"
+msgstr "Weg"
+"#;
+
+    let import = import_po(po, &compilation).unwrap();
+
+    assert_eq!(2, import.string_table.len());
+    assert_eq!(
+        vec![LineId::from("line:yes"), LineId::from("line:no")],
+        import.outdated_line_ids
+    );
+    assert_eq!(vec![LineId::from("line:deleted")], import.extra_line_ids);
+    assert!(!import.is_complete());
+}
+
+#[test]
+fn rejects_invalid_po() {
+    let compilation = compile_test_file();
+
+    let result = import_po("msgid \"Yes\"\nmsgstr \"Ja\"", &compilation);
+    assert_eq!(
+        Err(PoError::MissingContext {
+            line: 1,
+            msgid: "Yes".to_owned()
+        }),
+        result
+    );
+
+    let result = import_po(
+        "msgctxt \"line:yes\"\nmsgid \"Yes\nmsgstr \"Ja\"",
+        &compilation,
+    );
+    assert_eq!(Err(PoError::InvalidString { line: 2 }), result);
+
+    let result = import_po("msgctxt \"line:yes\"\nwhat is this", &compilation);
+    assert!(matches!(
+        result,
+        Err(PoError::UnexpectedLine { line: 2, .. })
+    ));
+}
+
+#[test]
+fn reports_outdated_xliff_translations() {
+    let compilation = compile_test_file();
+    let xliff = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file original="test.yarn" source-language="en" target-language="de" datatype="plaintext">
+    <body>
+      <trans-unit id="line:yes"><source>Yes</source><target state="needs-review-translation">Ja</target></trans-unit>
+      <trans-unit id="line:no"><source>Nope</source><target>Nö</target></trans-unit>
+      <trans-unit id="line:greeting"><source>Mae: Hello &amp; welcome!</source><target>Hallo</target></trans-unit>
+    </body>
+  </file>
+</xliff>"#;
+
+    let import = import_xliff(xliff, &compilation).unwrap();
+
+    assert_eq!(
+        vec![LineId::from("line:yes"), LineId::from("line:no")],
+        import.outdated_line_ids
+    );
+}
+
+#[test]
+fn reports_outdated_xliff_2_0_translations() {
+    let compilation = compile_test_file();
+    let xliff = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff version="2.0" xmlns="urn:oasis:names:tc:xliff:document:2.0" srcLang="en" trgLang="de">
+  <file id="f1">
+    <unit id="line:yes"><segment state="initial"><source>Yes</source><target>Ja</target></segment></unit>
+    <unit id="line:no"><segment state="final"><source>No</source><target>Nein</target></segment></unit>
+    <unit id="line:greeting"><segment state="translated"><source>Mae: Hello &amp; welcome!</source><target>Hallo</target></segment></unit>
+  </file>
+</xliff>"#;
+
+    let import = import_xliff(xliff, &compilation).unwrap();
+
+    assert_eq!(3, import.string_table.len());
+    assert_eq!(vec![LineId::from("line:yes")], import.outdated_line_ids);
+}
+
+#[test]
+fn accepts_consistent_translations() {
+    let compilation = compile_test_file();
+    let translation = StringTable::from([
+        (
+            "line:greeting".into(),
+            "Mae: Hallo & willkommen!".to_owned(),
+        ),
+        ("line:name".into(), "Mae: Du heisst {0}.".to_owned()),
+        ("line:yes".into(), "Ja".to_owned()),
+    ]);
+
+    let report = validate_translation(&compilation, &translation);
+
+    assert!(report.is_ok(), "{report}");
+}
+
+#[test]
+fn validates_placeholders() {
+    assert_eq!(
+        vec![
+            TranslationIssueKind::MissingPlaceholder { index: 0 },
+            TranslationIssueKind::UnexpectedPlaceholder { index: 2 },
+        ],
+        validate_translated_line("{0} has {1} apples", "{2} hat {1} Äpfel")
+    );
+    // Translators may pick a different format specifier
+    assert!(validate_translated_line("{0:N0} gold", "{0:N2} Gold").is_empty());
+}
+
+#[test]
+fn validates_markup() {
+    assert_eq!(
+        vec![TranslationIssueKind::MissingAttribute {
+            name: "wave".to_owned()
+        }],
+        validate_translated_line("Hello [wave]there[/wave]!", "Hallo [wave]du!")
+    );
+    assert_eq!(
+        vec![TranslationIssueKind::UnexpectedAttribute {
+            name: "b".to_owned()
+        }],
+        validate_translated_line("Hello there!", "Hallo [b]du[/b]!")
+    );
+    assert!(matches!(
+        validate_translated_line("Hello [b]there[/b]!", "Hallo du[/b]!")[..],
+        [TranslationIssueKind::InvalidMarkup { .. }]
+    ));
+}
+
+#[test]
+fn validates_replacement_markers() {
+    let base_text = r#"I have {0} [plural value={0} one="apple" other="apples"/]"#;
+
+    assert_eq!(
+        vec![TranslationIssueKind::MissingReplacementMarker {
+            name: "plural".to_owned()
+        }],
+        validate_translated_line(base_text, "Ich habe {0} Äpfel")
+    );
+    assert!(validate_translated_line(
+        base_text,
+        r#"Ich habe {0} [plural value={0} one="Apfel" other="Äpfel"/]"#
+    )
+    .is_empty());
+}
+
+#[test]
+fn validates_character_names() {
+    assert_eq!(
+        vec![TranslationIssueKind::CharacterNameMismatch {
+            expected: Some("Mae".to_owned()),
+            found: None,
+        }],
+        validate_translated_line("Mae: Hello!", "Hallo!")
+    );
+}
+
+#[test]
+fn reports_issues_with_location() {
+    let compilation = compile_test_file();
+    let translation = StringTable::from([("line:name".into(), "Mae: Du heisst.".to_owned())]);
+
+    let report = validate_translation(&compilation, &translation);
+
+    assert_eq!(
+        vec![TranslationIssue {
+            line_id: "line:name".into(),
+            file_name: "test.yarn".to_owned(),
+            line_number: 5,
+            kind: TranslationIssueKind::MissingPlaceholder { index: 0 },
+        }],
+        report.issues
+    );
+    assert_eq!(
+        "test.yarn:5 (line:name): Placeholder {0} is missing\n",
+        report.to_string()
+    );
+}
+
+#[test]
+fn pseudo_localizes_displayed_text() {
+    let pseudo_localization = PseudoLocalization::default();
+
+    assert_eq!(
+        "Mae: ⟦Ĥéļļö {0}!~~⟧",
+        pseudo_localization.localize_line("Mae: Hello {0}!")
+    );
+    assert_eq!(
+        "⟦Šö [b]ƀöļđ[/b] \\[ŧåĝ\\]~~~~⟧",
+        pseudo_localization.localize_line("So [b]bold[/b] \\[tag\\]")
+    );
+    assert_eq!(
+        r#"⟦Î ĥåṽé {0} [plural value={0} one="apple" other="apples"/]~~⟧"#,
+        pseudo_localization
+            .localize_line(r#"I have {0} [plural value={0} one="apple" other="apples"/]"#)
+    );
+    assert_eq!(
+        "⟦Ýöû ĥåṽé {0:N0} ĝöļđ~~~~⟧",
+        pseudo_localization.localize_line("You have {0:N0} gold")
+    );
+}
+
+#[test]
+fn pseudo_localization_can_be_configured() {
+    let pseudo_localization = PseudoLocalization::new()
+        .with_accents(false)
+        .with_expansion_percent(100)
+        .with_padding('*')
+        .without_brackets();
+
+    assert_eq!(
+        "Mae: Hello {0}!******",
+        pseudo_localization.localize_line("Mae: Hello {0}!")
+    );
+    assert_eq!(
+        "Hallo du[/b]!",
+        pseudo_localization.localize_line("Hallo du[/b]!")
+    );
+}
+
+#[test]
+fn pseudo_localization_passes_validation() {
+    let compilation = compile_test_file();
+
+    let string_table =
+        PseudoLocalization::default().localize_string_table(&compilation.string_table);
+
+    assert_eq!(compilation.string_table.len(), string_table.len());
+    assert_eq!("⟦Ýéš~⟧", string_table[&LineId::from("line:yes")]);
+    let report = validate_translation(&compilation, &string_table);
+    assert!(report.is_ok(), "{report}");
+}
+
+#[test]
+fn pseudo_localization_plugs_into_text_provider() {
+    let compilation = compile_test_file();
+    let mut text_provider = string_table_text_provider(&compilation);
+    text_provider.extend_translation(
+        PSEUDO_LOCALIZATION_LANGUAGE,
+        PseudoLocalization::default().localize_string_table(&compilation.string_table),
+    );
+
+    text_provider.set_language(Some(PSEUDO_LOCALIZATION_LANGUAGE.into()));
+
+    assert!(text_provider.are_lines_available());
+    assert_eq!(
+        Some("⟦Ńö~⟧".to_owned()),
+        text_provider.get_text(&LineId::from("line:no"))
+    );
+}
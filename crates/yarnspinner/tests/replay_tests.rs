@@ -0,0 +1,200 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::core::{LineId, YarnValue};
+use yarnspinner::replay::*;
+use yarnspinner::runtime::*;
+
+mod test_base;
+
+fn compile(farewell: &str) -> Compilation {
+    let file = File {
+        file_name: "test.yarn".to_string(),
+        source: format!(
+            "title: Start
+---
+<<declare $gold = 0>>
+Mae: You have {{$gold}} gold. #line:gold
+-> Buy #line:buy
+    <<set $gold to $gold - 1>>
+-> Leave #line:leave
+Mae: {farewell} #line:bye
+==="
+        ),
+    };
+    Compiler::new().add_file(file).compile().unwrap()
+}
+
+fn create_dialogue(compilation: &Compilation) -> Dialogue {
+    dialogue_from_compilation(compilation, MemoryVariableStorage::new())
+}
+
+fn record(compilation: &Compilation) -> SessionRecording {
+    record_with(create_dialogue(compilation))
+}
+
+/// Plays through the `Start` node, starting with 5 gold, setting it to 10 before the first line and always buying.
+fn record_with(mut dialogue: Dialogue) -> SessionRecording {
+    dialogue
+        .variable_storage_mut()
+        .set("$gold".to_owned(), 5.0.into())
+        .unwrap();
+    let mut recorder = SessionRecorder::start(&mut dialogue, "Start")
+        .unwrap()
+        .with_seed(42);
+    recorder.set_variable(&mut dialogue, "$gold", 10.0).unwrap();
+    loop {
+        let events = recorder.continue_(&mut dialogue).unwrap();
+        if events.contains(&DialogueEvent::DialogueComplete) {
+            return recorder.into_recording();
+        }
+        if events
+            .iter()
+            .any(|event| matches!(event, DialogueEvent::Options(_)))
+        {
+            recorder
+                .set_selected_option(&mut dialogue, OptionId(0))
+                .unwrap();
+        }
+    }
+}
+
+#[test]
+fn records_inputs_and_events_in_order() {
+    let recording = record(&compile("Bye!"));
+
+    assert_eq!(SESSION_RECORDING_FORMAT_VERSION, recording.format_version);
+    assert_eq!("Start", recording.start_node);
+    assert_eq!(Some(42), recording.seed);
+    assert_eq!(
+        Some(&YarnValue::from(5.0)),
+        recording.initial_variables.get("$gold")
+    );
+    assert!(matches!(
+        &recording.entries[0],
+        SessionEntry::VariableSet { name, value, .. } if name == "$gold" && *value == YarnValue::from(10.0)
+    ));
+    let selected_options: Vec<_> = recording
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            SessionEntry::OptionSelected { option_id, .. } => Some(*option_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vec![OptionId(0)], selected_options);
+    let lines: Vec<_> = recording
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            SessionEntry::Continued { events, .. } => Some(events),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|event| match event {
+            DialogueEvent::Line(line) => Some(line.text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vec!["Mae: You have 10 gold.", "Mae: Bye!"], lines);
+    assert!(recording
+        .entries
+        .windows(2)
+        .all(|entries| entries[0].elapsed() <= entries[1].elapsed()));
+}
+
+#[test]
+fn replays_session_without_divergence() {
+    let compilation = compile("Bye!");
+    let recording = record(&compilation);
+
+    let report = replay(&recording, &mut create_dialogue(&compilation)).unwrap();
+
+    assert_eq!(None, report.divergence);
+    assert_eq!(recording.entries.len(), report.replayed_entries);
+}
+
+#[test]
+fn replays_session_with_initially_seen_lines() {
+    let compilation = compile("Bye!");
+    let mut dialogue = create_dialogue(&compilation);
+    dialogue.seen_lines_mut().insert(LineId::from("line:gold"));
+    let recording = record_with(dialogue);
+
+    let report = replay(&recording, &mut create_dialogue(&compilation)).unwrap();
+
+    assert_eq!(
+        vec![LineId::from("line:gold")],
+        recording.initial_seen_lines
+    );
+    assert!(recording.entries.iter().any(|entry| matches!(
+        entry,
+        SessionEntry::Continued { events, .. }
+            if events.iter().any(|event| matches!(event, DialogueEvent::Line(line) if line.is_seen))
+    )));
+    assert_eq!(None, report.divergence);
+    assert_eq!(recording.entries.len(), report.replayed_entries);
+}
+
+#[test]
+fn reports_divergence_after_script_changed() {
+    let recording = record(&compile("Bye!"));
+    let changed_compilation = compile("See you!");
+
+    let report = replay(&recording, &mut create_dialogue(&changed_compilation)).unwrap();
+
+    let Some(Divergence::Event {
+        entry_index,
+        expected: Some(DialogueEvent::Line(expected)),
+        actual: Some(DialogueEvent::Line(actual)),
+        ..
+    }) = report.divergence
+    else {
+        panic!("Expected a diverging line, got {:?}", report.divergence);
+    };
+    assert_eq!(entry_index, report.replayed_entries);
+    assert_eq!("Mae: Bye!", expected.text);
+    assert_eq!("Mae: See you!", actual.text);
+}
+
+#[test]
+fn reports_missing_start_node() {
+    let compilation = compile("Bye!");
+    let mut recording = record(&compilation);
+    recording.start_node = "Nowhere".to_owned();
+
+    let report = replay(&recording, &mut create_dialogue(&compilation)).unwrap();
+
+    assert!(matches!(
+        report.divergence,
+        Some(Divergence::StartNode { .. })
+    ));
+    assert_eq!(0, report.replayed_entries);
+}
+
+#[test]
+fn rejects_unsupported_format_versions() {
+    let compilation = compile("Bye!");
+    let mut recording = record(&compilation);
+    recording.format_version = SESSION_RECORDING_FORMAT_VERSION + 1;
+
+    let result = replay(&recording, &mut create_dialogue(&compilation));
+
+    assert!(matches!(
+        result,
+        Err(ReplayError::UnsupportedFormatVersion { .. })
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serializes_recording_as_json() {
+    let compilation = compile("Bye!");
+    let recording = record(&compilation);
+
+    let json = recording.to_json().unwrap();
+    let deserialized = SessionRecording::from_json(&json).unwrap();
+
+    assert_eq!(recording, deserialized);
+    let report = replay(&deserialized, &mut create_dialogue(&compilation)).unwrap();
+    assert_eq!(None, report.divergence);
+}
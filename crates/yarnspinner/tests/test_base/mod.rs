@@ -26,8 +26,6 @@ use yarnspinner::runtime::*;
 mod extensions;
 mod logger;
 mod paths;
-mod step;
-mod test_plan;
 mod text_provider;
 use logger::*;
 pub use text_provider::SharedTextProvider;
@@ -35,7 +33,8 @@ use yarnspinner::log::{self, LevelFilter, SetLoggerError};
 
 pub mod prelude {
     #[allow(unused_imports)] // False positive
-    pub use crate::test_base::{extensions::*, paths::*, step::*, test_plan::*, *};
+    pub use crate::test_base::{extensions::*, paths::*, *};
+    pub use yarnspinner_testing::prelude::*;
 }
 
 pub fn init_logger(runtime_errors_cause_failure: Arc<AtomicBool>) -> Result<(), SetLoggerError> {
@@ -43,6 +42,33 @@ pub fn init_logger(runtime_errors_cause_failure: Arc<AtomicBool>) -> Result<(),
     log::set_boxed_logger(Box::new(logger)).map(|()| log::set_max_level(LevelFilter::Info))
 }
 
+/// Creates a text provider that knows the lines of the compilation in the base language.
+pub fn string_table_text_provider(compilation: &Compilation) -> StringTableTextProvider {
+    let mut text_provider = StringTableTextProvider::new();
+    text_provider.extend_base_language(
+        compilation
+            .string_table
+            .iter()
+            .map(|(line_id, string_info)| (line_id.clone(), string_info.text.clone()))
+            .collect(),
+    );
+    text_provider
+}
+
+/// Creates a dialogue with the program and base language lines of the compilation that keeps its variables in the given storage.
+/// Unlike [`TestBase`], it has no test functions and does not fail on runtime errors, and no node is set yet.
+pub fn dialogue_from_compilation(
+    compilation: &Compilation,
+    variable_storage: impl VariableStorage + 'static,
+) -> Dialogue {
+    let mut dialogue = Dialogue::new(
+        Box::new(variable_storage),
+        Box::new(string_table_text_provider(compilation)),
+    );
+    dialogue.add_program(compilation.program.clone().unwrap());
+    dialogue
+}
+
 #[derive(Debug)]
 pub struct TestBase {
     pub dialogue: Dialogue,
@@ -92,7 +118,8 @@ impl TestBase {
     /// Sets the current test plan to one loaded from a given path.
     #[must_use]
     pub fn read_test_plan(self, path: impl AsRef<Path>) -> Self {
-        self.with_test_plan(TestPlan::read(path))
+        let test_plan = TestPlan::read(path).unwrap_or_else(|e| panic!("{e}"));
+        self.with_test_plan(test_plan)
     }
 
     #[must_use]
@@ -235,6 +262,7 @@ impl TestBase {
                     DialogueEvent::NodeComplete(_) => {}
                     DialogueEvent::NodeStart(_) => {}
                     DialogueEvent::LineHints(_) => {}
+                    DialogueEvent::VariableChanged { .. } => {}
                     DialogueEvent::DialogueComplete => {
                         let Some(test_plan) = self.test_plan.as_mut() else {
                             continue;
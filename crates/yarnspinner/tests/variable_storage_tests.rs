@@ -0,0 +1,344 @@
+use test_base::prelude::*;
+use yarnspinner::compiler::*;
+use yarnspinner::core::*;
+use yarnspinner::runtime::*;
+use yarnspinner::variable_storage::*;
+
+mod test_base;
+
+fn compile_test_file() -> Compilation {
+    let file = File {
+        file_name: "test.yarn".to_string(),
+        source: "title: Start
+---
+/// How much money the player has
+<<declare $gold = 0>>
+<<declare $name = \"Mae\">>
+<<set $gold to $gold + 10>>
+<<set $met_mae to true>>
+Hello, {$name}!
+==="
+        .to_string(),
+    };
+    Compiler::new().add_file(file).compile().unwrap()
+}
+
+#[test]
+fn accepts_values_of_declared_type() {
+    let mut variable_storage = TypedVariableStorage::from_compilation(&compile_test_file());
+
+    variable_storage.set("$gold".to_owned(), 5.into()).unwrap();
+    variable_storage
+        .set("$name".to_owned(), "Ferris".into())
+        .unwrap();
+    variable_storage
+        .set("$met_mae".to_owned(), false.into())
+        .unwrap();
+
+    assert_eq!(YarnValue::from(5), variable_storage.get("$gold").unwrap());
+    assert_eq!(
+        YarnValue::from("Ferris"),
+        variable_storage.get("$name").unwrap()
+    );
+}
+
+#[test]
+fn rejects_values_of_other_types() {
+    let mut variable_storage = TypedVariableStorage::from_compilation(&compile_test_file());
+
+    let error = variable_storage
+        .set("$gold".to_owned(), "lots".into())
+        .unwrap_err();
+
+    let VariableStorageError::TypeMismatch {
+        name,
+        expected,
+        actual,
+    } = &error
+    else {
+        panic!("Expected a type mismatch, got {error:?}");
+    };
+    assert_eq!("$gold", name);
+    assert_eq!(&Type::Number, expected);
+    assert_eq!(&Type::String, actual);
+    assert_eq!(
+        "Cannot assign a value of type String to $gold, which was declared as Number",
+        error.to_string()
+    );
+    assert!(!variable_storage.contains("$gold"));
+
+    // Nothing is stored if a single value is invalid
+    let result = variable_storage.extend(
+        [
+            ("$gold".to_owned(), 5.into()),
+            ("$name".to_owned(), 5.into()),
+        ]
+        .into(),
+    );
+    assert!(matches!(
+        result,
+        Err(VariableStorageError::TypeMismatch { .. })
+    ));
+    assert!(variable_storage.variables().is_empty());
+}
+
+#[test]
+fn rejects_undeclared_variables_unless_allowed() {
+    let compilation = compile_test_file();
+    let mut variable_storage = TypedVariableStorage::from_compilation(&compilation);
+    let mut lenient_variable_storage = TypedVariableStorage::from_compilation(&compilation)
+        .with_undeclared_variables_allowed(true);
+
+    let result = variable_storage.set("$unknown".to_owned(), 1.into());
+    let lenient_result = lenient_variable_storage.set("$unknown".to_owned(), 1.into());
+    let invalid_name_result = variable_storage.set("gold".to_owned(), 1.into());
+
+    assert!(matches!(
+        result,
+        Err(VariableStorageError::UndeclaredVariable { .. })
+    ));
+    assert!(lenient_result.is_ok());
+    assert!(matches!(
+        invalid_name_result,
+        Err(VariableStorageError::InvalidVariableName { .. })
+    ));
+}
+
+#[test]
+fn exposes_declarations() {
+    let variable_storage = TypedVariableStorage::from_compilation(&compile_test_file());
+
+    let names: Vec<_> = variable_storage
+        .declarations()
+        .into_iter()
+        .map(|declaration| declaration.name.as_str())
+        .collect();
+
+    assert_eq!(vec!["$gold", "$met_mae", "$name"], names);
+    assert_eq!(
+        Some(&YarnValue::from(0)),
+        variable_storage.default_value("$gold")
+    );
+    assert_eq!(
+        Some(&YarnValue::from("Mae")),
+        variable_storage.default_value("$name")
+    );
+    assert_eq!(
+        Some("How much money the player has"),
+        variable_storage.description("$gold")
+    );
+    assert_eq!(None, variable_storage.description("$name"));
+    assert!(variable_storage.declaration("$unknown").is_none());
+}
+
+#[test]
+fn runs_dialogue() {
+    let compilation = compile_test_file();
+    let variable_storage = TypedVariableStorage::from_compilation(&compilation);
+    let mut dialogue = dialogue_from_compilation(&compilation, variable_storage);
+    dialogue.set_node("Start").unwrap();
+
+    let events = dialogue.continue_().unwrap();
+
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, DialogueEvent::Line(line) if line.text == "Hello, Mae!")));
+    assert_eq!(
+        YarnValue::from(10),
+        dialogue.variable_storage().get("$gold").unwrap()
+    );
+    assert_eq!(
+        YarnValue::from(true),
+        dialogue.variable_storage().get("$met_mae").unwrap()
+    );
+}
+
+#[cfg(feature = "serde")]
+fn empty_test_directory(name: &str) -> std::path::PathBuf {
+    let directory = std::env::temp_dir()
+        .join("yarnspinner_variable_storage_tests")
+        .join(name);
+    let _ = std::fs::remove_dir_all(&directory);
+    directory
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn file_storage_persists_variables() {
+    let path = empty_test_directory("file").join("variables.json");
+    let mut variable_storage = FileVariableStorage::open(&path).unwrap();
+    let mut shallow_clone = variable_storage.clone_shallow();
+
+    variable_storage.set("$gold".to_owned(), 5.into()).unwrap();
+    shallow_clone
+        .as_mut()
+        .extend(
+            [
+                ("$name".to_owned(), "Mae".into()),
+                ("$Yarn.Internal.Visiting.Start".to_owned(), 1.into()),
+            ]
+            .into(),
+        )
+        .unwrap();
+
+    assert_eq!(
+        YarnValue::from("Mae"),
+        variable_storage.get("$name").unwrap()
+    );
+    assert_eq!(
+        r#"{
+  "variables": {
+    "$gold": {
+      "Number": 5.0
+    },
+    "$name": {
+      "String": "Mae"
+    }
+  },
+  "internal": {
+    "$Yarn.Internal.Visiting.Start": {
+      "Number": 1.0
+    }
+  }
+}"#,
+        std::fs::read_to_string(&path).unwrap()
+    );
+    assert_eq!(
+        variable_storage.variables(),
+        FileVariableStorage::open(&path).unwrap().variables()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn storages_can_keep_internal_variables_in_memory() {
+    let directory = empty_test_directory("internal");
+    let options = PersistenceOptions::new().with_internal_variables(InternalVariables::InMemory);
+    let values: std::collections::HashMap<_, _> = [
+        ("$gold".to_owned(), 5.into()),
+        ("$Yarn.Internal.Visiting.Start".to_owned(), 1.into()),
+    ]
+    .into();
+    let mut file_storage =
+        FileVariableStorage::open_with_options(directory.join("variables.json"), options).unwrap();
+    let mut journal_storage =
+        JournalVariableStorage::open_with_options(directory.join("variables.jsonl"), options)
+            .unwrap();
+
+    file_storage.extend(values.clone()).unwrap();
+    journal_storage.extend(values.clone()).unwrap();
+
+    for variable_storage in [
+        Box::new(
+            FileVariableStorage::open_with_options(directory.join("variables.json"), options)
+                .unwrap(),
+        ) as Box<dyn VariableStorage>,
+        Box::new(
+            JournalVariableStorage::open_with_options(directory.join("variables.jsonl"), options)
+                .unwrap(),
+        ),
+    ] {
+        assert_eq!(YarnValue::from(5), variable_storage.get("$gold").unwrap());
+        assert!(!variable_storage.contains("$Yarn.Internal.Visiting.Start"));
+    }
+    assert_eq!(values, file_storage.variables());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn journal_storage_replays_and_compacts_changes() {
+    let path = empty_test_directory("journal").join("variables.jsonl");
+    let mut variable_storage = JournalVariableStorage::open(&path)
+        .unwrap()
+        .with_compaction_threshold(3);
+
+    variable_storage.set("$gold".to_owned(), 5.into()).unwrap();
+    variable_storage.set("$gold".to_owned(), 5.into()).unwrap();
+    variable_storage.set("$gold".to_owned(), 10.into()).unwrap();
+    variable_storage
+        .extend([("$name".to_owned(), "Mae".into())].into())
+        .unwrap();
+    let journal_before_compaction = std::fs::read_to_string(&path).unwrap();
+    variable_storage
+        .set("$met".to_owned(), true.into())
+        .unwrap();
+
+    // Setting a variable to the value it already has is not written
+    assert_eq!(
+        r#"{"Set":{"name":"$gold","value":{"Number":5.0}}}
+{"Set":{"name":"$gold","value":{"Number":10.0}}}
+{"Extend":{"values":{"$name":{"String":"Mae"}}}}
+"#,
+        journal_before_compaction
+    );
+    assert_eq!(
+        r#"{"Extend":{"values":{"$gold":{"Number":10.0},"$met":{"Boolean":true},"$name":{"String":"Mae"}}}}
+"#,
+        std::fs::read_to_string(&path).unwrap()
+    );
+    assert_eq!(
+        variable_storage.variables(),
+        JournalVariableStorage::open(&path).unwrap().variables()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn journal_storage_ignores_cut_off_last_line() {
+    let directory = empty_test_directory("cut_off");
+    let path = directory.join("variables.jsonl");
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+        &path,
+        r#"{"Set":{"name":"$gold","value":{"Number":5.0}}}
+{"Set":{"name":"$gold","val"#,
+    )
+    .unwrap();
+
+    let mut variable_storage = JournalVariableStorage::open(&path).unwrap();
+    variable_storage
+        .set("$met".to_owned(), true.into())
+        .unwrap();
+    let reopened_variable_storage = JournalVariableStorage::open(&path).unwrap();
+
+    assert_eq!(
+        YarnValue::from(5),
+        reopened_variable_storage.get("$gold").unwrap()
+    );
+    assert_eq!(
+        YarnValue::from(true),
+        reopened_variable_storage.get("$met").unwrap()
+    );
+
+    std::fs::write(&path, "{\"Set\":\nnot json\n").unwrap();
+    let error = JournalVariableStorage::open(&path).unwrap_err();
+    assert!(matches!(
+        error,
+        PersistenceError::Parse { line: Some(1), .. }
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn dialogue_keeps_loaded_variables() {
+    let compilation = compile_test_file();
+    let path = empty_test_directory("dialogue").join("variables.json");
+    let mut variable_storage = FileVariableStorage::open(&path).unwrap();
+    variable_storage
+        .set("$name".to_owned(), "Ferris".into())
+        .unwrap();
+
+    let mut dialogue =
+        dialogue_from_compilation(&compilation, FileVariableStorage::open(&path).unwrap());
+    dialogue.set_node("Start").unwrap();
+    let events = dialogue.continue_().unwrap();
+
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, DialogueEvent::Line(line) if line.text == "Hello, Ferris!")));
+    let reopened_variable_storage = FileVariableStorage::open(&path).unwrap();
+    assert_eq!(
+        YarnValue::from(10),
+        reopened_variable_storage.get("$gold").unwrap()
+    );
+}